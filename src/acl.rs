@@ -29,33 +29,62 @@
 //! ```
 
 use std::{
+    collections::{BTreeMap, BTreeSet},
     ffi::c_void,
     fmt::{Debug, Formatter},
+    iter::FusedIterator,
     marker::PhantomData,
     mem::size_of,
+    ops::Deref,
+    ptr::null,
 };
 
-use windows_sys::Win32::{
-    Foundation::{ERROR_OUTOFMEMORY, FALSE},
-    Security::{
-        ACCESS_ALLOWED_ACE, ACE_HEADER, ACL, ACL_REVISION, ACL_SIZE_INFORMATION, AclSizeInformation,
-        AddAccessAllowedAce, AddAccessDeniedAce, DeleteAce, GetAce, GetAclInformation, GetLengthSid, InitializeAcl,
-        IsValidAcl, PSID,
-    },
-    System::{
-        Memory::{LMEM_FIXED, LocalAlloc},
-        SystemServices::{ACCESS_ALLOWED_ACE_TYPE, ACCESS_DENIED_ACE_TYPE, SYSTEM_AUDIT_ACE_TYPE},
+use windows_sys::{
+    Win32::{
+        Foundation::{ERROR_INVALID_ACL, ERROR_INVALID_PARAMETER, ERROR_OUTOFMEMORY, FALSE},
+        Security::{
+            ACCESS_ALLOWED_ACE, ACE_HEADER, ACE_INHERITED_OBJECT_TYPE_PRESENT, ACE_OBJECT_TYPE_PRESENT, ACL,
+            ACL_REVISION, ACL_REVISION_DS, ACL_SIZE_INFORMATION, AclSizeInformation, AddAccessAllowedAce,
+            AddAccessAllowedAceEx, AddAccessAllowedObjectAce, AddAccessDeniedAce, AddAccessDeniedAceEx,
+            AddAccessDeniedObjectAce, AddAce, AddAuditAccessAceEx, AddMandatoryAce, AddScopedPolicyIDAce,
+            CONTAINER_INHERIT_ACE, DeleteAce, FAILED_ACCESS_ACE_FLAG, GetAce, GetAclInformation, INHERIT_ONLY_ACE,
+            INHERITED_ACE, InitializeAcl, IsValidAcl, NO_PROPAGATE_INHERIT_ACE, OBJECT_INHERIT_ACE,
+            SUCCESSFUL_ACCESS_ACE_FLAG,
+        },
+        System::{
+            Memory::{LMEM_FIXED, LocalAlloc},
+            SystemServices::{
+                ACCESS_ALLOWED_ACE_TYPE, ACCESS_ALLOWED_CALLBACK_ACE_TYPE, ACCESS_ALLOWED_CALLBACK_OBJECT_ACE_TYPE,
+                ACCESS_ALLOWED_COMPOUND_ACE_TYPE, ACCESS_ALLOWED_OBJECT_ACE_TYPE, ACCESS_DENIED_ACE_TYPE,
+                ACCESS_DENIED_CALLBACK_ACE_TYPE, ACCESS_DENIED_CALLBACK_OBJECT_ACE_TYPE, ACCESS_DENIED_OBJECT_ACE_TYPE,
+                ACL_REVISION3, MAXDWORD, SYSTEM_ACCESS_FILTER_ACE_TYPE, SYSTEM_ALARM_ACE_TYPE,
+                SYSTEM_ALARM_CALLBACK_ACE_TYPE, SYSTEM_ALARM_CALLBACK_OBJECT_ACE_TYPE, SYSTEM_ALARM_OBJECT_ACE_TYPE,
+                SYSTEM_AUDIT_ACE_TYPE, SYSTEM_AUDIT_CALLBACK_ACE_TYPE, SYSTEM_AUDIT_CALLBACK_OBJECT_ACE_TYPE,
+                SYSTEM_AUDIT_OBJECT_ACE_TYPE, SYSTEM_MANDATORY_LABEL_ACE_TYPE, SYSTEM_PROCESS_TRUST_LABEL_ACE_TYPE,
+                SYSTEM_RESOURCE_ATTRIBUTE_ACE_TYPE, SYSTEM_SCOPED_POLICY_ID_ACE_TYPE,
+            },
+        },
     },
+    core::GUID,
 };
 
 use crate::{
     assert_free,
+    audit::AuditOn,
+    claims::ResourceAttribute,
+    condition::Condition,
     error::WinError,
-    mask::Mask,
-    sid::{AsSidRef, Sid},
+    guid::Guid,
+    integrity::{IntegrityLevel, MandatoryLabel, MandatoryPolicy},
+    mask::{IntoAccessMask, bit_ops},
+    sd::{DiffEntry, MaskChange, SecurityDescriptor, SecurityDescriptorBuilder},
+    sid::{AsSidRef, DebugResolved, Sid, debug_account},
     winapi_bool_call,
 };
 
+/// The signature starting the application data of conditional ACEs.
+const CONDITIONAL_ACE_SIGNATURE: &[u8] = b"artx";
+
 /// An Access Control List (ACL) containing zero or more Access Control Entries (ACEs).
 ///
 /// ACLs define which security principals have which access rights. There are two types:
@@ -69,10 +98,37 @@ pub struct Acl {
     owned: bool,
 }
 
+/// A borrowed reference to an ACL inside a larger structure, such as the DACL or SACL of a
+/// security descriptor.
+///
+/// The reference dereferences to [`Acl`] for reading; the borrow of the owner is enforced at
+/// compile time, so the reference cannot outlive the security descriptor. Use
+/// [`Acl::try_clone`] for an owned, modifiable copy.
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::sd::SecurityDescriptor;
+///
+/// let sd = SecurityDescriptor::from_path("C:\\data")?;
+/// if let Some(dacl) = sd.dacl() {
+///     for ace in &dacl {
+///         println!("{:?}", ace);
+///     }
+///     let mut copy = dacl.try_clone()?;
+///     copy.remove_ace(0)?;
+/// }
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+pub struct AclRef<'a> {
+    acl: Acl,
+    _phantom: PhantomData<&'a ACL>,
+}
+
 /// An Access Control Entry (ACE) within an ACL.
 ///
 /// An ACE specifies access rights for a specific security principal (identified by a SID).
-/// ACEs can be of type `AccessAllowed`, `AccessDenied`, or `SystemAudit`.
+/// Its type is given by [`Ace::ace_type`] and its content, decoded by type, by [`Ace::payload`].
 ///
 /// This is a borrowed reference to an ACE within an ACL and should not outlive the ACL.
 pub struct Ace<'a> {
@@ -81,15 +137,82 @@ pub struct Ace<'a> {
 }
 
 /// An iterator over the ACEs in an ACL.
+///
+/// The iterator knows its exact length and can be iterated from both ends. It is created by
+/// [`Acl::iter`], which reports a failure to read the ACL, or by iterating over `&Acl`, which
+/// yields no entries in that case. Its length is the ACE count of the ACL header; an ACE that
+/// cannot be read ends the iteration early, use [`Acl::try_iter`] to have that reported.
 #[derive(Debug)]
 pub struct AclIter<'a> {
     acl: &'a Acl,
+    front: u32,
+    back: u32,
+}
+
+/// A fallible iterator over the ACEs in an ACL.
+///
+/// Unlike [`AclIter`], which stops silently when the ACL cannot be read, this iterator yields
+/// the error. Iteration ends after the first error.
+#[derive(Debug)]
+pub struct AclTryIter<'a> {
+    acl: &'a Acl,
+    index: u32,
+    count: u32,
+    error: Option<WinError>,
+}
+
+/// An owning iterator over the ACEs in an ACL, yielding [`AceEntry`] values.
+///
+/// Created by [`Acl::into_entries`]. ACEs that cannot be represented as an entry (e.g., object
+/// or callback ACEs) yield an error instead of being skipped. Iteration ends after an ACE that
+/// cannot be read.
+#[derive(Debug)]
+pub struct AclIntoEntries {
+    acl: Acl,
     index: u32,
     count: u32,
 }
 
+/// A problem found in an ACL by [`Acl::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AclIssue {
+    /// The ACL revision is neither `ACL_REVISION` nor `ACL_REVISION_DS` (or the intermediate
+    /// revision 3).
+    UnknownRevision(u8),
+    /// The ACE at `index` needs a higher ACL revision than the ACL has, e.g., an object ACE in
+    /// an ACL of revision `ACL_REVISION`.
+    RevisionTooLow {
+        /// The position of the ACE.
+        index: u32,
+        /// The type of the ACE.
+        ace_type: AceType,
+        /// The lowest revision allowing the ACE type.
+        required: u8,
+    },
+    /// The ACE at `index` does not fit in the ACL or is too short for its SID. The following
+    /// ACEs cannot be located, so they are not checked.
+    Truncated {
+        /// The position of the ACE.
+        index: u32,
+    },
+    /// The ACE at `index` is out of canonical order (see [`Acl::is_canonical`]).
+    NotCanonical {
+        /// The position of the ACE.
+        index: u32,
+    },
+    /// The ACE at `index` has `INHERIT_ONLY` or `NO_PROPAGATE_INHERIT` set without
+    /// `OBJECT_INHERIT` or `CONTAINER_INHERIT`, so it applies neither to the object nor to any
+    /// child.
+    InconsistentInheritance {
+        /// The position of the ACE.
+        index: u32,
+        /// The header flags of the ACE.
+        flags: AceFlags,
+    },
+}
+
 /// The type of an Access Control Entry (ACE).
-#[derive(Debug, Copy, Clone, PartialEq, PartialOrd, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum AceType {
     /// An ACE that grants access rights to a security principal.
     AccessAllowed,
@@ -97,10 +220,534 @@ pub enum AceType {
     AccessDenied,
     /// An ACE used for system auditing, generating audit logs when access is attempted.
     SystemAudit,
+    /// An access-allowed ACE scoped to a property, property set, extended right or child object
+    /// class of a directory service object (identified by GUID).
+    AccessAllowedObject,
+    /// An access-denied ACE scoped to a property, property set, extended right or child object
+    /// class of a directory service object (identified by GUID).
+    AccessDeniedObject,
+    /// An audit ACE scoped to a property, property set, extended right or child object class
+    /// of a directory service object (identified by GUID).
+    SystemAuditObject,
+    /// An access-allowed ACE carrying application data, usually a condition (conditional ACE).
+    AccessAllowedCallback,
+    /// An access-denied ACE carrying application data, usually a condition (conditional ACE).
+    AccessDeniedCallback,
+    /// An audit ACE carrying application data, usually a condition (conditional ACE).
+    SystemAuditCallback,
+    /// An alarm ACE (reserved by the system, not enforced).
+    SystemAlarm,
+    /// An alarm object ACE (reserved by the system, not enforced).
+    SystemAlarmObject,
+    /// An alarm callback ACE (reserved by the system, not enforced).
+    SystemAlarmCallback,
+    /// An access-allowed object ACE carrying application data (conditional object ACE).
+    AccessAllowedCallbackObject,
+    /// An access-denied object ACE carrying application data (conditional object ACE).
+    AccessDeniedCallbackObject,
+    /// An audit object ACE carrying application data (conditional object ACE).
+    SystemAuditCallbackObject,
+    /// An alarm callback object ACE (reserved by the system, not enforced).
+    SystemAlarmCallbackObject,
+    /// An access-allowed ACE for a server impersonating a client (compound ACE).
+    AccessAllowedCompound,
+    /// The mandatory integrity label of the object.
+    SystemMandatoryLabel,
+    /// A resource attribute (claim) of the object, see [`claims`](crate::claims).
+    SystemResourceAttribute,
+    /// The central access policy bound to the object, see [`claims`](crate::claims).
+    SystemScopedPolicyId,
+    /// The process trust label of a protected process object.
+    SystemProcessTrustLabel,
+    /// An access filter restricting the access granted by the DACL by a condition.
+    SystemAccessFilter,
     /// An unknown ACE type with the raw byte value.
     Unknown(u8),
 }
 
+impl AceType {
+    /// Returns `true` for the ACE types granting access.
+    pub fn is_allow(self) -> bool {
+        matches!(
+            self,
+            AceType::AccessAllowed
+                | AceType::AccessAllowedObject
+                | AceType::AccessAllowedCallback
+                | AceType::AccessAllowedCallbackObject
+                | AceType::AccessAllowedCompound
+        )
+    }
+
+    /// Returns `true` for the ACE types denying access.
+    pub fn is_deny(self) -> bool {
+        matches!(
+            self,
+            AceType::AccessDenied
+                | AceType::AccessDeniedObject
+                | AceType::AccessDeniedCallback
+                | AceType::AccessDeniedCallbackObject
+        )
+    }
+
+    /// Returns `true` for the audit ACE types.
+    pub fn is_audit(self) -> bool {
+        matches!(
+            self,
+            AceType::SystemAudit
+                | AceType::SystemAuditObject
+                | AceType::SystemAuditCallback
+                | AceType::SystemAuditCallbackObject
+        )
+    }
+
+    /// Returns `true` for the alarm ACE types.
+    pub fn is_alarm(self) -> bool {
+        matches!(
+            self,
+            AceType::SystemAlarm
+                | AceType::SystemAlarmObject
+                | AceType::SystemAlarmCallback
+                | AceType::SystemAlarmCallbackObject
+        )
+    }
+
+    /// Returns `true` for the object ACE types, which may carry object type GUIDs.
+    pub fn is_object(self) -> bool {
+        matches!(
+            self,
+            AceType::AccessAllowedObject
+                | AceType::AccessDeniedObject
+                | AceType::SystemAuditObject
+                | AceType::SystemAlarmObject
+                | AceType::AccessAllowedCallbackObject
+                | AceType::AccessDeniedCallbackObject
+                | AceType::SystemAuditCallbackObject
+                | AceType::SystemAlarmCallbackObject
+        )
+    }
+
+    /// Returns `true` for the callback ACE types, which carry application data after the SID.
+    pub fn is_callback(self) -> bool {
+        matches!(
+            self,
+            AceType::AccessAllowedCallback
+                | AceType::AccessDeniedCallback
+                | AceType::SystemAuditCallback
+                | AceType::SystemAlarmCallback
+                | AceType::AccessAllowedCallbackObject
+                | AceType::AccessDeniedCallbackObject
+                | AceType::SystemAuditCallbackObject
+                | AceType::SystemAlarmCallbackObject
+        )
+    }
+
+    /// Returns `true` for the ACE types stored in a SACL.
+    pub fn is_system(self) -> bool {
+        self.is_audit()
+            || self.is_alarm()
+            || matches!(
+                self,
+                AceType::SystemMandatoryLabel
+                    | AceType::SystemResourceAttribute
+                    | AceType::SystemScopedPolicyId
+                    | AceType::SystemProcessTrustLabel
+                    | AceType::SystemAccessFilter
+            )
+    }
+}
+
+impl From<u8> for AceType {
+    fn from(value: u8) -> Self {
+        match value as u32 {
+            ACCESS_ALLOWED_ACE_TYPE => AceType::AccessAllowed,
+            ACCESS_DENIED_ACE_TYPE => AceType::AccessDenied,
+            SYSTEM_AUDIT_ACE_TYPE => AceType::SystemAudit,
+            SYSTEM_ALARM_ACE_TYPE => AceType::SystemAlarm,
+            ACCESS_ALLOWED_COMPOUND_ACE_TYPE => AceType::AccessAllowedCompound,
+            ACCESS_ALLOWED_OBJECT_ACE_TYPE => AceType::AccessAllowedObject,
+            ACCESS_DENIED_OBJECT_ACE_TYPE => AceType::AccessDeniedObject,
+            SYSTEM_AUDIT_OBJECT_ACE_TYPE => AceType::SystemAuditObject,
+            SYSTEM_ALARM_OBJECT_ACE_TYPE => AceType::SystemAlarmObject,
+            ACCESS_ALLOWED_CALLBACK_ACE_TYPE => AceType::AccessAllowedCallback,
+            ACCESS_DENIED_CALLBACK_ACE_TYPE => AceType::AccessDeniedCallback,
+            ACCESS_ALLOWED_CALLBACK_OBJECT_ACE_TYPE => AceType::AccessAllowedCallbackObject,
+            ACCESS_DENIED_CALLBACK_OBJECT_ACE_TYPE => AceType::AccessDeniedCallbackObject,
+            SYSTEM_AUDIT_CALLBACK_ACE_TYPE => AceType::SystemAuditCallback,
+            SYSTEM_ALARM_CALLBACK_ACE_TYPE => AceType::SystemAlarmCallback,
+            SYSTEM_AUDIT_CALLBACK_OBJECT_ACE_TYPE => AceType::SystemAuditCallbackObject,
+            SYSTEM_ALARM_CALLBACK_OBJECT_ACE_TYPE => AceType::SystemAlarmCallbackObject,
+            SYSTEM_MANDATORY_LABEL_ACE_TYPE => AceType::SystemMandatoryLabel,
+            SYSTEM_RESOURCE_ATTRIBUTE_ACE_TYPE => AceType::SystemResourceAttribute,
+            SYSTEM_SCOPED_POLICY_ID_ACE_TYPE => AceType::SystemScopedPolicyId,
+            SYSTEM_PROCESS_TRUST_LABEL_ACE_TYPE => AceType::SystemProcessTrustLabel,
+            SYSTEM_ACCESS_FILTER_ACE_TYPE => AceType::SystemAccessFilter,
+            _ => AceType::Unknown(value),
+        }
+    }
+}
+
+impl From<AceType> for u8 {
+    fn from(value: AceType) -> Self {
+        let raw = match value {
+            AceType::AccessAllowed => ACCESS_ALLOWED_ACE_TYPE,
+            AceType::AccessDenied => ACCESS_DENIED_ACE_TYPE,
+            AceType::SystemAudit => SYSTEM_AUDIT_ACE_TYPE,
+            AceType::SystemAlarm => SYSTEM_ALARM_ACE_TYPE,
+            AceType::AccessAllowedCompound => ACCESS_ALLOWED_COMPOUND_ACE_TYPE,
+            AceType::AccessAllowedObject => ACCESS_ALLOWED_OBJECT_ACE_TYPE,
+            AceType::AccessDeniedObject => ACCESS_DENIED_OBJECT_ACE_TYPE,
+            AceType::SystemAuditObject => SYSTEM_AUDIT_OBJECT_ACE_TYPE,
+            AceType::SystemAlarmObject => SYSTEM_ALARM_OBJECT_ACE_TYPE,
+            AceType::AccessAllowedCallback => ACCESS_ALLOWED_CALLBACK_ACE_TYPE,
+            AceType::AccessDeniedCallback => ACCESS_DENIED_CALLBACK_ACE_TYPE,
+            AceType::AccessAllowedCallbackObject => ACCESS_ALLOWED_CALLBACK_OBJECT_ACE_TYPE,
+            AceType::AccessDeniedCallbackObject => ACCESS_DENIED_CALLBACK_OBJECT_ACE_TYPE,
+            AceType::SystemAuditCallback => SYSTEM_AUDIT_CALLBACK_ACE_TYPE,
+            AceType::SystemAlarmCallback => SYSTEM_ALARM_CALLBACK_ACE_TYPE,
+            AceType::SystemAuditCallbackObject => SYSTEM_AUDIT_CALLBACK_OBJECT_ACE_TYPE,
+            AceType::SystemAlarmCallbackObject => SYSTEM_ALARM_CALLBACK_OBJECT_ACE_TYPE,
+            AceType::SystemMandatoryLabel => SYSTEM_MANDATORY_LABEL_ACE_TYPE,
+            AceType::SystemResourceAttribute => SYSTEM_RESOURCE_ATTRIBUTE_ACE_TYPE,
+            AceType::SystemScopedPolicyId => SYSTEM_SCOPED_POLICY_ID_ACE_TYPE,
+            AceType::SystemProcessTrustLabel => SYSTEM_PROCESS_TRUST_LABEL_ACE_TYPE,
+            AceType::SystemAccessFilter => SYSTEM_ACCESS_FILTER_ACE_TYPE,
+            AceType::Unknown(raw) => return raw,
+        };
+        raw as u8
+    }
+}
+
+/// The decoded content of an ACE, by the kind of its type.
+///
+/// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/secauthz/ace)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AcePayload<'a> {
+    /// An access mask and a SID: allowed, denied, audit, alarm and process trust label ACEs.
+    Access {
+        /// The access mask.
+        mask: u32,
+        /// The trustee.
+        sid: Sid,
+    },
+    /// An object ACE: an access mask and a SID scoped by object type GUIDs.
+    Object {
+        /// The access mask.
+        mask: u32,
+        /// The GUID of the schema element the ACE applies to, see [`Ace::object_type`].
+        object_type: Option<Guid>,
+        /// The GUID of the object class inheriting the ACE, see [`Ace::inherited_object_type`].
+        inherited_object_type: Option<Guid>,
+        /// The trustee.
+        sid: Sid,
+    },
+    /// A callback ACE (including callback object ACEs) or an access filter ACE: an access
+    /// mask and a SID with application data, usually a condition.
+    Callback {
+        /// The access mask.
+        mask: u32,
+        /// The GUID of the schema element the ACE applies to (callback object ACEs only).
+        object_type: Option<Guid>,
+        /// The GUID of the object class inheriting the ACE (callback object ACEs only).
+        inherited_object_type: Option<Guid>,
+        /// The trustee.
+        sid: Sid,
+        /// The application data, see [`Ace::application_data`].
+        application_data: &'a [u8],
+    },
+    /// A mandatory label ACE.
+    MandatoryLabel(MandatoryLabel),
+    /// A resource attribute ACE.
+    ResourceAttribute(ResourceAttribute),
+    /// A scoped policy ID ACE holding the ID of a central access policy.
+    ScopedPolicyId(Sid),
+    /// A compound ACE granting access to a client impersonated by a server.
+    Compound {
+        /// The access mask.
+        mask: u32,
+        /// The server doing the impersonation, see [`Ace::sid`].
+        server: Sid,
+        /// The impersonated client, see [`Ace::client_sid`].
+        client: Sid,
+    },
+    /// An ACE not decoded by this crate (e.g., an alarm callback ACE): the raw bytes, header
+    /// included.
+    Other(&'a [u8]),
+}
+
+/// An owned ACE: its type, flags, access mask and SID.
+///
+/// Unlike [`Ace`], which borrows the memory of its ACL, an entry can be collected, stored and
+/// compared freely, and added to any ACL later. Entries cover the ACE types consisting of just
+/// these fields (allowed, denied, audit, alarm, mandatory label, scoped policy ID and process
+/// trust label ACEs).
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::{
+///     acl::{AceFlags, Acl},
+///     sd::{SecurityDescriptor, SecurityDescriptorBuilder},
+/// };
+///
+/// let sd = SecurityDescriptor::from_path("C:\\data")?;
+/// let mut entries = sd
+///     .dacl()
+///     .map(|dacl| dacl.try_iter().map(|ace| ace?.to_entry()).collect::<Result<Vec<_>, _>>())
+///     .transpose()?
+///     .unwrap_or_default();
+/// entries.retain(|entry| !entry.flags.contains(AceFlags::INHERITED));
+/// let dacl = Acl::from_entries(&entries)?;
+/// let sd = SecurityDescriptorBuilder::new().dacl(dacl).build()?;
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AceEntry {
+    /// The type of the ACE.
+    pub kind: AceType,
+    /// The header flags of the ACE (inheritance and audit flags).
+    pub flags: AceFlags,
+    /// The access mask of the ACE (the policy of a mandatory label ACE).
+    pub mask: u32,
+    /// The SID of the ACE.
+    pub sid: Sid,
+}
+
+impl AceEntry {
+    /// Creates an entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The type of the ACE.
+    /// * `flags` - The header flags of the ACE.
+    /// * `mask` - The access mask of the ACE.
+    /// * `sid` - The SID of the ACE.
+    pub fn new<M>(kind: AceType, flags: AceFlags, mask: M, sid: Sid) -> Self
+    where
+        M: IntoAccessMask,
+    {
+        Self {
+            kind,
+            flags,
+            mask: mask.as_u32(),
+            sid,
+        }
+    }
+
+    /// Returns `true` if entries of the given type can be represented: the types consisting of
+    /// a header, an access mask and a SID.
+    fn is_supported(kind: AceType) -> bool {
+        matches!(
+            kind,
+            AceType::AccessAllowed
+                | AceType::AccessDenied
+                | AceType::SystemAudit
+                | AceType::SystemAlarm
+                | AceType::SystemMandatoryLabel
+                | AceType::SystemScopedPolicyId
+                | AceType::SystemProcessTrustLabel
+        )
+    }
+
+    /// Parses an entry from a single ACE in SDDL form, e.g., `(A;OICI;FA;;;WD)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `sddl` - The ACE string, in parentheses.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ERROR_INVALID_PARAMETER` if the string is not a single ACE or the ACE has an
+    /// unsupported type (see [`AceEntry`]), or an error if the string cannot be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::acl::{AceEntry, AceFlags, AceType};
+    ///
+    /// let entry = AceEntry::from_sddl("(A;OICI;FA;;;WD)")?;
+    /// assert_eq!(entry.kind, AceType::AccessAllowed);
+    /// assert!(entry.flags.contains(AceFlags::CONTAINER_INHERIT));
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn from_sddl<S>(sddl: S) -> Result<Self, WinError>
+    where
+        S: AsRef<str>,
+    {
+        let sddl = sddl.as_ref().trim();
+        let invalid = || WinError::new(ERROR_INVALID_PARAMETER, Some(format!("Not a single ACE: {}", sddl)));
+        let code = sddl
+            .strip_prefix('(')
+            .filter(|ace| ace.ends_with(')'))
+            .and_then(|ace| ace.split(';').next())
+            .ok_or_else(invalid)?;
+
+        // the ACEs stored in a SACL are only accepted in the S: part
+        let system = matches!(
+            code,
+            "AU" | "AL" | "OU" | "OL" | "XU" | "ML" | "RA" | "SP" | "TL" | "FL"
+        );
+        let sd = SecurityDescriptor::from_sd_string(format!("{}:{}", if system { "S" } else { "D" }, sddl))?;
+        let acl = if system { sd.sacl_unchecked() } else { sd.dacl() }.ok_or_else(invalid)?;
+        if acl.ace_count() != 1 {
+            return Err(invalid());
+        }
+        acl.get(0).ok_or_else(invalid)?.to_entry()
+    }
+
+    /// Renders the entry in SDDL form, e.g., `(A;OICI;FA;;;WD)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ERROR_INVALID_PARAMETER` if the entry has an unsupported type, or an error if
+    /// the ACE cannot be rendered.
+    pub fn to_sddl(&self) -> Result<String, WinError> {
+        let acl = Acl::from_ace(&self.to_bytes()?)?;
+        let ace = acl.get(0).ok_or(ERROR_INVALID_PARAMETER)?;
+        ace.to_sddl()
+    }
+
+    /// Encodes the entry as an ACE.
+    pub(crate) fn to_bytes(&self) -> Result<Vec<u8>, WinError> {
+        if !Self::is_supported(self.kind) {
+            return Err(WinError::new(
+                ERROR_INVALID_PARAMETER,
+                Some(format!("Unsupported ACE entry type: {:?}", self.kind)),
+            ));
+        }
+        Ok(access_ace_bytes(self.kind, self.flags, self.mask, self.sid.as_bytes()))
+    }
+}
+
+impl TryFrom<&Ace<'_>> for AceEntry {
+    type Error = WinError;
+
+    fn try_from(ace: &Ace<'_>) -> Result<Self, Self::Error> {
+        if !Self::is_supported(ace.ace_type()) {
+            return Err(WinError::new(
+                ERROR_INVALID_PARAMETER,
+                Some(format!("Unsupported ACE entry type: {:?}", ace.ace_type())),
+            ));
+        }
+        Ok(Self {
+            kind: ace.ace_type(),
+            flags: ace.flags(),
+            mask: ace.mask(),
+            sid: ace.sid()?,
+        })
+    }
+}
+
+/// The flags of an Access Control Entry (ACE) header.
+///
+/// The inheritance flags control how an ACE of a container (e.g., a directory or a registry key)
+/// propagates to its children; the audit flags select which access attempts an audit ACE logs.
+/// All bitwise operations (`|`, `&`, `|=`, `&=`, `!`) are supported for combining flags.
+///
+/// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-ace_header)
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::acl::AceFlags;
+///
+/// // applies to the directory, its subdirectories and its files
+/// let flags = AceFlags::OBJECT_INHERIT | AceFlags::CONTAINER_INHERIT;
+/// assert!(flags.contains(AceFlags::CONTAINER_INHERIT));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct AceFlags(pub u8);
+
+impl AceFlags {
+    /// Non-container child objects (e.g., files) inherit the ACE.
+    pub const OBJECT_INHERIT: Self = Self(OBJECT_INHERIT_ACE as u8);
+    /// Container child objects (e.g., directories) inherit the ACE.
+    pub const CONTAINER_INHERIT: Self = Self(CONTAINER_INHERIT_ACE as u8);
+    /// The ACE is inherited by the immediate children only, not propagated further.
+    pub const NO_PROPAGATE_INHERIT: Self = Self(NO_PROPAGATE_INHERIT_ACE as u8);
+    /// The ACE does not apply to the object itself, only to the children inheriting it.
+    pub const INHERIT_ONLY: Self = Self(INHERIT_ONLY_ACE as u8);
+    /// The ACE was inherited from the parent.
+    pub const INHERITED: Self = Self(INHERITED_ACE as u8);
+    /// The audit ACE logs successful access attempts.
+    pub const SUCCESSFUL_ACCESS: Self = Self(SUCCESSFUL_ACCESS_ACE_FLAG as u8);
+    /// The audit ACE logs failed access attempts.
+    pub const FAILED_ACCESS: Self = Self(FAILED_ACCESS_ACE_FLAG as u8);
+
+    /// No flags.
+    pub fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns `true` if all flags of `other` are set in `self`.
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns the flags in SDDL notation (e.g., `OICIIO`).
+    pub(crate) fn to_sddl(self) -> String {
+        const FLAGS: [(AceFlags, &str); 7] = [
+            (AceFlags::OBJECT_INHERIT, "OI"),
+            (AceFlags::CONTAINER_INHERIT, "CI"),
+            (AceFlags::NO_PROPAGATE_INHERIT, "NP"),
+            (AceFlags::INHERIT_ONLY, "IO"),
+            (AceFlags::INHERITED, "ID"),
+            (AceFlags::SUCCESSFUL_ACCESS, "SA"),
+            (AceFlags::FAILED_ACCESS, "FA"),
+        ];
+        FLAGS
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| *name)
+            .collect()
+    }
+}
+
+impl From<AceFlags> for u8 {
+    fn from(value: AceFlags) -> Self {
+        value.0
+    }
+}
+
+impl From<u8> for AceFlags {
+    fn from(value: u8) -> Self {
+        AceFlags(value)
+    }
+}
+
+bit_ops!(AceFlags);
+
+/// A lookup index over the ACEs of an ACL.
+///
+/// Scanning an ACL is linear in the number of its ACEs, which adds up when a large ACL
+/// (e.g., of an Active Directory object or a file server root) is queried once per trustee.
+/// The index is built once in a single pass and then answers queries by SID and by ACE type
+/// in logarithmic time. Matching ACEs are yielded in ACL order.
+///
+/// The index borrows the ACL, so it cannot be modified while the index is alive.
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::{acl::{Acl, AclIndex, AceType}, mask::AccessMask, sid::Sid};
+/// use win_acl_rs::wellknown::WinWorldSid;
+///
+/// let mut acl = Acl::new()?;
+/// let everyone = Sid::from_well_known_sid(WinWorldSid)?;
+/// acl.allow(AccessMask::read(), &everyone)?;
+///
+/// let index = AclIndex::new(&acl)?;
+/// let granted = index.mask_for(&everyone, AceType::AccessAllowed);
+/// println!("Everyone is granted 0x{:X}", granted);
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+#[derive(Debug)]
+pub struct AclIndex<'a> {
+    acl: &'a Acl,
+    by_sid: BTreeMap<Vec<u8>, Vec<*const c_void>>,
+    by_type: BTreeMap<AceType, Vec<*const c_void>>,
+}
+
 impl Drop for Acl {
     fn drop(&mut self) {
         if self.owned {
@@ -196,9 +843,15 @@ impl Acl {
         Self { ptr, owned: false }
     }
 
+    /// Returns the raw pointer to the underlying ACL structure.
+    pub(crate) fn as_ptr(&self) -> *const ACL {
+        self.ptr
+    }
+
     /// Checks if the ACL structure is valid.
     ///
     /// Validates that the ACL structure is properly formatted according to Windows security APIs.
+    /// Use [`Acl::validate`] to find out what is wrong with an ACL.
     ///
     /// # Returns
     ///
@@ -207,196 +860,2135 @@ impl Acl {
         unsafe { IsValidAcl(self.ptr) != FALSE }
     }
 
+    /// Returns the revision of the ACL: `ACL_REVISION`, or `ACL_REVISION_DS` once the ACL holds
+    /// object ACEs.
+    ///
+    /// New ACLs start at `ACL_REVISION`; adding an ACE type that needs a higher revision (e.g.,
+    /// with [`Acl::allow_object`]) raises the revision automatically.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{acl::{AceFlags, Acl}, mask::AccessMask, sid::Sid};
+    /// use windows_sys::Win32::Security::{ACL_REVISION, ACL_REVISION_DS};
+    ///
+    /// let mut acl = Acl::new()?;
+    /// assert_eq!(acl.revision() as u32, ACL_REVISION);
+    /// acl.allow_object(AccessMask::from(0x100), AceFlags::empty(), None, None, &Sid::from_string("S-1-5-11")?)?;
+    /// assert_eq!(acl.revision() as u32, ACL_REVISION_DS);
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn revision(&self) -> u8 {
+        unsafe { (*self.ptr).AclRevision }
+    }
+
     /// Returns the number of ACEs in this ACL.
     ///
     /// # Returns
     ///
     /// The number of Access Control Entries in the ACL.
     pub fn ace_count(&self) -> u32 {
-        unsafe {
-            let mut info: ACL_SIZE_INFORMATION = std::mem::zeroed();
-            GetAclInformation(
-                self.ptr,
-                &mut info as *mut _ as *mut _,
-                size_of::<ACL_SIZE_INFORMATION>() as u32,
-                AclSizeInformation,
-            );
-            info.AceCount
-        }
+        self.size_information().map(|info| info.AceCount).unwrap_or(0)
     }
 
-    /// Adds an access-allowed ACE to the ACL.
-    ///
-    /// An access-allowed ACE grants the specified access rights to the given security principal.
+    /// Copies the ACL into a byte vector (the whole `ACL` buffer, including unused space).
     ///
-    /// # Arguments
+    /// The bytes can be stored and turned back into an ACL with [`Acl::from_bytes`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        unsafe { std::slice::from_raw_parts(self.ptr as *const u8, self.capacity() as usize) }.to_vec()
+    }
+
+    /// Creates an owned deep copy of the ACL, with the same size and ACEs.
     ///
-    /// * `access_mask` - A bitmask specifying the access rights to grant. Can be:
-    ///   - An `AccessMask` or other mask type from the `mask` module (recommended)
-    ///   - A raw `u32` for low-level control
-    /// * `sid_ref` - The SID (Security Identifier) of the security principal to grant access to.
-    ///   Can be a `Sid`, `SidRef`, or any type implementing `AsSidRef`.
+    /// An ACL borrowed from a security descriptor (e.g., by [`SecurityDescriptorImpl::dacl`](crate::sd::SecurityDescriptorImpl::dacl))
+    /// points into the memory of the descriptor; the copy owns its buffer, so it can outlive the
+    /// descriptor and be modified without touching it.
     ///
     /// # Errors
     ///
-    /// Returns an error if the ACE cannot be added (e.g., insufficient memory).
+    /// Returns an error if memory allocation fails.
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// use win_acl_rs::{acl::Acl, mask::AccessMask, sid::Sid};
-    /// use win_acl_rs::wellknown::WinWorldSid;
+    /// use win_acl_rs::{acl::Acl, sd::SecurityDescriptor};
     ///
-    /// let mut acl = Acl::new()?;
-    /// let sid = Sid::from_well_known_sid(WinWorldSid)?;
-    /// // Using AccessMask type
-    /// acl.allow(AccessMask::full(), &sid)?;
-    /// // Or with raw u32/i32
-    /// acl.allow(0x1F01FF, &sid)?;
+    /// let dacl: Option<Acl> = {
+    ///     let sd = SecurityDescriptor::from_path("C:\\data")?;
+    ///     sd.dacl().map(|dacl| dacl.try_clone()).transpose()?
+    /// };
+    /// println!("{} ACEs", dacl.map_or(0, |dacl| dacl.ace_count()));
     /// # Ok::<(), win_acl_rs::error::WinError>(())
     /// ```
-    pub fn allow<'a, S, M>(&mut self, access_mask: M, sid_ref: &'a S) -> Result<(), WinError>
-    where
-        S: AsSidRef<'a>,
-        M: Mask,
-    {
-        unsafe {
-            winapi_bool_call!(AddAccessAllowedAce(
-                self.ptr,
-                ACL_REVISION,
-                access_mask.as_u32(),
-                sid_ref.as_sid_ref().as_ptr() as _,
-            ))
-        };
-        Ok(())
+    pub fn try_clone(&self) -> Result<Acl, WinError> {
+        Self::from_bytes(&self.to_bytes())
     }
 
-    /// Adds an access-denied ACE to the ACL.
-    ///
-    /// An access-denied ACE explicitly denies the specified access rights to the given security principal.
-    /// Access-denied ACEs take precedence over access-allowed ACEs.
+    /// Creates an owned ACL from its binary form (an `ACL` header followed by its ACEs).
     ///
     /// # Arguments
     ///
-    /// * `access_mask` - A bitmask specifying the access rights to deny. Can be:
-    ///   - An `AccessMask` or other mask type from the `mask` module (recommended)
-    ///   - A raw `u32` for low-level control
-    /// * `sid_ref` - The SID (Security Identifier) of the security principal to deny access to.
-    ///   Can be a `Sid`, `SidRef`, or any type implementing `AsSidRef`.
+    /// * `bytes` - The binary ACL, at least as long as the size in its header.
     ///
     /// # Errors
     ///
-    /// Returns an error if the ACE cannot be added (e.g., insufficient memory).
+    /// Returns `ERROR_INVALID_ACL` if the bytes are too short or do not form a valid ACL, or an
+    /// error if memory allocation fails.
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// use win_acl_rs::{acl::Acl, mask::AccessMask, sid::Sid};
-    /// use win_acl_rs::wellknown::WinWorldSid;
+    /// use win_acl_rs::{acl::Acl, mask::FileAccess, sid::Sid};
     ///
     /// let mut acl = Acl::new()?;
-    /// let sid = Sid::from_well_known_sid(WinWorldSid)?;
-    /// // Using AccessMask type
-    /// acl.deny(AccessMask::read(), &sid)?;
+    /// acl.allow(FileAccess::FULL, &Sid::from_string("S-1-5-18")?)?;
+    /// let bytes = acl.to_bytes();
+    /// let copy = Acl::from_bytes(&bytes)?;
+    /// assert_eq!(copy.ace_count(), 1);
     /// # Ok::<(), win_acl_rs::error::WinError>(())
     /// ```
-    pub fn deny<'a, S, M>(&mut self, access_mask: M, sid_ref: &'a S) -> Result<(), WinError>
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WinError> {
+        let invalid = || WinError::new(ERROR_INVALID_ACL, Some("Invalid binary ACL".to_owned()));
+        if bytes.len() < size_of::<ACL>() {
+            return Err(invalid());
+        }
+        let size = u16::from_le_bytes([bytes[2], bytes[3]]) as usize;
+        if size < size_of::<ACL>() || size > bytes.len() {
+            return Err(invalid());
+        }
+
+        let ptr = unsafe { LocalAlloc(LMEM_FIXED, size) as *mut ACL };
+        if ptr.is_null() {
+            return Err(ERROR_OUTOFMEMORY.into());
+        }
+        let acl = Self { ptr, owned: true };
+        unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr as *mut u8, size) };
+        if !acl.is_valid() {
+            return Err(invalid());
+        }
+        Ok(acl)
+    }
+
+    /// Returns the number of bytes of the ACL in use: the header and the ACEs.
+    pub fn bytes_in_use(&self) -> u32 {
+        self.size_information().map(|info| info.AclBytesInUse).unwrap_or(0)
+    }
+
+    /// Returns the number of unused bytes of the ACL, available for new ACEs.
+    pub fn bytes_free(&self) -> u32 {
+        self.size_information().map(|info| info.AclBytesFree).unwrap_or(0)
+    }
+
+    /// Returns the size of the ACL in bytes, used and free.
+    pub fn capacity(&self) -> u32 {
+        unsafe { (*self.ptr).AclSize as u32 }
+    }
+
+    /// Returns `true` if an ACE of the given type for the given SID fits into the free space
+    /// of the ACL.
+    ///
+    /// Object ACEs are assumed to carry both GUIDs. Variable data (the condition of a callback
+    /// ACE, the value of a resource attribute) is not counted.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid_ref` - The SID of the ACE.
+    /// * `ace_type` - The type of the ACE.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{
+    ///     acl::{AceType, Acl},
+    ///     mask::FileAccess,
+    ///     sid::Sid,
+    /// };
+    ///
+    /// let sids = (1000..1100)
+    ///     .map(|rid| Sid::from_string(format!("S-1-5-21-1-2-3-{}", rid)))
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    /// let mut acl = Acl::with_capacity(sids.len(), 28)?;
+    /// for sid in &sids {
+    ///     assert!(acl.would_fit(sid, AceType::AccessAllowed));
+    ///     acl.allow(FileAccess::FULL, sid)?;
+    /// }
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn would_fit<'a, S>(&self, sid_ref: &'a S, ace_type: AceType) -> bool
     where
         S: AsSidRef<'a>,
-        M: Mask,
     {
-        unsafe {
-            winapi_bool_call!(AddAccessDeniedAce(
-                self.ptr,
-                ACL_REVISION,
-                access_mask.as_u32(),
-                sid_ref.as_sid_ref().as_ptr() as _
-            ))
-        };
-        Ok(())
+        let sid_len = sid_ref.as_sid_ref().as_bytes().len();
+        let mut size = size_of::<ACE_HEADER>() + size_of::<u32>() + sid_len;
+        if ace_type.is_object() {
+            size += size_of::<u32>() + 2 * size_of::<GUID>();
+        }
+        size <= self.bytes_free() as usize
     }
 
-    /// Removes the ACE at the given index.
+    /// Returns the ACE at the given position.
     ///
     /// # Arguments
     ///
-    /// * `index` - The zero-based index of the ACE to remove. Must be less than `ace_count()`.
+    /// * `index` - The zero-based index of the ACE.
     ///
-    /// # Errors
+    /// # Returns
     ///
-    /// Returns an error if the index is out of bounds or if the ACE cannot be removed.
+    /// The ACE, or `None` if the index is out of bounds.
     ///
-    /// # Panics
+    /// # Examples
     ///
-    /// This function does not panic, but passing an invalid index will result in an error.
-    pub fn remove_ace(&mut self, index: u32) -> Result<(), WinError> {
-        unsafe {
-            winapi_bool_call!(DeleteAce(self.ptr, index));
+    /// ```no_run
+    /// use win_acl_rs::sd::SecurityDescriptor;
+    ///
+    /// let sd = SecurityDescriptor::from_path("C:\\data")?;
+    /// if let Some(first) = sd.dacl().as_ref().and_then(|dacl| dacl.get(0)) {
+    ///     println!("evaluated first: {:?}", first);
+    /// }
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn get(&self, index: u32) -> Option<Ace<'_>> {
+        if index >= self.ace_count() {
+            return None;
         }
-        Ok(())
+        self.ace_ptr(index).ok().map(|ptr| Ace {
+            ptr,
+            _phantom: PhantomData,
+        })
     }
-}
 
-impl<'a> Iterator for AclIter<'a> {
-    type Item = Ace<'a>;
+    /// Returns an iterator over the ACEs in this ACL.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ACL information cannot be read. Iterating over `&Acl` directly
+    /// yields no entries in that case instead.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sd::SecurityDescriptor;
+    ///
+    /// let sd = SecurityDescriptor::from_path("C:\\data")?;
+    /// if let Some(dacl) = sd.dacl() {
+    ///     let aces = dacl.iter()?;
+    ///     println!("{} ACEs, last one: {:?}", aces.len(), aces.rev().next());
+    /// }
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn iter(&self) -> Result<AclIter<'_>, WinError> {
+        let info = self.size_information()?;
+        Ok(AclIter {
+            acl: self,
+            front: 0,
+            back: info.AceCount,
+        })
+    }
+
+    /// Returns a fallible iterator over the ACEs in this ACL.
+    ///
+    /// Unlike iterating over `&Acl`, failures to read the ACL or one of its ACEs are reported
+    /// as errors instead of silently ending the iteration, so corrupted ACLs are not mistaken
+    /// for empty ones.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::acl::Acl;
+    ///
+    /// let acl = Acl::new()?;
+    /// for ace in acl.try_iter() {
+    ///     let ace = ace?;
+    ///     println!("ACE: {:?}", ace);
+    /// }
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn try_iter(&self) -> AclTryIter<'_> {
+        match self.size_information() {
+            Ok(info) => AclTryIter {
+                acl: self,
+                index: 0,
+                count: info.AceCount,
+                error: None,
+            },
+            Err(error) => AclTryIter {
+                acl: self,
+                index: 0,
+                count: 0,
+                error: Some(error),
+            },
+        }
+    }
+
+    /// Consumes the ACL, returning an iterator over its ACEs as owned [`AceEntry`] values.
+    ///
+    /// ACEs that cannot be represented as an entry (e.g., object or callback ACEs) yield
+    /// `ERROR_INVALID_PARAMETER`, so that an ACL rebuilt from the entries never silently lacks
+    /// them. Together with collecting entries into a `Result<Acl, WinError>`, this allows
+    /// filtering an ACL.
+    ///
+    /// This is a method rather than `IntoIterator for Acl`, which would change what `into_iter`
+    /// yields for existing callers iterating over an `Acl` by reference.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{acl::Acl, mask::FileAccess, sid::Sid};
+    ///
+    /// let guests = Sid::from_string("S-1-5-32-546")?;
+    /// let mut acl = Acl::new()?;
+    /// acl.allow(FileAccess::FULL, &Sid::from_string("S-1-5-18")?)?;
+    /// acl.allow(FileAccess::READ, &guests)?;
+    /// let entries = acl.into_entries().collect::<Result<Vec<_>, _>>()?;
+    /// let acl: Acl = entries
+    ///     .into_iter()
+    ///     .filter(|entry| entry.sid != guests)
+    ///     .collect::<Result<_, _>>()?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn into_entries(self) -> AclIntoEntries {
+        let count = self.ace_count();
+        AclIntoEntries {
+            acl: self,
+            index: 0,
+            count,
+        }
+    }
+
+    /// Returns an iterator over the ACEs granting access (see [`AceType::is_allow`]).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sd::SecurityDescriptor;
+    ///
+    /// let sd = SecurityDescriptor::from_path("C:\\data")?;
+    /// if let Some(dacl) = sd.dacl() {
+    ///     for ace in dacl.allowed() {
+    ///         println!("{} may {:#x}", ace.sid()?, ace.mask());
+    ///     }
+    ///     println!("{} explicit deny ACEs", dacl.denied().filter(|ace| !ace.is_inherited()).count());
+    /// }
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn allowed(&self) -> impl Iterator<Item = Ace<'_>> {
+        self.into_iter().filter(|ace| ace.ace_type().is_allow())
+    }
+
+    /// Returns an iterator over the ACEs denying access (see [`AceType::is_deny`]).
+    pub fn denied(&self) -> impl Iterator<Item = Ace<'_>> {
+        self.into_iter().filter(|ace| ace.ace_type().is_deny())
+    }
+
+    /// Returns an iterator over the audit ACEs (see [`AceType::is_audit`]).
+    pub fn audits(&self) -> impl Iterator<Item = Ace<'_>> {
+        self.into_iter().filter(|ace| ace.ace_type().is_audit())
+    }
+
+    /// Returns an iterator over the explicit ACEs, i.e. those set on the object itself.
+    pub fn explicit(&self) -> impl Iterator<Item = Ace<'_>> {
+        self.into_iter().explicit()
+    }
+
+    /// Returns an iterator over the ACEs inherited from the parent object.
+    pub fn inherited(&self) -> impl Iterator<Item = Ace<'_>> {
+        self.into_iter().inherited()
+    }
+
+    /// Returns the bytes of the ACEs, which are stored contiguously after the ACL header.
+    pub(crate) fn ace_bytes(&self) -> Result<&[u8], WinError> {
+        let info = self.size_information()?;
+        let len = (info.AclBytesInUse as usize).saturating_sub(size_of::<ACL>());
+        Ok(unsafe { std::slice::from_raw_parts((self.ptr as *const u8).add(size_of::<ACL>()), len) })
+    }
+
+    fn size_information(&self) -> Result<ACL_SIZE_INFORMATION, WinError> {
+        let mut info = ACL_SIZE_INFORMATION {
+            AceCount: 0,
+            AclBytesInUse: 0,
+            AclBytesFree: 0,
+        };
+        unsafe {
+            winapi_bool_call!(GetAclInformation(
+                self.ptr,
+                &mut info as *mut _ as *mut _,
+                size_of::<ACL_SIZE_INFORMATION>() as u32,
+                AclSizeInformation,
+            ))
+        };
+        Ok(info)
+    }
+
+    fn ace_ptr(&self, index: u32) -> Result<*const c_void, WinError> {
+        let mut ace_ptr: *mut c_void = std::ptr::null_mut();
+        unsafe { winapi_bool_call!(GetAce(self.ptr, index, &mut ace_ptr)) };
+        Ok(ace_ptr.cast_const())
+    }
+
+    /// Adds an access-allowed ACE to the ACL.
+    ///
+    /// An access-allowed ACE grants the specified access rights to the given security principal.
+    ///
+    /// # Arguments
+    ///
+    /// * `access_mask` - A bitmask specifying the access rights to grant, an `AccessMask` or other
+    ///   mask type from the `mask` module. Raw integers are not accepted, wrap them explicitly
+    ///   (e.g., `AccessMask::from(0x1F01FF)`).
+    /// * `sid_ref` - The SID (Security Identifier) of the security principal to grant access to.
+    ///   Can be a `Sid`, `SidRef`, or any type implementing `AsSidRef`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ACE cannot be added (e.g., insufficient memory).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{acl::Acl, mask::AccessMask, sid::Sid};
+    /// use win_acl_rs::wellknown::WinWorldSid;
+    ///
+    /// let mut acl = Acl::new()?;
+    /// let sid = Sid::from_well_known_sid(WinWorldSid)?;
+    /// // Using AccessMask type
+    /// acl.allow(AccessMask::full(), &sid)?;
+    /// // Or with an explicitly wrapped raw value
+    /// acl.allow(AccessMask::from(0x1F01FF), &sid)?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn allow<'a, S, M>(&mut self, access_mask: M, sid_ref: &'a S) -> Result<(), WinError>
+    where
+        S: AsSidRef<'a>,
+        M: IntoAccessMask,
+    {
+        unsafe {
+            winapi_bool_call!(AddAccessAllowedAce(
+                self.ptr,
+                ACL_REVISION,
+                access_mask.as_u32(),
+                sid_ref.as_sid_ref().as_ptr() as _,
+            ))
+        };
+        Ok(())
+    }
+
+    /// Adds an access-denied ACE to the ACL.
+    ///
+    /// An access-denied ACE explicitly denies the specified access rights to the given security principal.
+    /// Access-denied ACEs take precedence over access-allowed ACEs only when they come first:
+    /// the ACE is appended, use [`Acl::add_canonical`] to insert it before the allow ACEs.
+    ///
+    /// # Arguments
+    ///
+    /// * `access_mask` - A bitmask specifying the access rights to deny, an `AccessMask` or other
+    ///   mask type from the `mask` module. Raw integers are not accepted, see [`Acl::allow`].
+    /// * `sid_ref` - The SID (Security Identifier) of the security principal to deny access to.
+    ///   Can be a `Sid`, `SidRef`, or any type implementing `AsSidRef`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ACE cannot be added (e.g., insufficient memory).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{acl::Acl, mask::AccessMask, sid::Sid};
+    /// use win_acl_rs::wellknown::WinWorldSid;
+    ///
+    /// let mut acl = Acl::new()?;
+    /// let sid = Sid::from_well_known_sid(WinWorldSid)?;
+    /// // Using AccessMask type
+    /// acl.deny(AccessMask::read(), &sid)?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn deny<'a, S, M>(&mut self, access_mask: M, sid_ref: &'a S) -> Result<(), WinError>
+    where
+        S: AsSidRef<'a>,
+        M: IntoAccessMask,
+    {
+        unsafe {
+            winapi_bool_call!(AddAccessDeniedAce(
+                self.ptr,
+                ACL_REVISION,
+                access_mask.as_u32(),
+                sid_ref.as_sid_ref().as_ptr() as _
+            ))
+        };
+        Ok(())
+    }
+
+    /// Adds an access-allowed ACE with the given header flags to the ACL.
+    ///
+    /// Unlike [`Acl::allow`], the ACE can be made inheritable, which is what ACLs of directories
+    /// and registry keys usually need.
+    ///
+    /// # Arguments
+    ///
+    /// * `access_mask` - A bitmask specifying the access rights to grant.
+    /// * `flags` - The inheritance flags of the ACE (e.g., `AceFlags::OBJECT_INHERIT | AceFlags::CONTAINER_INHERIT`).
+    /// * `sid_ref` - The SID of the security principal to grant access to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ACE cannot be added (e.g., insufficient memory or invalid flags).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{acl::{AceFlags, Acl}, mask::FileAccess, sid::Sid};
+    ///
+    /// let mut acl = Acl::new()?;
+    /// let users = Sid::from_string("S-1-5-32-545")?;
+    /// acl.allow_with_flags(FileAccess::READ, AceFlags::OBJECT_INHERIT | AceFlags::CONTAINER_INHERIT, &users)?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn allow_with_flags<'a, S, M>(
+        &mut self,
+        access_mask: M,
+        flags: AceFlags,
+        sid_ref: &'a S,
+    ) -> Result<(), WinError>
+    where
+        S: AsSidRef<'a>,
+        M: IntoAccessMask,
+    {
+        unsafe {
+            winapi_bool_call!(AddAccessAllowedAceEx(
+                self.ptr,
+                ACL_REVISION,
+                flags.0 as u32,
+                access_mask.as_u32(),
+                sid_ref.as_sid_ref().as_ptr() as _,
+            ))
+        };
+        Ok(())
+    }
+
+    /// Adds an access-denied ACE with the given header flags to the ACL.
+    ///
+    /// # Arguments
+    ///
+    /// * `access_mask` - A bitmask specifying the access rights to deny.
+    /// * `flags` - The inheritance flags of the ACE.
+    /// * `sid_ref` - The SID of the security principal to deny access to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ACE cannot be added (e.g., insufficient memory or invalid flags).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{acl::{AceFlags, Acl}, mask::FileAccess, sid::Sid};
+    ///
+    /// let mut acl = Acl::new()?;
+    /// let guests = Sid::from_string("S-1-5-32-546")?;
+    /// acl.deny_with_flags(FileAccess::WRITE, AceFlags::OBJECT_INHERIT | AceFlags::CONTAINER_INHERIT, &guests)?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn deny_with_flags<'a, S, M>(&mut self, access_mask: M, flags: AceFlags, sid_ref: &'a S) -> Result<(), WinError>
+    where
+        S: AsSidRef<'a>,
+        M: IntoAccessMask,
+    {
+        unsafe {
+            winapi_bool_call!(AddAccessDeniedAceEx(
+                self.ptr,
+                ACL_REVISION,
+                flags.0 as u32,
+                access_mask.as_u32(),
+                sid_ref.as_sid_ref().as_ptr() as _,
+            ))
+        };
+        Ok(())
+    }
+
+    /// Adds a system-audit ACE to the ACL.
+    ///
+    /// Audit ACEs belong in a SACL: they make the system log an event when the given security
+    /// principal attempts the audited access. Writing a SACL to an object requires the
+    /// *SeSecurityPrivilege* privilege (see [`elevated`](crate::elevated)).
+    ///
+    /// # Arguments
+    ///
+    /// * `access_mask` - The audited access rights.
+    /// * `sid_ref` - The SID of the security principal whose access is audited.
+    /// * `on` - Whether successful and/or failed access attempts are audited.
+    /// * `flags` - The inheritance flags of the ACE.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ACE cannot be added (e.g., insufficient memory or invalid flags).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{
+    ///     acl::{AceFlags, Acl},
+    ///     audit::AuditOn,
+    ///     mask::FileAccess,
+    ///     sid::Sid,
+    ///     wellknown::WinWorldSid,
+    /// };
+    ///
+    /// // audit failed writes by Everyone to a directory and everything below it
+    /// let mut sacl = Acl::new()?;
+    /// let everyone = Sid::from_well_known_sid(WinWorldSid)?;
+    /// sacl.audit(
+    ///     FileAccess::WRITE,
+    ///     &everyone,
+    ///     AuditOn::Failure,
+    ///     AceFlags::OBJECT_INHERIT | AceFlags::CONTAINER_INHERIT,
+    /// )?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn audit<'a, S, M>(
+        &mut self,
+        access_mask: M,
+        sid_ref: &'a S,
+        on: AuditOn,
+        flags: AceFlags,
+    ) -> Result<(), WinError>
+    where
+        S: AsSidRef<'a>,
+        M: IntoAccessMask,
+    {
+        unsafe {
+            winapi_bool_call!(AddAuditAccessAceEx(
+                self.ptr,
+                self.revision() as u32,
+                flags.0 as u32,
+                access_mask.as_u32(),
+                sid_ref.as_sid_ref().as_ptr() as _,
+                on.success() as _,
+                on.failure() as _,
+            ))
+        };
+        Ok(())
+    }
+
+    /// Adds a conditional access-allowed ACE (`XA`) to the ACL.
+    ///
+    /// The access is granted only when the condition holds, see [`condition`](crate::condition).
+    ///
+    /// # Arguments
+    ///
+    /// * `access_mask` - A bitmask specifying the access rights to grant.
+    /// * `flags` - The inheritance flags of the ACE.
+    /// * `sid_ref` - The SID of the security principal to grant access to.
+    /// * `condition` - The condition of the ACE.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the condition cannot be rendered or the ACE cannot be added
+    /// (e.g., insufficient space in the ACL).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{
+    ///     acl::{AceFlags, Acl},
+    ///     condition::Attribute,
+    ///     mask::FileAccess,
+    ///     sid::Sid,
+    /// };
+    ///
+    /// let mut dacl = Acl::new()?;
+    /// let users = Sid::from_string("S-1-5-32-545")?;
+    /// let finance = Attribute::user("Department").equals("Finance");
+    /// dacl.allow_if(FileAccess::READ, AceFlags::OBJECT_INHERIT | AceFlags::CONTAINER_INHERIT, &users, &finance)?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn allow_if<'a, S, M>(
+        &mut self,
+        access_mask: M,
+        flags: AceFlags,
+        sid_ref: &'a S,
+        condition: &Condition,
+    ) -> Result<(), WinError>
+    where
+        S: AsSidRef<'a>,
+        M: IntoAccessMask,
+    {
+        let sid = Sid::from_bytes(sid_ref.as_sid_ref().as_bytes())?;
+        let ace = condition.ace_bytes(AceType::AccessAllowedCallback, flags, access_mask.as_u32(), &sid)?;
+        self.push_ace(&ace)
+    }
+
+    /// Adds a conditional access-denied ACE (`XD`) to the ACL.
+    ///
+    /// The access is denied only when the condition holds, see [`condition`](crate::condition).
+    ///
+    /// # Arguments
+    ///
+    /// * `access_mask` - A bitmask specifying the access rights to deny.
+    /// * `flags` - The inheritance flags of the ACE.
+    /// * `sid_ref` - The SID of the security principal to deny access to.
+    /// * `condition` - The condition of the ACE.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the condition cannot be rendered or the ACE cannot be added
+    /// (e.g., insufficient space in the ACL).
+    pub fn deny_if<'a, S, M>(
+        &mut self,
+        access_mask: M,
+        flags: AceFlags,
+        sid_ref: &'a S,
+        condition: &Condition,
+    ) -> Result<(), WinError>
+    where
+        S: AsSidRef<'a>,
+        M: IntoAccessMask,
+    {
+        let sid = Sid::from_bytes(sid_ref.as_sid_ref().as_bytes())?;
+        let ace = condition.ace_bytes(AceType::AccessDeniedCallback, flags, access_mask.as_u32(), &sid)?;
+        self.push_ace(&ace)
+    }
+
+    /// Adds a resource attribute ACE to the ACL.
+    ///
+    /// Resource attribute ACEs belong in a SACL and are written with `SecurityInformation::ATTRIBUTE`,
+    /// see [`claims`](crate::claims). An ACL should hold at most one ACE per attribute name.
+    ///
+    /// # Arguments
+    ///
+    /// * `attribute` - The resource attribute.
+    /// * `flags` - The inheritance flags of the ACE.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the attribute is invalid (see [`ResourceAttribute::to_sddl`]) or the
+    /// ACE cannot be added (e.g., insufficient space in the ACL).
+    pub fn add_resource_attribute(&mut self, attribute: &ResourceAttribute, flags: AceFlags) -> Result<(), WinError> {
+        // the system encodes the attribute, the trustee of resource attribute ACEs is always Everyone
+        let ace = format!("(RA;{};;;;WD;{})", flags.to_sddl(), attribute.to_sddl()?);
+        let sd = SecurityDescriptor::from_sd_string(format!("S:{}", ace))?;
+        let bytes = sd
+            .sacl_unchecked()
+            .and_then(|sacl| sacl.into_iter().next().map(|ace| ace.as_bytes().to_vec()))
+            .ok_or_else(|| {
+                WinError::new(
+                    ERROR_INVALID_PARAMETER,
+                    Some(format!("Invalid resource attribute: {}", ace)),
+                )
+            })?;
+        self.push_ace(&bytes)
+    }
+
+    /// Adds a scoped policy ID ACE to the ACL, binding the object to a central access policy.
+    ///
+    /// Scoped policy ID ACEs belong in a SACL and are written with `SecurityInformation::SCOPE`.
+    /// The policy is only enforced on machines it is applied to by group policy, see
+    /// [`claims::applied_capids`](crate::claims::applied_capids). An ACL should hold at most
+    /// one scoped policy ID ACE.
+    ///
+    /// # Arguments
+    ///
+    /// * `capid` - The ID of the central access policy (`S-1-17-*`).
+    /// * `flags` - The inheritance flags of the ACE.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ACE cannot be added (e.g., insufficient space in the ACL).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{
+    ///     acl::{AceFlags, Acl},
+    ///     claims,
+    ///     sd::{SecurityDescriptorBuilder, SecurityInformation},
+    /// };
+    ///
+    /// let capid = claims::applied_capids()?.into_iter().next().expect("no central access policy");
+    /// let mut sacl = Acl::new()?;
+    /// sacl.add_scoped_policy_id(&capid, AceFlags::OBJECT_INHERIT | AceFlags::CONTAINER_INHERIT)?;
+    /// let sd = SecurityDescriptorBuilder::new().sacl(sacl).build()?;
+    /// sd.apply_to_path("C:\\data", SecurityInformation::SCOPE)?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn add_scoped_policy_id<'a, S>(&mut self, capid: &'a S, flags: AceFlags) -> Result<(), WinError>
+    where
+        S: AsSidRef<'a>,
+    {
+        unsafe {
+            winapi_bool_call!(AddScopedPolicyIDAce(
+                self.ptr,
+                ACL_REVISION,
+                flags.0 as u32,
+                0,
+                capid.as_sid_ref().as_ptr() as _,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Adds a compound access-allowed ACE to the ACL.
+    ///
+    /// A compound ACE grants access to a client only while it is impersonated by the given
+    /// server, e.g., a service acting on behalf of its callers. The ACL revision is raised to
+    /// `ACL_REVISION3` if needed. Compound ACEs have no SDDL form.
+    ///
+    /// # Arguments
+    ///
+    /// * `access_mask` - A bitmask specifying the access rights to grant.
+    /// * `flags` - The inheritance flags of the ACE.
+    /// * `server` - The SID of the server doing the impersonation.
+    /// * `client` - The SID of the impersonated client.
+    ///
+    /// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/secauthz/ace)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ACE cannot be added (e.g., insufficient space in the ACL).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{
+    ///     acl::{AceFlags, Acl},
+    ///     mask::FileAccess,
+    ///     sid::Sid,
+    /// };
+    ///
+    /// let network_service = Sid::from_string("S-1-5-20")?;
+    /// let users = Sid::from_string("S-1-5-32-545")?;
+    /// let mut acl = Acl::new()?;
+    /// acl.allow_compound(FileAccess::READ, AceFlags::empty(), &network_service, &users)?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn allow_compound<'a, 'b, S, C, M>(
+        &mut self,
+        access_mask: M,
+        flags: AceFlags,
+        server: &'a S,
+        client: &'b C,
+    ) -> Result<(), WinError>
+    where
+        S: AsSidRef<'a>,
+        C: AsSidRef<'b>,
+        M: IntoAccessMask,
+    {
+        let server = server.as_sid_ref().as_bytes();
+        let client = client.as_sid_ref().as_bytes();
+        let size = (COMPOUND_ACE_SIDS_OFFSET + server.len() + client.len()) as u16;
+
+        let mut ace = Vec::with_capacity(size as usize);
+        ace.extend_from_slice(&[AceType::AccessAllowedCompound.into(), flags.0]);
+        ace.extend_from_slice(&size.to_le_bytes());
+        ace.extend_from_slice(&access_mask.as_u32().to_le_bytes());
+        ace.extend_from_slice(&COMPOUND_ACE_IMPERSONATION.to_le_bytes());
+        ace.extend_from_slice(&0u16.to_le_bytes());
+        ace.extend_from_slice(server);
+        ace.extend_from_slice(client);
+        self.push_ace(&ace)
+    }
+
+    /// Inserts an access-allowed ACE at the given position of the ACL.
+    ///
+    /// Unlike [`Acl::allow`], which always appends, this allows building canonical ACLs
+    /// (explicit deny ACEs before explicit allow ACEs) or fixing the order of existing ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The zero-based position of the new ACE, at most `ace_count()` (appends).
+    /// * `access_mask` - A bitmask specifying the access rights to grant.
+    /// * `sid_ref` - The SID of the security principal to grant access to.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ERROR_INVALID_PARAMETER` if the index is out of bounds, or an error if the ACE
+    /// cannot be added (e.g., insufficient space in the ACL).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{acl::Acl, mask::FileAccess, sid::Sid};
+    ///
+    /// let mut acl = Acl::new()?;
+    /// acl.allow(FileAccess::READ, &Sid::from_string("S-1-5-32-545")?)?;
+    /// // the deny ACE goes first
+    /// acl.insert_deny(0, FileAccess::WRITE, &Sid::from_string("S-1-5-32-546")?)?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn insert_allow<'a, S, M>(&mut self, index: u32, access_mask: M, sid_ref: &'a S) -> Result<(), WinError>
+    where
+        S: AsSidRef<'a>,
+        M: IntoAccessMask,
+    {
+        let ace = access_ace_bytes(
+            AceType::AccessAllowed,
+            AceFlags::empty(),
+            access_mask.as_u32(),
+            sid_ref.as_sid_ref().as_bytes(),
+        );
+        self.insert_ace(index, &ace)
+    }
+
+    /// Inserts an access-denied ACE at the given position of the ACL.
+    ///
+    /// Unlike [`Acl::deny`], which always appends, this allows putting the deny ACE before the
+    /// allow ACEs, where it is evaluated first.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The zero-based position of the new ACE, at most `ace_count()` (appends).
+    /// * `access_mask` - A bitmask specifying the access rights to deny.
+    /// * `sid_ref` - The SID of the security principal to deny access to.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ERROR_INVALID_PARAMETER` if the index is out of bounds, or an error if the ACE
+    /// cannot be added (e.g., insufficient space in the ACL).
+    pub fn insert_deny<'a, S, M>(&mut self, index: u32, access_mask: M, sid_ref: &'a S) -> Result<(), WinError>
+    where
+        S: AsSidRef<'a>,
+        M: IntoAccessMask,
+    {
+        let ace = access_ace_bytes(
+            AceType::AccessDenied,
+            AceFlags::empty(),
+            access_mask.as_u32(),
+            sid_ref.as_sid_ref().as_bytes(),
+        );
+        self.insert_ace(index, &ace)
+    }
+
+    /// Adds an access-allowed or access-denied ACE at its canonical position (see
+    /// [`Acl::is_canonical`]): a deny ACE after the explicit deny ACEs, an allow ACE after the
+    /// other explicit ACEs, an inherited ACE at the end.
+    ///
+    /// Unlike [`Acl::deny`], which appends and so may be evaluated after an allow ACE granting
+    /// the same access, the deny takes effect. The order of a canonical ACL is kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `ace_type` - `AceType::AccessAllowed` or `AceType::AccessDenied`.
+    /// * `access_mask` - A bitmask specifying the access rights to grant or deny.
+    /// * `flags` - The inheritance flags of the ACE.
+    /// * `sid_ref` - The SID of the security principal.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ERROR_INVALID_PARAMETER` if `ace_type` is neither allowed nor denied, or an error
+    /// if the ACE cannot be added (e.g., insufficient space in the ACL).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{
+    ///     acl::{AceFlags, AceType, Acl},
+    ///     mask::FileAccess,
+    ///     sid::Sid,
+    /// };
+    ///
+    /// let mut acl = Acl::new()?;
+    /// let everyone = Sid::from_string("S-1-1-0")?;
+    /// acl.add_canonical(AceType::AccessAllowed, FileAccess::FULL, AceFlags::empty(), &everyone)?;
+    /// // goes before the allow ACE
+    /// let anonymous = Sid::from_string("S-1-5-7")?;
+    /// acl.add_canonical(AceType::AccessDenied, FileAccess::WRITE, AceFlags::empty(), &anonymous)?;
+    /// assert!(acl.is_canonical()?);
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn add_canonical<'a, S, M>(
+        &mut self,
+        ace_type: AceType,
+        access_mask: M,
+        flags: AceFlags,
+        sid_ref: &'a S,
+    ) -> Result<(), WinError>
+    where
+        S: AsSidRef<'a>,
+        M: IntoAccessMask,
+    {
+        if !matches!(ace_type, AceType::AccessAllowed | AceType::AccessDenied) {
+            return Err(WinError::new(
+                ERROR_INVALID_PARAMETER,
+                Some(format!("Not an access allowed or denied ACE type: {:?}", ace_type)),
+            ));
+        }
+        let ace = access_ace_bytes(ace_type, flags, access_mask.as_u32(), sid_ref.as_sid_ref().as_bytes());
+        let rank = canonical_rank(&Ace {
+            ptr: ace.as_ptr() as _,
+            _phantom: PhantomData,
+        });
+
+        let mut index = 0;
+        for existing in self.try_iter() {
+            if canonical_rank(&existing?) > rank {
+                break;
+            }
+            index += 1;
+        }
+        self.insert_ace(index, &ace)
+    }
+
+    /// Creates an ACL holding the given entries, in order, sized to fit them.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ERROR_INVALID_PARAMETER` if an entry has an unsupported type (see [`AceEntry`]),
+    /// or an error if the ACL cannot be created.
+    pub fn from_entries<'e, I>(entries: I) -> Result<Self, WinError>
+    where
+        I: IntoIterator<Item = &'e AceEntry>,
+    {
+        let aces = entries
+            .into_iter()
+            .map(AceEntry::to_bytes)
+            .collect::<Result<Vec<_>, _>>()?;
+        let sid_max_len = aces
+            .iter()
+            .map(|ace| ace.len() - ACE_HEADER_AND_MASK_SIZE)
+            .max()
+            .unwrap_or(0);
+        let mut acl = Self::with_capacity(aces.len(), sid_max_len)?;
+        if !aces.is_empty() {
+            acl.push_ace(&aces.concat())?;
+        }
+        Ok(acl)
+    }
+
+    /// Appends an entry to the ACL.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ERROR_INVALID_PARAMETER` if the entry has an unsupported type (see [`AceEntry`]),
+    /// or an error if the ACE cannot be added (e.g., insufficient space in the ACL).
+    pub fn push_entry(&mut self, entry: &AceEntry) -> Result<(), WinError> {
+        self.push_ace(&entry.to_bytes()?)
+    }
+
+    /// Appends the raw bytes of an ACE to the ACL.
+    fn push_ace(&mut self, ace: &[u8]) -> Result<(), WinError> {
+        self.insert_ace(self.ace_count(), ace)
+    }
+
+    /// Inserts the raw bytes of an ACE at the given position of the ACL.
+    fn insert_ace(&mut self, index: u32, ace: &[u8]) -> Result<(), WinError> {
+        if index > self.ace_count() {
+            return Err(WinError::new(
+                ERROR_INVALID_PARAMETER,
+                Some(format!("ACE index {} out of bounds ({} ACEs)", index, self.ace_count())),
+            ));
+        }
+        let index = if index == self.ace_count() { MAXDWORD } else { index };
+        let revision = self.revision().max(aces_required_revision(ace));
+        unsafe {
+            winapi_bool_call!(AddAce(
+                self.ptr,
+                revision as u32,
+                index,
+                ace.as_ptr() as _,
+                ace.len() as u32
+            ));
+            (*self.ptr).AclRevision = revision;
+        }
+        Ok(())
+    }
+
+    /// Adds a mandatory label ACE to the ACL.
+    ///
+    /// Label ACEs belong in a SACL and set the integrity level of the object. Writing the label
+    /// with `SecurityInformation::LABEL` requires `WRITE_OWNER` access and, for levels above the
+    /// integrity level of the caller, the *SeRelabelPrivilege* privilege. An ACL should hold at
+    /// most one label ACE.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The integrity level and policy of the label.
+    /// * `flags` - The inheritance flags of the ACE.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ACE cannot be added (e.g., insufficient memory or invalid flags).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{
+    ///     acl::{AceFlags, Acl},
+    ///     integrity::{IntegrityLevel, MandatoryLabel, MandatoryPolicy},
+    ///     sd::{SecurityDescriptorBuilder, SecurityInformation},
+    /// };
+    ///
+    /// // let low integrity (sandboxed) processes write to the directory
+    /// let mut sacl = Acl::new()?;
+    /// sacl.add_mandatory_label(
+    ///     MandatoryLabel::new(IntegrityLevel::LOW, MandatoryPolicy::NO_WRITE_UP),
+    ///     AceFlags::OBJECT_INHERIT | AceFlags::CONTAINER_INHERIT,
+    /// )?;
+    /// let sd = SecurityDescriptorBuilder::new().sacl(sacl).build()?;
+    /// sd.apply_to_path("C:\\sandbox", SecurityInformation::LABEL)?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn add_mandatory_label(&mut self, label: MandatoryLabel, flags: AceFlags) -> Result<(), WinError> {
+        let sid = label.level().to_sid()?;
+        unsafe {
+            winapi_bool_call!(AddMandatoryAce(
+                self.ptr,
+                ACL_REVISION,
+                flags.0 as u32,
+                label.policy().0,
+                sid.as_sid_ref().as_ptr() as _,
+            ))
+        };
+        Ok(())
+    }
+
+    /// Adds an access-allowed object ACE to the ACL.
+    ///
+    /// Object ACEs scope the granted rights of a directory service object (`SE_DS_OBJECT`) to an
+    /// attribute, property set, validated write or extended right (`object_type`) and/or limit
+    /// which child object classes inherit the ACE (`inherited_object_type`). The ACL revision is
+    /// raised to `ACL_REVISION_DS` if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `access_mask` - A bitmask specifying the access rights to grant.
+    /// * `flags` - The inheritance flags of the ACE.
+    /// * `object_type` - The GUID of the schema element the rights apply to, `None` for the whole object.
+    /// * `inherited_object_type` - The GUID of the object class inheriting the ACE, `None` for all classes.
+    /// * `sid_ref` - The SID of the security principal to grant access to.
+    ///
+    /// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-addaccessallowedobjectace)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ACE cannot be added (e.g., insufficient memory or invalid flags).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{
+    ///     acl::{AceFlags, Acl},
+    ///     guid::Guid,
+    ///     mask::AccessMask,
+    ///     sid::Sid,
+    /// };
+    ///
+    /// // delegate "Reset Password" on the user objects below an OU to a help desk group
+    /// const CONTROL_ACCESS: AccessMask = AccessMask(0x100);
+    /// let reset_password: Guid = "00299570-246d-11d0-a768-00aa006e0529".parse()?;
+    /// let user_class: Guid = "bf967aba-0de6-11d0-a285-00aa003049e2".parse()?;
+    /// let helpdesk = Sid::from_account_name("CONTOSO\\Helpdesk")?;
+    ///
+    /// let mut dacl = Acl::new()?;
+    /// dacl.allow_object(
+    ///     CONTROL_ACCESS,
+    ///     AceFlags::CONTAINER_INHERIT | AceFlags::INHERIT_ONLY,
+    ///     Some(reset_password),
+    ///     Some(user_class),
+    ///     &helpdesk,
+    /// )?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn allow_object<'a, S, M>(
+        &mut self,
+        access_mask: M,
+        flags: AceFlags,
+        object_type: Option<Guid>,
+        inherited_object_type: Option<Guid>,
+        sid_ref: &'a S,
+    ) -> Result<(), WinError>
+    where
+        S: AsSidRef<'a>,
+        M: IntoAccessMask,
+    {
+        let object_type = object_type.map(GUID::from);
+        let inherited_object_type = inherited_object_type.map(GUID::from);
+        unsafe {
+            winapi_bool_call!(AddAccessAllowedObjectAce(
+                self.ptr,
+                ACL_REVISION_DS,
+                flags.0 as u32,
+                access_mask.as_u32(),
+                object_type.as_ref().map_or(null(), |guid| guid as *const GUID),
+                inherited_object_type
+                    .as_ref()
+                    .map_or(null(), |guid| guid as *const GUID),
+                sid_ref.as_sid_ref().as_ptr() as _,
+            ))
+        };
+        Ok(())
+    }
+
+    /// Adds an access-denied object ACE to the ACL.
+    ///
+    /// See [`Acl::allow_object`] for the meaning of the object type GUIDs.
+    ///
+    /// # Arguments
+    ///
+    /// * `access_mask` - A bitmask specifying the access rights to deny.
+    /// * `flags` - The inheritance flags of the ACE.
+    /// * `object_type` - The GUID of the schema element the rights apply to, `None` for the whole object.
+    /// * `inherited_object_type` - The GUID of the object class inheriting the ACE, `None` for all classes.
+    /// * `sid_ref` - The SID of the security principal to deny access to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ACE cannot be added (e.g., insufficient memory or invalid flags).
+    pub fn deny_object<'a, S, M>(
+        &mut self,
+        access_mask: M,
+        flags: AceFlags,
+        object_type: Option<Guid>,
+        inherited_object_type: Option<Guid>,
+        sid_ref: &'a S,
+    ) -> Result<(), WinError>
+    where
+        S: AsSidRef<'a>,
+        M: IntoAccessMask,
+    {
+        let object_type = object_type.map(GUID::from);
+        let inherited_object_type = inherited_object_type.map(GUID::from);
+        unsafe {
+            winapi_bool_call!(AddAccessDeniedObjectAce(
+                self.ptr,
+                ACL_REVISION_DS,
+                flags.0 as u32,
+                access_mask.as_u32(),
+                object_type.as_ref().map_or(null(), |guid| guid as *const GUID),
+                inherited_object_type
+                    .as_ref()
+                    .map_or(null(), |guid| guid as *const GUID),
+                sid_ref.as_sid_ref().as_ptr() as _,
+            ))
+        };
+        Ok(())
+    }
+
+    /// Returns an ACL holding a copy of a single ACE.
+    fn from_ace(ace: &[u8]) -> Result<Acl, WinError> {
+        let size = size_of::<ACL>() + ace.len();
+        let ptr = unsafe { LocalAlloc(LMEM_FIXED, size) as *mut ACL };
+        if ptr.is_null() {
+            return Err(ERROR_OUTOFMEMORY.into());
+        }
+        let acl = Self { ptr, owned: true };
+        unsafe {
+            winapi_bool_call!(InitializeAcl(ptr, size as u32, ACL_REVISION_DS));
+            winapi_bool_call!(AddAce(
+                ptr,
+                ACL_REVISION_DS,
+                MAXDWORD,
+                ace.as_ptr() as _,
+                ace.len() as u32
+            ));
+        }
+        Ok(acl)
+    }
+
+    /// Returns a copy of the ACL with room for `extra_bytes` more bytes of ACEs.
+    pub(crate) fn copy_with_extra(&self, extra_bytes: usize) -> Result<Acl, WinError> {
+        let info = self.size_information()?;
+        let revision = unsafe { (*self.ptr).AclRevision } as u32;
+        let size = info.AclBytesInUse as usize + extra_bytes;
+
+        let ptr = unsafe { LocalAlloc(LMEM_FIXED, size) as *mut ACL };
+        if ptr.is_null() {
+            return Err(ERROR_OUTOFMEMORY.into());
+        }
+        let copy = Self { ptr, owned: true };
+
+        unsafe {
+            winapi_bool_call!(InitializeAcl(ptr, size as u32, revision));
+
+            // the ACEs are stored contiguously right after the ACL header
+            let aces_len = info.AclBytesInUse as usize - size_of::<ACL>();
+            if aces_len > 0 {
+                winapi_bool_call!(AddAce(
+                    ptr,
+                    revision,
+                    MAXDWORD,
+                    (self.ptr as *const u8).add(size_of::<ACL>()) as _,
+                    aces_len as u32,
+                ));
+            }
+        }
+        Ok(copy)
+    }
+
+    /// Returns a copy of the ACL holding only explicit ACEs.
+    ///
+    /// Inherited ACEs are either turned into explicit ones (`copy_inherited`) or dropped.
+    pub(crate) fn explicit_copy(&self, copy_inherited: bool) -> Result<Acl, WinError> {
+        let info = self.size_information()?;
+        let revision = unsafe { (*self.ptr).AclRevision } as u32;
+        let size = info.AclBytesInUse as usize;
+
+        let ptr = unsafe { LocalAlloc(LMEM_FIXED, size) as *mut ACL };
+        if ptr.is_null() {
+            return Err(ERROR_OUTOFMEMORY.into());
+        }
+        let copy = Self { ptr, owned: true };
+        unsafe { winapi_bool_call!(InitializeAcl(ptr, size as u32, revision)) };
+
+        for index in 0..self.ace_count() {
+            let ace_ptr = self.ace_ptr(index)?;
+            let header = unsafe { &*(ace_ptr as *const ACE_HEADER) };
+            let inherited = header.AceFlags as u32 & INHERITED_ACE != 0;
+            if inherited && !copy_inherited {
+                continue;
+            }
+
+            let mut ace = unsafe { std::slice::from_raw_parts(ace_ptr as *const u8, header.AceSize as usize) }.to_vec();
+            // AceFlags is the second byte of the header
+            ace[1] &= !(INHERITED_ACE as u8);
+            unsafe { winapi_bool_call!(AddAce(ptr, revision, MAXDWORD, ace.as_ptr() as _, ace.len() as u32)) };
+        }
+        Ok(copy)
+    }
+
+    /// Returns a copy of the ACL auditing `access_mask` for `sid_ref`.
+    ///
+    /// If an explicit audit ACE for the same SID with the same flags exists, the mask is merged
+    /// into it, otherwise a new audit ACE is appended.
+    pub(crate) fn with_merged_audit<'a, S>(
+        &self,
+        access_mask: u32,
+        sid_ref: &'a S,
+        inheritance_flags: u32,
+        success: bool,
+        failure: bool,
+    ) -> Result<Acl, WinError>
+    where
+        S: AsSidRef<'a>,
+    {
+        let sid_ref = sid_ref.as_sid_ref();
+        let sid = Sid::from_bytes(sid_ref.as_bytes())?;
+
+        let mut ace_flags = inheritance_flags;
+        if success {
+            ace_flags |= SUCCESSFUL_ACCESS_ACE_FLAG;
+        }
+        if failure {
+            ace_flags |= FAILED_ACCESS_ACE_FLAG;
+        }
+
+        let acl = self.copy_with_extra(size_of::<ACCESS_ALLOWED_ACE>() + sid.len())?;
+        for index in 0..acl.ace_count() {
+            let ace = Ace {
+                ptr: acl.ace_ptr(index)?,
+                _phantom: PhantomData,
+            };
+            let header = unsafe { &*(ace.ptr as *const ACE_HEADER) };
+            if ace.ace_type() == AceType::SystemAudit
+                && header.AceFlags as u32 & INHERITED_ACE == 0
+                && header.AceFlags as u32 == ace_flags
+                && ace.sid()? == sid
+            {
+                let mask_ptr = unsafe { (ace.ptr as *mut u8).add(size_of::<ACE_HEADER>()) as *mut u32 };
+                unsafe { *mask_ptr |= access_mask };
+                return Ok(acl);
+            }
+        }
+
+        unsafe {
+            winapi_bool_call!(AddAuditAccessAceEx(
+                acl.ptr,
+                acl.revision() as u32,
+                inheritance_flags,
+                access_mask,
+                sid_ref.as_ptr() as _,
+                success as _,
+                failure as _,
+            ))
+        };
+        Ok(acl)
+    }
+
+    /// Removes the ACE at the given index.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The zero-based index of the ACE to remove. Must be less than `ace_count()`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the index is out of bounds or if the ACE cannot be removed.
+    ///
+    /// # Panics
+    ///
+    /// This function does not panic, but passing an invalid index will result in an error.
+    pub fn remove_ace(&mut self, index: u32) -> Result<(), WinError> {
+        unsafe {
+            winapi_bool_call!(DeleteAce(self.ptr, index));
+        }
+        Ok(())
+    }
+
+    /// Removes all ACEs for which the predicate returns `false`, keeping the order of the others.
+    ///
+    /// The predicate is called once per ACE, in order.
+    ///
+    /// # Arguments
+    ///
+    /// * `keep` - Returns `true` for the ACEs to keep.
+    ///
+    /// # Returns
+    ///
+    /// The number of removed ACEs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an ACE cannot be read or removed. The ACEs visited before the failure
+    /// have already been removed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{acl::Acl, mask::FileAccess, sid::Sid};
+    ///
+    /// let mut acl = Acl::new()?;
+    /// acl.allow(FileAccess::FULL, &Sid::from_string("S-1-5-18")?)?;
+    /// acl.deny(FileAccess::WRITE, &Sid::from_string("S-1-5-32-546")?)?;
+    /// // keep only the allow ACEs
+    /// let removed = acl.retain(|ace| ace.ace_type().is_allow())?;
+    /// assert_eq!(removed, 1);
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn retain<F>(&mut self, mut keep: F) -> Result<u32, WinError>
+    where
+        F: FnMut(&Ace<'_>) -> bool,
+    {
+        let mut index = 0;
+        let mut removed = 0;
+        while index < self.ace_count() {
+            let ace = Ace {
+                ptr: self.ace_ptr(index)?,
+                _phantom: PhantomData,
+            };
+            if keep(&ace) {
+                index += 1;
+            } else {
+                self.remove_ace(index)?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Returns `true` if the ACEs are in canonical order: explicit access-denied ACEs, then the
+    /// other explicit ACEs, then the inherited ACEs.
+    ///
+    /// Windows evaluates ACEs in order, so an ACL in another order may grant access an explicit
+    /// deny ACE was meant to block; Explorer and other tools refuse to edit such ACLs.
+    ///
+    /// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/secauthz/order-of-aces-in-a-dacl)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an ACE cannot be read.
+    pub fn is_canonical(&self) -> Result<bool, WinError> {
+        let mut last = 0;
+        for ace in self.try_iter() {
+            let rank = canonical_rank(&ace?);
+            if rank < last {
+                return Ok(false);
+            }
+            last = rank;
+        }
+        Ok(true)
+    }
+
+    /// Checks the ACL and reports the problems found.
+    ///
+    /// Unlike [`Acl::is_valid`], which only answers whether Windows accepts the ACL, this walks
+    /// the raw ACEs and names each problem: an unknown revision, ACE types the revision does not
+    /// allow, truncated ACEs, non-canonical ordering and contradicting inheritance flags.
+    ///
+    /// # Returns
+    ///
+    /// The problems in ACL order, empty if none was found.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{acl::{AclIssue, Acl}, mask::FileAccess, sid::Sid};
+    ///
+    /// let mut acl = Acl::new()?;
+    /// acl.allow(FileAccess::READ, &Sid::from_string("S-1-5-32-545")?)?;
+    /// acl.deny(FileAccess::WRITE, &Sid::from_string("S-1-5-32-546")?)?;
+    /// assert_eq!(acl.validate(), vec![AclIssue::NotCanonical { index: 1 }]);
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn validate(&self) -> Vec<AclIssue> {
+        let mut issues = Vec::new();
+        let header = unsafe { &*self.ptr };
+        let revision = header.AclRevision;
+        if !(ACL_REVISION..=ACL_REVISION_DS).contains(&(revision as u32)) {
+            issues.push(AclIssue::UnknownRevision(revision));
+        }
+
+        let size = header.AclSize as usize;
+        let mut offset = size_of::<ACL>();
+        let mut last_rank = 0;
+        for index in 0..header.AceCount as u32 {
+            if offset + size_of::<ACE_HEADER>() > size {
+                issues.push(AclIssue::Truncated { index });
+                break;
+            }
+            let ace_ptr = unsafe { (self.ptr as *const u8).add(offset) };
+            let ace_header = unsafe { std::ptr::read_unaligned(ace_ptr as *const ACE_HEADER) };
+            let ace_size = ace_header.AceSize as usize;
+            if ace_size < ACE_HEADER_AND_MASK_SIZE || offset + ace_size > size {
+                issues.push(AclIssue::Truncated { index });
+                break;
+            }
+            let ace = Ace {
+                ptr: ace_ptr as _,
+                _phantom: PhantomData,
+            };
+            let ace_type = ace.ace_type();
+            if AceEntry::is_supported(ace_type) {
+                // the SID follows the mask; its length depends on the sub-authority count
+                let sid = &ace.as_bytes()[ACE_HEADER_AND_MASK_SIZE..];
+                if sid.len() < 8 || sid.len() < 8 + 4 * sid[1] as usize {
+                    issues.push(AclIssue::Truncated { index });
+                    break;
+                }
+            }
+
+            let required = required_revision(ace_type);
+            if revision < required {
+                issues.push(AclIssue::RevisionTooLow {
+                    index,
+                    ace_type,
+                    required,
+                });
+            }
+
+            let rank = canonical_rank(&ace);
+            if rank < last_rank {
+                issues.push(AclIssue::NotCanonical { index });
+            } else {
+                last_rank = rank;
+            }
+
+            let flags = ace.flags();
+            let inheritable = (flags & (AceFlags::OBJECT_INHERIT | AceFlags::CONTAINER_INHERIT)) != AceFlags::empty();
+            if !inheritable
+                && (flags.contains(AceFlags::INHERIT_ONLY) || flags.contains(AceFlags::NO_PROPAGATE_INHERIT))
+            {
+                issues.push(AclIssue::InconsistentInheritance { index, flags });
+            }
+
+            offset += ace_size;
+        }
+        issues
+    }
+
+    /// Reorders the ACEs into canonical order (see [`Acl::is_canonical`]).
+    ///
+    /// The order within each group is kept, in particular the inherited ACEs stay in the order
+    /// they were inherited in.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an ACE cannot be read or the ACL cannot be rewritten.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{acl::Acl, mask::FileAccess, sid::Sid};
+    ///
+    /// let mut acl = Acl::new()?;
+    /// acl.allow(FileAccess::READ, &Sid::from_string("S-1-5-32-545")?)?;
+    /// acl.deny(FileAccess::WRITE, &Sid::from_string("S-1-5-32-546")?)?;
+    /// assert!(!acl.is_canonical()?);
+    /// acl.reorder_canonical()?;
+    /// assert!(acl.is_canonical()?);
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn reorder_canonical(&mut self) -> Result<(), WinError> {
+        if self.is_canonical()? {
+            return Ok(());
+        }
+        let mut aces = self
+            .try_iter()
+            .map(|ace| ace.map(|ace| (canonical_rank(&ace), ace.as_bytes().to_vec())))
+            .collect::<Result<Vec<_>, _>>()?;
+        aces.sort_by_key(|(rank, _)| *rank);
+        let bytes = aces.into_iter().flat_map(|(_, bytes)| bytes).collect::<Vec<_>>();
+        self.replace_aces(&bytes)
+    }
+
+    /// Merges ACEs of the same type, flags and SID into one ACE with the combined access mask
+    /// and removes exact duplicates.
+    ///
+    /// Only consecutive ACEs of the same type are merged (a run of allow ACEs, a run of deny
+    /// ACEs), so the access granted by the ACL does not change even if it is not canonical.
+    /// Allowed, denied and audit ACEs are merged; other ACE types are only deduplicated.
+    ///
+    /// # Returns
+    ///
+    /// The number of removed ACEs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an ACE cannot be read or the ACL cannot be rewritten.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{
+    ///     acl::Acl,
+    ///     mask::{FileAccess, Mask},
+    ///     sid::Sid,
+    /// };
+    ///
+    /// let users = Sid::from_string("S-1-5-32-545")?;
+    /// let mut acl = Acl::new()?;
+    /// acl.allow(FileAccess::READ, &users)?;
+    /// acl.allow(FileAccess::EXECUTE, &users)?;
+    /// acl.allow(FileAccess::READ, &users)?;
+    /// assert_eq!(acl.normalize()?, 2);
+    /// assert_eq!(acl.get(0).unwrap().mask(), (FileAccess::READ | FileAccess::EXECUTE).as_u32());
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn normalize(&mut self) -> Result<u32, WinError> {
+        let mut aces: Vec<Vec<u8>> = Vec::new();
+        let mut seen = BTreeSet::new();
+        let mut run: BTreeMap<(u8, Vec<u8>), usize> = BTreeMap::new();
+        let mut run_type = None;
+        let mut removed = 0;
+
+        for ace in self.try_iter() {
+            let ace = ace?;
+            let bytes = ace.as_bytes();
+            if !seen.insert(bytes) {
+                removed += 1;
+                continue;
+            }
+            let ace_type = ace.ace_type();
+            if run_type != Some(ace_type) {
+                run.clear();
+                run_type = Some(ace_type);
+            }
+            if !matches!(
+                ace_type,
+                AceType::AccessAllowed | AceType::AccessDenied | AceType::SystemAudit
+            ) {
+                aces.push(bytes.to_vec());
+                continue;
+            }
+            let key = (ace.header_flags(), ace.sid()?.to_vec());
+            match run.get(&key) {
+                Some(&index) => {
+                    let mask = ace.mask() | u32::from_le_bytes(aces[index][4..8].try_into().unwrap());
+                    aces[index][4..8].copy_from_slice(&mask.to_le_bytes());
+                    removed += 1;
+                }
+                None => {
+                    run.insert(key, aces.len());
+                    aces.push(bytes.to_vec());
+                }
+            }
+        }
+        if removed > 0 {
+            self.replace_aces(&aces.concat())?;
+        }
+        Ok(removed)
+    }
+
+    /// Replaces all ACEs of the ACL with the given list of ACEs, which must not be larger than
+    /// the ACL.
+    fn replace_aces(&mut self, aces: &[u8]) -> Result<(), WinError> {
+        for index in (0..self.ace_count()).rev() {
+            self.remove_ace(index)?;
+        }
+        if aces.is_empty() {
+            return Ok(());
+        }
+        self.push_ace(aces)
+    }
+
+    /// Removes all ACEs of a security principal (allowed, denied, audit and object ACEs alike),
+    /// revoking every permission the ACL grants or denies it explicitly.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid_ref` - The SID of the security principal.
+    ///
+    /// # Returns
+    ///
+    /// The number of removed ACEs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an ACE cannot be read or removed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{acl::Acl, mask::FileAccess, sid::Sid};
+    ///
+    /// let mut acl = Acl::new()?;
+    /// let guests = Sid::from_string("S-1-5-32-546")?;
+    /// acl.allow(FileAccess::FULL, &guests)?;
+    /// assert_eq!(acl.remove_entries_for(&guests)?, 1);
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn remove_entries_for<'a, S>(&mut self, sid_ref: &'a S) -> Result<u32, WinError>
+    where
+        S: AsSidRef<'a>,
+    {
+        let sid = sid_ref.as_sid_ref();
+        self.retain(|ace| ace.sid().map_or(true, |ace_sid| ace_sid.as_bytes() != sid.as_bytes()))
+    }
+}
+
+/// The differences between two ACLs, computed by [`diff`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AclDiff {
+    /// The entries present only in the second ACL.
+    pub added: Vec<DiffEntry>,
+    /// The entries present only in the first ACL.
+    pub removed: Vec<DiffEntry>,
+    /// The entries present in both ACLs with different access masks.
+    pub modified: Vec<MaskChange>,
+}
+
+impl AclDiff {
+    /// Returns `true` if the ACLs do not differ.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Compares two ACLs.
+///
+/// Entries are matched by everything but their access mask: trustee, ACE type, ACE flags, object
+/// types and application data (e.g., the condition). Multiple ACEs with the same key are
+/// combined, so the order of the ACEs does not matter. Entries are reported in the order of
+/// their keys. See [`sd::diff`](crate::sd::diff) to also compare owners and groups.
+///
+/// # Arguments
+///
+/// * `a` - The first (e.g., baseline) ACL.
+/// * `b` - The second (e.g., current) ACL.
+///
+/// # Errors
+///
+/// Returns an error if an ACL or one of its ACEs cannot be read.
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::{acl, sd::SecurityDescriptor};
+///
+/// let baseline: SecurityDescriptor = "D:(A;;FA;;;SY)(A;;FR;;;BU)".parse()?;
+/// let current = SecurityDescriptor::from_path("C:\\data\\reports")?;
+/// if let (Some(before), Some(after)) = (baseline.dacl(), current.dacl()) {
+///     for entry in acl::diff(&before, &after)?.added {
+///         println!("added: {} {:?} 0x{:X}", entry.trustee, entry.ace_type, entry.mask);
+///     }
+/// }
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+pub fn diff(a: &Acl, b: &Acl) -> Result<AclDiff, WinError> {
+    diff_optional(Some(a), Some(b))
+}
+
+/// Compares two ACLs, a missing ACL having no entries.
+pub(crate) fn diff_optional(a: Option<&Acl>, b: Option<&Acl>) -> Result<AclDiff, WinError> {
+    let mut diff = AclDiff::default();
+    let mut before = diff_entries(a)?;
+    for (key, after) in diff_entries(b)? {
+        match before.remove(&key) {
+            None => diff.added.push(after),
+            Some(before) if before.mask != after.mask => diff.modified.push(MaskChange {
+                trustee: after.trustee,
+                ace_type: after.ace_type,
+                flags: after.flags,
+                object_type: after.object_type,
+                inherited_object_type: after.inherited_object_type,
+                application_data: after.application_data,
+                before: before.mask,
+                after: after.mask,
+            }),
+            Some(_) => {}
+        }
+    }
+    diff.removed.extend(before.into_values());
+    Ok(diff)
+}
+
+/// The key ACL entries are matched by: the SID bytes of the trustee, the ACE type, the ACE flags
+/// and, except for the basic ACE types, all bytes following the mask (the object types, the
+/// application data, ...).
+type EntryKey = (Vec<u8>, AceType, u8, Vec<u8>);
+
+/// Returns the entries of an ACL keyed by everything but their mask, combining the masks of equal keys.
+fn diff_entries(acl: Option<&Acl>) -> Result<BTreeMap<EntryKey, DiffEntry>, WinError> {
+    let mut entries = BTreeMap::new();
+    let Some(acl) = acl else {
+        return Ok(entries);
+    };
+    for ace in acl.try_iter() {
+        let ace = ace?;
+        let trustee = ace.sid()?;
+        let rest = match ace.ace_type() {
+            // padding after the SID does not change the meaning of these
+            AceType::AccessAllowed | AceType::AccessDenied | AceType::SystemAudit => Vec::new(),
+            _ => ace.as_bytes()[ACE_HEADER_AND_MASK_SIZE..].to_vec(),
+        };
+        let key = (trustee.to_vec(), ace.ace_type(), ace.header_flags(), rest);
+        entries
+            .entry(key)
+            .or_insert_with(|| DiffEntry {
+                trustee,
+                ace_type: ace.ace_type(),
+                flags: ace.header_flags(),
+                object_type: ace.object_type(),
+                inherited_object_type: ace.inherited_object_type(),
+                application_data: ace.application_data().map(<[u8]>::to_vec),
+                mask: 0,
+            })
+            .mask |= ace.mask();
+    }
+    Ok(entries)
+}
+
+/// Returns the group of an ACE in canonical order: explicit deny ACEs, other explicit ACEs,
+/// inherited ACEs.
+fn canonical_rank(ace: &Ace<'_>) -> u8 {
+    if ace.is_inherited() {
+        2
+    } else if ace.ace_type().is_deny() {
+        0
+    } else {
+        1
+    }
+}
+
+/// Returns the lowest ACL revision allowing ACEs of the given type.
+fn required_revision(ace_type: AceType) -> u8 {
+    if ace_type.is_object() {
+        ACL_REVISION_DS as u8
+    } else if ace_type == AceType::AccessAllowedCompound {
+        ACL_REVISION3 as u8
+    } else {
+        ACL_REVISION as u8
+    }
+}
+
+/// Returns the lowest ACL revision allowing all ACEs of a buffer of contiguous ACEs.
+fn aces_required_revision(aces: &[u8]) -> u8 {
+    let mut revision = ACL_REVISION as u8;
+    let mut offset = 0;
+    while offset + size_of::<ACE_HEADER>() <= aces.len() {
+        revision = revision.max(required_revision(AceType::from(aces[offset])));
+        let size = u16::from_le_bytes([aces[offset + 2], aces[offset + 3]]) as usize;
+        if size == 0 {
+            break;
+        }
+        offset += size;
+    }
+    revision
+}
+
+/// The size of the fixed part of an ACE with the layout of `ACCESS_ALLOWED_ACE`.
+const ACE_HEADER_AND_MASK_SIZE: usize = size_of::<ACE_HEADER>() + size_of::<u32>();
+
+/// The offset of the server SID of a compound ACE: after the header, the mask, the compound
+/// ACE type and a reserved field.
+const COMPOUND_ACE_SIDS_OFFSET: usize = ACE_HEADER_AND_MASK_SIZE + 2 * size_of::<u16>();
+
+/// The only compound ACE type, granting access to a client impersonated by a server.
+const COMPOUND_ACE_IMPERSONATION: u16 = 1;
+
+/// Encodes an ACE with the layout of `ACCESS_ALLOWED_ACE`: the header, the mask and the SID.
+fn access_ace_bytes(ace_type: AceType, flags: AceFlags, mask: u32, sid: &[u8]) -> Vec<u8> {
+    let size = (ACE_HEADER_AND_MASK_SIZE + sid.len()) as u16;
+    let mut ace = Vec::with_capacity(size as usize);
+    ace.extend_from_slice(&[ace_type.into(), flags.0]);
+    ace.extend_from_slice(&size.to_le_bytes());
+    ace.extend_from_slice(&mask.to_le_bytes());
+    ace.extend_from_slice(sid);
+    ace
+}
+
+impl<'a> AclIndex<'a> {
+    /// Builds an index over the ACEs of `acl`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ACL or one of its ACEs cannot be read.
+    pub fn new(acl: &'a Acl) -> Result<Self, WinError> {
+        let mut by_sid: BTreeMap<Vec<u8>, Vec<*const c_void>> = BTreeMap::new();
+        let mut by_type: BTreeMap<AceType, Vec<*const c_void>> = BTreeMap::new();
+
+        for ace in acl.try_iter() {
+            let ace = ace?;
+            by_sid.entry(ace.sid()?.to_vec()).or_default().push(ace.ptr);
+            by_type.entry(ace.ace_type()).or_default().push(ace.ptr);
+        }
+
+        Ok(Self { acl, by_sid, by_type })
+    }
+
+    /// Returns the indexed ACL.
+    pub fn acl(&self) -> &'a Acl {
+        self.acl
+    }
+
+    /// Returns the ACEs applying to a security principal.
+    pub fn by_sid<'s, S>(&self, sid: &'s S) -> impl Iterator<Item = Ace<'a>> + '_
+    where
+        S: AsSidRef<'s>,
+    {
+        Self::aces(self.by_sid.get(sid.as_sid_ref().as_bytes()))
+    }
+
+    /// Returns the ACEs of a type.
+    pub fn by_type(&self, ace_type: AceType) -> impl Iterator<Item = Ace<'a>> + '_ {
+        Self::aces(self.by_type.get(&ace_type))
+    }
+
+    /// Returns the combined access mask of the ACEs of a type applying to a security principal.
+    ///
+    /// For example, the mask of the `AccessAllowed` ACEs is everything explicitly granted
+    /// to the principal itself (ignoring group memberships).
+    pub fn mask_for<'s, S>(&self, sid: &'s S, ace_type: AceType) -> u32
+    where
+        S: AsSidRef<'s>,
+    {
+        self.by_sid(sid)
+            .filter(|ace| ace.ace_type() == ace_type)
+            .fold(0, |mask, ace| mask | ace.mask())
+    }
+
+    /// Returns the number of distinct security principals referenced by the ACL.
+    pub fn sid_count(&self) -> usize {
+        self.by_sid.len()
+    }
+
+    fn aces(ptrs: Option<&Vec<*const c_void>>) -> impl Iterator<Item = Ace<'a>> + '_ {
+        ptrs.into_iter().flatten().map(|&ptr| Ace {
+            ptr,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<'a> AclIter<'a> {
+    /// Keeps only the explicit ACEs, i.e. those set on the object itself.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sd::SecurityDescriptor;
+    ///
+    /// let sd = SecurityDescriptor::from_path("C:\\data")?;
+    /// if let Some(dacl) = sd.dacl() {
+    ///     println!("{} explicit ACEs", dacl.into_iter().explicit().count());
+    /// }
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn explicit(self) -> impl Iterator<Item = Ace<'a>> {
+        self.filter(|ace| !ace.is_inherited())
+    }
+
+    /// Keeps only the ACEs inherited from the parent object.
+    pub fn inherited(self) -> impl Iterator<Item = Ace<'a>> {
+        self.filter(Ace::is_inherited)
+    }
+}
+
+impl<'a> Iterator for AclIter<'a> {
+    type Item = Ace<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+
+        let Ok(ptr) = self.acl.ace_ptr(self.front) else {
+            self.front = self.back;
+            return None;
+        };
+
+        self.front += 1;
+
+        Some(Ace {
+            ptr,
+            _phantom: PhantomData,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.back - self.front) as usize;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for AclIter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front >= self.back {
+            return None;
+        }
+
+        let Ok(ptr) = self.acl.ace_ptr(self.back - 1) else {
+            self.back = self.front;
+            return None;
+        };
+
+        self.back -= 1;
+
+        Some(Ace {
+            ptr,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl ExactSizeIterator for AclIter<'_> {}
+
+impl FusedIterator for AclIter<'_> {}
+
+impl<'a> Iterator for AclTryIter<'a> {
+    type Item = Result<Ace<'a>, WinError>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        if let Some(error) = self.error.take() {
+            return Some(Err(error));
+        }
         if self.index >= self.count {
             return None;
         }
 
-        let mut ace_ptr: *mut c_void = std::ptr::null_mut();
-        let err = unsafe { GetAce(self.acl.ptr, self.index, &mut ace_ptr) };
-        if err == FALSE {
-            return None;
+        match self.acl.ace_ptr(self.index) {
+            Ok(ptr) => {
+                self.index += 1;
+                Some(Ok(Ace {
+                    ptr,
+                    _phantom: PhantomData,
+                }))
+            }
+            Err(error) => {
+                self.index = self.count;
+                Some(Err(error))
+            }
         }
+    }
+}
 
-        self.index += 1;
-
-        Some(Ace {
-            ptr: ace_ptr.cast(),
+impl AclRef<'_> {
+    /// Creates a reference to the ACL at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to a valid ACL that stays valid and unmodified for the lifetime of
+    /// the reference.
+    pub(crate) unsafe fn from_ptr(ptr: *mut ACL) -> Self {
+        Self {
+            acl: unsafe { Acl::from_ptr(ptr) },
             _phantom: PhantomData,
-        })
+        }
     }
 }
 
-impl<'a> IntoIterator for &'a Acl {
+impl Deref for AclRef<'_> {
+    type Target = Acl;
+
+    fn deref(&self) -> &Acl {
+        &self.acl
+    }
+}
+
+impl Debug for AclRef<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.acl.fmt(f)
+    }
+}
+
+impl<'a> IntoIterator for &'a AclRef<'_> {
     type Item = Ace<'a>;
     type IntoIter = AclIter<'a>;
 
     fn into_iter(self) -> Self::IntoIter {
-        let mut info = ACL_SIZE_INFORMATION {
-            AceCount: 0,
-            AclBytesInUse: 0,
-            AclBytesFree: 0,
-        };
-
-        let err = unsafe {
-            GetAclInformation(
-                self.ptr,
-                &mut info as *mut _ as *mut _,
-                size_of::<ACL_SIZE_INFORMATION>() as u32,
-                AclSizeInformation,
-            )
-        };
+        (&self.acl).into_iter()
+    }
+}
 
-        if err == FALSE {
-            // TODO: this could perhaps be handled better... :/
-            return AclIter {
-                acl: self,
-                index: 0,
-                count: 0,
-            };
-        }
+impl<'a> IntoIterator for &'a Acl {
+    type Item = Ace<'a>;
+    type IntoIter = AclIter<'a>;
 
+    fn into_iter(self) -> Self::IntoIter {
+        // errors are swallowed here, use `Acl::try_iter()` to surface them
         AclIter {
             acl: self,
-            index: 0,
-            count: info.AceCount,
+            front: 0,
+            back: self.ace_count(),
         }
     }
 }
 
+impl Iterator for AclIntoEntries {
+    type Item = Result<AceEntry, WinError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.count {
+            return None;
+        }
+        let ptr = match self.acl.ace_ptr(self.index) {
+            Ok(ptr) => ptr,
+            Err(error) => {
+                self.index = self.count;
+                return Some(Err(error));
+            }
+        };
+        self.index += 1;
+        Some(
+            Ace {
+                ptr,
+                _phantom: PhantomData,
+            }
+            .to_entry(),
+        )
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some((self.count - self.index) as usize))
+    }
+}
+
+impl FusedIterator for AclIntoEntries {}
+
+/// Collects entries into an ACL sized to fit them, like [`Acl::from_entries`].
+///
+/// Fails with `ERROR_INVALID_PARAMETER` if an entry has an unsupported type, or with an error if
+/// the ACL cannot be created.
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::{
+///     acl::{AceEntry, AceFlags, AceType, Acl},
+///     mask::FileAccess,
+///     sid::Sid,
+/// };
+///
+/// let system = Sid::from_string("S-1-5-18")?;
+/// let acl: Acl = [AceEntry::new(AceType::AccessAllowed, AceFlags::empty(), FileAccess::FULL, system)]
+///     .into_iter()
+///     .collect::<Result<_, _>>()?;
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+impl FromIterator<AceEntry> for Result<Acl, WinError> {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = AceEntry>,
+    {
+        let entries: Vec<_> = iter.into_iter().collect();
+        Acl::from_entries(&entries)
+    }
+}
+
 impl<'a> Ace<'a> {
     /// Returns the type of this ACE (allowed, denied, audit, etc.).
     ///
@@ -404,35 +2996,449 @@ impl<'a> Ace<'a> {
     ///
     /// The `AceType` enum variant indicating what type of ACE this is.
     pub fn ace_type(&self) -> AceType {
-        unsafe {
-            let header = &*(self.ptr as *const ACE_HEADER);
-            match header.AceType as u32 {
-                ACCESS_ALLOWED_ACE_TYPE => AceType::AccessAllowed,
-                ACCESS_DENIED_ACE_TYPE => AceType::AccessDenied,
-                SYSTEM_AUDIT_ACE_TYPE => AceType::SystemAudit,
-                unknown => AceType::Unknown(unknown as u8),
-            }
+        unsafe { (*(self.ptr as *const ACE_HEADER)).AceType }.into()
+    }
+
+    /// Copies this ACE into an owned [`AceEntry`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `ERROR_INVALID_PARAMETER` for ACE types carrying more than a mask and a SID
+    /// (object, callback, resource attribute and compound ACEs), see [`Ace::payload`] for those.
+    pub fn to_entry(&self) -> Result<AceEntry, WinError> {
+        AceEntry::try_from(self)
+    }
+
+    /// Decodes the content of this ACE according to its type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ACE is malformed (e.g., a label ACE without a mandatory label SID
+    /// or a malformed resource attribute).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{acl::AcePayload, sd::SecurityDescriptor};
+    ///
+    /// let sd = SecurityDescriptor::from_path("C:\\data")?;
+    /// if let Some(dacl) = sd.dacl() {
+    ///     for ace in &dacl {
+    ///         match ace.payload()? {
+    ///             AcePayload::Access { mask, sid } => println!("{:?}: 0x{:X}", sid, mask),
+    ///             AcePayload::Object { mask, sid, object_type, .. } => {
+    ///                 println!("{:?}: 0x{:X} on {:?}", sid, mask, object_type)
+    ///             }
+    ///             other => println!("{:?}", other),
+    ///         }
+    ///     }
+    /// }
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn payload(&self) -> Result<AcePayload<'a>, WinError> {
+        let ace_type = self.ace_type();
+        if let Some(data) = self.application_data() {
+            return Ok(AcePayload::Callback {
+                mask: self.mask(),
+                object_type: self.object_type(),
+                inherited_object_type: self.inherited_object_type(),
+                sid: self.sid()?,
+                application_data: data,
+            });
+        }
+        if ace_type.is_object() {
+            return Ok(AcePayload::Object {
+                mask: self.mask(),
+                object_type: self.object_type(),
+                inherited_object_type: self.inherited_object_type(),
+                sid: self.sid()?,
+            });
+        }
+        if let Some(label) = self.mandatory_label()? {
+            return Ok(AcePayload::MandatoryLabel(label));
+        }
+        if let Some(attribute) = self.resource_attribute()? {
+            return Ok(AcePayload::ResourceAttribute(attribute));
+        }
+        if let Some(capid) = self.scoped_policy_id()? {
+            return Ok(AcePayload::ScopedPolicyId(capid));
+        }
+        if let Some(client) = self.client_sid()? {
+            return Ok(AcePayload::Compound {
+                mask: self.mask(),
+                server: self.sid()?,
+                client,
+            });
         }
+        match ace_type {
+            AceType::AccessAllowed
+            | AceType::AccessDenied
+            | AceType::SystemAudit
+            | AceType::SystemAlarm
+            | AceType::SystemProcessTrustLabel => Ok(AcePayload::Access {
+                mask: self.mask(),
+                sid: self.sid()?,
+            }),
+            _ => Ok(AcePayload::Other(self.as_bytes())),
+        }
+    }
+
+    /// Returns a wrapper whose `Debug` output also includes the account name of the trustee,
+    /// see [`Sid::debug_resolved`].
+    pub fn debug_resolved(&self) -> impl Debug + '_ {
+        DebugResolved(self)
     }
 
     /// Extracts the SID (Security Identifier) from this ACE.
     ///
-    /// The SID identifies the security principal to which this ACE applies.
+    /// The SID identifies the security principal to which this ACE applies. For compound ACEs
+    /// this is the server SID, see [`Ace::client_sid`] for the client.
     ///
     /// # Returns
     ///
     /// An owned `Sid` containing the security identifier, or an error if the SID cannot be extracted.
     pub fn sid(&self) -> Result<Sid, WinError> {
+        Sid::from_bytes(self.sid_bytes()?)
+    }
+
+    /// Returns the client SID of a compound ACE, the principal impersonated by the server
+    /// returned by [`Ace::sid`].
+    ///
+    /// # Returns
+    ///
+    /// `Some(Sid)` for compound ACEs, or `None` for other ACE types.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the SID cannot be read.
+    pub fn client_sid(&self) -> Result<Option<Sid>, WinError> {
+        if self.ace_type() != AceType::AccessAllowedCompound {
+            return Ok(None);
+        }
+        Sid::from_bytes(self.trailing_data()?).map(Some)
+    }
+
+    /// Returns the GUID of the schema element (attribute, property set, validated write or
+    /// extended right) an object ACE applies to.
+    ///
+    /// # Returns
+    ///
+    /// `Some(Guid)` for object ACEs scoped to a schema element, or `None` for other ACEs.
+    pub fn object_type(&self) -> Option<Guid> {
+        let flags = self.object_flags().ok()??;
+        if flags & ACE_OBJECT_TYPE_PRESENT == 0 {
+            return None;
+        }
+        self.guid_at(Self::OBJECT_GUIDS_OFFSET)
+    }
+
+    /// Returns the GUID of the object class inheriting an object ACE.
+    ///
+    /// # Returns
+    ///
+    /// `Some(Guid)` for object ACEs inherited by a single object class, or `None` for other ACEs.
+    pub fn inherited_object_type(&self) -> Option<Guid> {
+        let flags = self.object_flags().ok()??;
+        if flags & ACE_INHERITED_OBJECT_TYPE_PRESENT == 0 {
+            return None;
+        }
+        let offset = if flags & ACE_OBJECT_TYPE_PRESENT != 0 {
+            Self::OBJECT_GUIDS_OFFSET + size_of::<GUID>()
+        } else {
+            Self::OBJECT_GUIDS_OFFSET
+        };
+        self.guid_at(offset)
+    }
+
+    /// Returns the application data of a callback ACE: the bytes following the SID.
+    ///
+    /// For conditional ACEs this is the binary form of the condition (starting with `artx`),
+    /// see [`Ace::condition`] for its textual form.
+    ///
+    /// # Returns
+    ///
+    /// `Some(bytes)` for callback and access filter ACEs (possibly empty), or `None` for other
+    /// ACE types.
+    pub fn application_data(&self) -> Option<&'a [u8]> {
+        let ace_type = self.ace_type();
+        if !ace_type.is_callback() && ace_type != AceType::SystemAccessFilter {
+            return None;
+        }
+        // a malformed ACE has no application data, its SID fails to decode instead
+        Some(self.trailing_data().unwrap_or_default())
+    }
+
+    /// Decodes this ACE as a resource attribute.
+    ///
+    /// # Returns
+    ///
+    /// `Some(ResourceAttribute)` for resource attribute ACEs, or `None` for other ACE types.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the attribute is malformed.
+    pub fn resource_attribute(&self) -> Result<Option<ResourceAttribute>, WinError> {
+        if self.ace_type() != AceType::SystemResourceAttribute {
+            return Ok(None);
+        }
+        ResourceAttribute::from_relative(self.trailing_data()?).map(Some)
+    }
+
+    /// Returns the central access policy ID (CAPID) of a scoped policy ID ACE.
+    ///
+    /// # Returns
+    ///
+    /// `Some(Sid)` for scoped policy ID ACEs, or `None` for other ACE types.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the SID cannot be extracted.
+    pub fn scoped_policy_id(&self) -> Result<Option<Sid>, WinError> {
+        if self.ace_type() != AceType::SystemScopedPolicyId {
+            return Ok(None);
+        }
+        self.sid().map(Some)
+    }
+
+    /// Renders this ACE in SDDL form, e.g., `(A;OICI;FA;;;WD)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ACE cannot be rendered.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sd::SecurityDescriptor;
+    ///
+    /// let sd = SecurityDescriptor::from_path("C:\\data")?;
+    /// if let Some(dacl) = sd.dacl() {
+    ///     for ace in &dacl {
+    ///         println!("{}", ace.to_sddl()?);
+    ///     }
+    /// }
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn to_sddl(&self) -> Result<String, WinError> {
+        // let the system render a descriptor holding just this ACE: D:(A;;FA;;;WD)
+        let acl = Acl::from_ace(self.as_bytes())?;
+        let builder = SecurityDescriptorBuilder::new();
+        let builder = if self.ace_type().is_system() {
+            builder.sacl(acl)
+        } else {
+            builder.dacl(acl)
+        };
+        let sddl = builder.to_sd_string()?;
+        match sddl.find('(') {
+            Some(start) => Ok(sddl[start..].to_owned()),
+            None => Err(WinError::new(
+                ERROR_INVALID_PARAMETER,
+                Some(format!("Cannot render ACE: {}", sddl)),
+            )),
+        }
+    }
+
+    /// Decodes the condition of a conditional ACE into its SDDL form, e.g.,
+    /// `(Member_of {SID(BA)}) && (@User.Department == "Finance")`.
+    ///
+    /// # Returns
+    ///
+    /// `Some(condition)` for callback ACEs holding a condition, or `None` for other ACEs
+    /// (including callback ACEs with application-specific data).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the condition is malformed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sd::SecurityDescriptor;
+    ///
+    /// let sd = SecurityDescriptor::from_path("C:\\data")?;
+    /// if let Some(dacl) = sd.dacl() {
+    ///     for ace in &dacl {
+    ///         if let Some(condition) = ace.condition()? {
+    ///             println!("{:?}: 0x{:X} if {}", ace.sid()?, ace.mask(), condition);
+    ///         }
+    ///     }
+    /// }
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn condition(&self) -> Result<Option<String>, WinError> {
+        match self.application_data() {
+            Some(data) if data.starts_with(CONDITIONAL_ACE_SIGNATURE) => {}
+            _ => return Ok(None),
+        }
+
+        // the condition is the last field of the ACE: (XA;flags;rights;;;sid;(condition))
+        let sddl = self.to_sddl()?;
+        let malformed = || {
+            WinError::new(
+                ERROR_INVALID_PARAMETER,
+                Some(format!("Malformed conditional ACE: {}", sddl)),
+            )
+        };
+        let ace = sddl
+            .strip_prefix('(')
+            .and_then(|ace| ace.strip_suffix(')'))
+            .ok_or_else(malformed)?;
+        let condition = ace.splitn(7, ';').nth(6).ok_or_else(malformed)?;
+        let condition = condition
+            .strip_prefix('(')
+            .and_then(|c| c.strip_suffix(')'))
+            .unwrap_or(condition);
+        Ok(Some(condition.to_owned()))
+    }
+
+    /// The offset of the first GUID of an object ACE: after the header, the mask and the object flags.
+    const OBJECT_GUIDS_OFFSET: usize = size_of::<ACE_HEADER>() + 2 * size_of::<u32>();
+
+    /// Returns the error for an ACE whose fields extend past its `AceSize`.
+    fn truncated(&self) -> WinError {
+        WinError::new(
+            ERROR_INVALID_ACL,
+            Some(format!(
+                "Truncated {:?} ACE of {} bytes",
+                self.ace_type(),
+                self.as_bytes().len()
+            )),
+        )
+    }
+
+    /// Returns the object flags of an object ACE (which GUIDs are present), `None` for other ACEs.
+    fn object_flags(&self) -> Result<Option<u32>, WinError> {
+        if !self.ace_type().is_object() {
+            return Ok(None);
+        }
+        let offset = size_of::<ACE_HEADER>() + size_of::<u32>();
+        let flags = self
+            .as_bytes()
+            .get(offset..offset + size_of::<u32>())
+            .ok_or_else(|| self.truncated())?;
+        Ok(Some(u32::from_le_bytes([flags[0], flags[1], flags[2], flags[3]])))
+    }
+
+    fn guid_at(&self, offset: usize) -> Option<Guid> {
+        let bytes = self.as_bytes().get(offset..offset + size_of::<GUID>())?;
+        Some(unsafe { (bytes.as_ptr() as *const GUID).read_unaligned() }.into())
+    }
+
+    /// Returns the offset of the SID: after the header and the mask, for object ACEs after the
+    /// object flags and the GUIDs present, and for compound ACEs after the compound ACE type.
+    fn sid_offset(&self) -> Result<usize, WinError> {
+        if self.ace_type() == AceType::AccessAllowedCompound {
+            return Ok(COMPOUND_ACE_SIDS_OFFSET);
+        }
+        Ok(match self.object_flags()? {
+            None => size_of::<ACE_HEADER>() + size_of::<u32>(),
+            Some(flags) => {
+                let guids = [ACE_OBJECT_TYPE_PRESENT, ACE_INHERITED_OBJECT_TYPE_PRESENT]
+                    .iter()
+                    .filter(|&&present| flags & present != 0)
+                    .count();
+                Self::OBJECT_GUIDS_OFFSET + guids * size_of::<GUID>()
+            }
+        })
+    }
+
+    /// Returns the bytes of the SID, checked to lie within the ACE.
+    fn sid_bytes(&self) -> Result<&'a [u8], WinError> {
+        let offset = self.sid_offset()?;
+        let bytes = self.as_bytes().get(offset..).ok_or_else(|| self.truncated())?;
+        // revision, sub-authority count and the 6-byte authority, then the sub-authorities
+        let count = *bytes.get(1).ok_or_else(|| self.truncated())? as usize;
+        bytes
+            .get(..8 + count * size_of::<u32>())
+            .ok_or_else(|| self.truncated())
+    }
+
+    /// Returns the bytes following the SID (application data, resource attribute).
+    fn trailing_data(&self) -> Result<&'a [u8], WinError> {
+        let offset = self.sid_offset()? + self.sid_bytes()?.len();
+        Ok(self.as_bytes().get(offset..).unwrap_or_default())
+    }
+
+    /// Returns the raw bytes of the ACE, header included.
+    pub(crate) fn as_bytes(&self) -> &'a [u8] {
         unsafe {
-            // Calculate offset to SidStart: after ACE_HEADER + Mask (u32)
-            let mask_offset = size_of::<ACE_HEADER>();
-            let sid_offset = mask_offset + size_of::<u32>();
-            let sid_ptr = (self.ptr as *const u8).add(sid_offset) as PSID;
+            let size = (*(self.ptr as *const ACE_HEADER)).AceSize as usize;
+            std::slice::from_raw_parts(self.ptr as *const u8, size)
+        }
+    }
+
+    /// Returns the raw flags of the ACE header (inheritance and audit flags).
+    pub(crate) fn header_flags(&self) -> u8 {
+        unsafe { (*(self.ptr as *const ACE_HEADER)).AceFlags }
+    }
+
+    /// Returns the flags of the ACE header (inheritance and audit flags).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{acl::AceFlags, sd::SecurityDescriptor};
+    ///
+    /// let sd = SecurityDescriptor::from_path("C:\\data")?;
+    /// if let Some(dacl) = sd.dacl() {
+    ///     for ace in &dacl {
+    ///         println!("{:?} inheritable: {}", ace.sid()?, ace.flags().contains(AceFlags::CONTAINER_INHERIT));
+    ///     }
+    /// }
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn flags(&self) -> AceFlags {
+        AceFlags(self.header_flags())
+    }
+
+    /// Returns `true` if the ACE was inherited from the parent object.
+    pub fn is_inherited(&self) -> bool {
+        self.flags().contains(AceFlags::INHERITED)
+    }
+
+    /// Returns `true` if the ACE applies only to the children inheriting it, not to the object itself.
+    pub fn is_inherit_only(&self) -> bool {
+        self.flags().contains(AceFlags::INHERIT_ONLY)
+    }
+
+    /// Returns which access attempts this ACE audits.
+    ///
+    /// # Returns
+    ///
+    /// `Some(AuditOn)` for audit ACEs, or `None` for other ACE types and for audit ACEs
+    /// with neither the success nor the failure flag set.
+    pub fn audit_on(&self) -> Option<AuditOn> {
+        if !self.ace_type().is_audit() {
+            return None;
+        }
+        let flags = self.header_flags() as u32;
+        match (
+            flags & SUCCESSFUL_ACCESS_ACE_FLAG != 0,
+            flags & FAILED_ACCESS_ACE_FLAG != 0,
+        ) {
+            (true, true) => Some(AuditOn::FailureAndSuccess),
+            (true, false) => Some(AuditOn::Success),
+            (false, true) => Some(AuditOn::Failure),
+            (false, false) => None,
+        }
+    }
 
-            let len = GetLengthSid(sid_ptr) as usize;
-            let data = std::slice::from_raw_parts(sid_ptr as *const u8, len).to_vec();
-            Sid::from_bytes(&data)
+    /// Decodes this ACE as a mandatory label.
+    ///
+    /// # Returns
+    ///
+    /// `Some(MandatoryLabel)` for mandatory label ACEs, or `None` for other ACE types.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the SID of a label ACE is not a mandatory label SID (`S-1-16-*`).
+    pub fn mandatory_label(&self) -> Result<Option<MandatoryLabel>, WinError> {
+        if self.ace_type() != AceType::SystemMandatoryLabel {
+            return Ok(None);
         }
+        // a label ACE has the layout of an access allowed ACE: the mask holds the policy
+        let level = IntegrityLevel::from_sid(&self.sid()?)?;
+        Ok(Some(MandatoryLabel::new(level, MandatoryPolicy(self.mask()))))
     }
 
     /// Returns the access mask from this ACE.
@@ -452,17 +3458,53 @@ impl<'a> Ace<'a> {
     }
 }
 
-impl<'a> Debug for Ace<'a> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let account_lookup = match self.sid().map(|sid| sid.lookup_name()) {
-            Ok(Ok(lookup)) => format!("{}/{}", lookup.domain, lookup.name),
-            _ => "<INVALID SID>".to_owned(),
-        };
+impl TryFrom<&[u8]> for Acl {
+    type Error = WinError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_bytes(value)
+    }
+}
+
+/// ACEs are equal when their bytes are equal: the same type, flags, mask, SID and data.
+impl PartialEq for Ace<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
 
-        f.debug_struct("Ace")
-            .field("account_lookup", &account_lookup)
-            .field("mask", &format_args!("{:b}b, 0x{:X}", &self.mask(), &self.mask()))
+impl Eq for Ace<'_> {}
+
+impl<'a> Ace<'a> {
+    fn fmt_debug(&self, f: &mut Formatter<'_>, resolve: bool) -> std::fmt::Result {
+        let sid = self.sid();
+        let mut fmt = f.debug_struct("Ace");
+        match &sid {
+            Ok(sid) => fmt.field("sid", &format_args!("{}", sid)),
+            Err(_) => fmt.field("sid", &"<INVALID SID>"),
+        };
+        if resolve {
+            match sid {
+                Ok(sid) => fmt.field("account_lookup", &debug_account(sid.lookup_name())),
+                Err(_) => fmt.field("account_lookup", &"<INVALID SID>"),
+            };
+        }
+        fmt.field("mask", &format_args!("{:b}b, 0x{:X}", &self.mask(), &self.mask()))
             .field("ace_type", &self.ace_type())
             .finish()
     }
 }
+
+/// The `Debug` output does not look up the account name of the trustee, see
+/// [`Ace::debug_resolved`].
+impl<'a> Debug for Ace<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.fmt_debug(f, false)
+    }
+}
+
+impl Debug for DebugResolved<'_, Ace<'_>> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt_debug(f, true)
+    }
+}
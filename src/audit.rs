@@ -0,0 +1,306 @@
+//! Security audit events.
+//!
+//! Applications that change permissions through this crate can report those changes to the
+//! Security event log, so that permission management is auditable like any other object access.
+//! This module wraps the Authz security event source APIs.
+//!
+//! The process must hold the *SeAuditPrivilege* privilege (granted to `LocalSystem`,
+//! `LocalService` and `NetworkService` by default) and the event source must be installed
+//! (see [`SecurityEventSource::install`]) before events can be reported.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use win_acl_rs::audit::{AuditParam, SecurityEventSource};
+//!
+//! let source = SecurityEventSource::register("MyPermissionTool")?;
+//! source.report(
+//!     4670,
+//!     None,
+//!     true,
+//!     &[AuditParam::String("C:\\data".into()), AuditParam::Ulong(0x1F01FF)],
+//! )?;
+//! # Ok::<(), win_acl_rs::error::WinError>(())
+//! ```
+//!
+//! It also sets up object access auditing in a single call (see [`audit_access`]).
+
+use std::{path::Path, ptr::null_mut};
+
+use windows_sys::Win32::Security::{
+    Authorization::{
+        APF_AuditFailure, APF_AuditSuccess, APT_Sid, APT_String, APT_Ulong, AUDIT_PARAM, AUDIT_PARAM_0, AUDIT_PARAM_1,
+        AUDIT_PARAMS, AUTHZ_SECURITY_EVENT_PROVIDER_HANDLE, AUTHZ_SOURCE_SCHEMA_REGISTRATION,
+        AuthzInstallSecurityEventSource, AuthzRegisterSecurityEventSource, AuthzReportSecurityEventFromParams,
+        AuthzUninstallSecurityEventSource, AuthzUnregisterSecurityEventSource, SE_FILE_OBJECT,
+    },
+    CONTAINER_INHERIT_ACE, OBJECT_INHERIT_ACE,
+};
+
+use crate::{
+    acl::Acl,
+    elevated::{PrivilegeToken, SecurityDescriptorElevated},
+    error::WinError,
+    mask::IntoAccessMask,
+    sd::SecurityInformation,
+    sid::{AsSidRef, Sid, SidRef},
+    utils::WideCString,
+    winapi_bool_call,
+};
+
+/// A parameter of a reported security audit event.
+#[derive(Debug, Clone)]
+pub enum AuditParam {
+    /// A string parameter.
+    String(String),
+    /// A 32-bit unsigned integer parameter.
+    Ulong(u32),
+    /// A SID parameter, rendered as an account name in the event log.
+    Sid(Sid),
+}
+
+/// A registered security event source.
+///
+/// The source is unregistered when this value is dropped.
+#[derive(Debug)]
+pub struct SecurityEventSource {
+    handle: AUTHZ_SECURITY_EVENT_PROVIDER_HANDLE,
+}
+
+impl SecurityEventSource {
+    /// Installs a security event source so it can be registered later.
+    ///
+    /// This is typically done once, at installation time of the application.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the event source.
+    /// * `message_file` - An optional resource file containing the event messages.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the source cannot be installed (e.g., it already exists or
+    /// the process is not running as an Administrator).
+    pub fn install<S>(name: S, message_file: Option<&Path>) -> Result<(), WinError>
+    where
+        S: AsRef<str>,
+    {
+        let wide_name = WideCString::new(name.as_ref());
+        let wide_message_file = message_file.map(|p| WideCString::new(p.as_os_str()));
+
+        let registration = AUTHZ_SOURCE_SCHEMA_REGISTRATION {
+            szEventSourceName: wide_name.as_ptr() as _,
+            szEventMessageFile: wide_message_file.as_ref().map_or(null_mut(), |f| f.as_ptr() as _),
+            ..Default::default()
+        };
+
+        unsafe { winapi_bool_call!(AuthzInstallSecurityEventSource(0, &registration)) };
+        Ok(())
+    }
+
+    /// Uninstalls a previously installed security event source.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the source cannot be uninstalled.
+    pub fn uninstall<S>(name: S) -> Result<(), WinError>
+    where
+        S: AsRef<str>,
+    {
+        let wide_name = WideCString::new(name.as_ref());
+        unsafe { winapi_bool_call!(AuthzUninstallSecurityEventSource(0, wide_name.as_ptr())) };
+        Ok(())
+    }
+
+    /// Registers an installed security event source for reporting events.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the source is not installed or the process does not hold
+    /// the *SeAuditPrivilege* privilege.
+    pub fn register<S>(name: S) -> Result<Self, WinError>
+    where
+        S: AsRef<str>,
+    {
+        let wide_name = WideCString::new(name.as_ref());
+        let mut handle: AUTHZ_SECURITY_EVENT_PROVIDER_HANDLE = null_mut();
+        unsafe { winapi_bool_call!(AuthzRegisterSecurityEventSource(0, wide_name.as_ptr(), &mut handle)) };
+        Ok(Self { handle })
+    }
+
+    /// Reports a security audit event to the Security event log.
+    ///
+    /// # Arguments
+    ///
+    /// * `audit_id` - The identifier of the audit event (defined by the message file of the source).
+    /// * `user_sid` - The SID of the user the event is reported for, or `None` for the calling process.
+    /// * `success` - Whether the event is a success or a failure audit.
+    /// * `params` - The parameters of the event.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the event cannot be reported.
+    pub fn report(
+        &self,
+        audit_id: u32,
+        user_sid: Option<SidRef>,
+        success: bool,
+        params: &[AuditParam],
+    ) -> Result<(), WinError> {
+        // keeps the wide strings alive until the event is reported
+        let strings: Vec<Option<WideCString>> = params
+            .iter()
+            .map(|p| match p {
+                AuditParam::String(s) => Some(WideCString::new(s)),
+                _ => None,
+            })
+            .collect();
+
+        let mut audit_params: Vec<AUDIT_PARAM> = params
+            .iter()
+            .zip(&strings)
+            .map(|(param, string)| {
+                let (param_type, data) = match param {
+                    AuditParam::String(_) => (
+                        APT_String,
+                        AUDIT_PARAM_0 {
+                            String: string.as_ref().map_or(null_mut(), |s| s.as_ptr() as _),
+                        },
+                    ),
+                    AuditParam::Ulong(value) => (APT_Ulong, AUDIT_PARAM_0 { Data0: *value as usize }),
+                    AuditParam::Sid(sid) => (
+                        APT_Sid,
+                        AUDIT_PARAM_0 {
+                            psid: sid.as_sid_ref().as_ptr() as _,
+                        },
+                    ),
+                };
+                AUDIT_PARAM {
+                    Type: param_type,
+                    Length: 0,
+                    Flags: 0,
+                    Anonymous1: data,
+                    Anonymous2: AUDIT_PARAM_1 { Data1: 0 },
+                }
+            })
+            .collect();
+
+        let audit_params = AUDIT_PARAMS {
+            Length: 0,
+            Flags: if success { APF_AuditSuccess } else { APF_AuditFailure },
+            Count: audit_params.len() as u16,
+            Parameters: audit_params.as_mut_ptr(),
+        };
+
+        let user_sid = user_sid.map_or(null_mut(), |sid| sid.as_ptr() as _);
+
+        unsafe {
+            winapi_bool_call!(AuthzReportSecurityEventFromParams(
+                0,
+                self.handle,
+                audit_id,
+                user_sid,
+                &audit_params,
+            ))
+        };
+        Ok(())
+    }
+}
+
+impl Drop for SecurityEventSource {
+    fn drop(&mut self) {
+        unsafe {
+            let freed = AuthzUnregisterSecurityEventSource(0, &mut self.handle);
+            debug_assert!(
+                freed != 0,
+                "AuthzUnregisterSecurityEventSource failed in SecurityEventSource::drop!"
+            );
+        }
+    }
+}
+
+/// Which access attempts an audit entry records.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AuditOn {
+    /// Successful access attempts.
+    Success,
+    /// Failed access attempts.
+    Failure,
+    /// Both successful and failed access attempts.
+    FailureAndSuccess,
+}
+
+impl AuditOn {
+    pub(crate) fn success(&self) -> bool {
+        matches!(self, AuditOn::Success | AuditOn::FailureAndSuccess)
+    }
+
+    pub(crate) fn failure(&self) -> bool {
+        matches!(self, AuditOn::Failure | AuditOn::FailureAndSuccess)
+    }
+}
+
+/// Starts auditing access to a file or directory.
+///
+/// Enables the *SeSecurityPrivilege* privilege, reads the SACL of the object, adds an audit entry
+/// (merging it into an existing explicit entry for the same principal, if any) and writes the SACL
+/// back. Entries added to a directory are inherited by its files and subdirectories.
+///
+/// Events are only logged if the *File System* audit subcategory is enabled
+/// (see [`auditpol`](crate::auditpol)).
+///
+/// # Arguments
+///
+/// * `path` - The file or directory to audit.
+/// * `sid` - The security principal whose access is audited.
+/// * `mask` - The audited access rights.
+/// * `on` - Whether successful and/or failed attempts are audited.
+///
+/// # Errors
+///
+/// Returns an error if the privilege cannot be enabled (e.g., the process is not running as
+/// an Administrator) or the SACL cannot be read or written.
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::{
+///     audit::{AuditOn, audit_access},
+///     mask::FileAccess,
+///     sid::Sid,
+///     wellknown::WinWorldSid,
+/// };
+///
+/// // audit all failed writes to the folder by Everyone
+/// let everyone = Sid::from_well_known_sid(WinWorldSid)?;
+/// audit_access("C:\\data", &everyone, FileAccess::WRITE, AuditOn::Failure)?;
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+pub fn audit_access<'a, P, S, M>(path: P, sid: &'a S, mask: M, on: AuditOn) -> Result<(), WinError>
+where
+    P: AsRef<Path>,
+    S: AsSidRef<'a>,
+    M: IntoAccessMask,
+{
+    let path = path.as_ref();
+    let token = PrivilegeToken::new().try_elevate()?;
+
+    let sd = SecurityDescriptorElevated::from_path(&token, path)?;
+    let sacl = match sd.sacl_unchecked() {
+        Some(sacl) => sacl.try_clone()?,
+        None => Acl::empty()?,
+    };
+
+    let inheritance_flags = if path.is_dir() {
+        OBJECT_INHERIT_ACE | CONTAINER_INHERIT_ACE
+    } else {
+        0
+    };
+    let sacl = sacl.with_merged_audit(mask.as_u32(), sid, inheritance_flags, on.success(), on.failure())?;
+
+    SecurityDescriptorElevated::from_components(None, None, None, Some(&sacl))?.set_named(
+        path.as_os_str(),
+        SE_FILE_OBJECT,
+        SecurityInformation::SACL,
+    )
+}
@@ -0,0 +1,279 @@
+//! System audit policy.
+//!
+//! Object access auditing configured through SACLs only produces events when the matching
+//! audit subcategory (e.g., *File System* or *Registry*) is enabled in the system audit policy.
+//! This module enumerates the audit categories and subcategories and queries or configures
+//! the system policy, like `auditpol.exe` does.
+//!
+//! Querying and changing the policy requires the *SeSecurityPrivilege* privilege, which is why
+//! those operations take an elevated token (see [`PrivilegeToken::try_elevate`](crate::elevated::PrivilegeToken)).
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use win_acl_rs::{
+//!     auditpol::{AuditSetting, AuditSubcategory},
+//!     elevated::PrivilegeToken,
+//! };
+//!
+//! let token = PrivilegeToken::new().try_elevate()?;
+//! let setting = AuditSubcategory::FILE_SYSTEM.policy(&token)?;
+//! if !setting.contains(AuditSetting::FAILURE) {
+//!     AuditSubcategory::FILE_SYSTEM.set_policy(&token, setting | AuditSetting::FAILURE)?;
+//! }
+//! # Ok::<(), win_acl_rs::error::WinError>(())
+//! ```
+
+use std::{
+    fmt::{Display, Formatter},
+    ptr::null_mut,
+    slice::from_raw_parts,
+};
+
+use windows_sys::{
+    Win32::{
+        Foundation::GetLastError,
+        Security::Authentication::Identity::{
+            AUDIT_POLICY_INFORMATION, AuditEnumerateCategories, AuditEnumerateSubCategories, AuditFree,
+            AuditLookupCategoryNameW, AuditLookupSubCategoryNameW, AuditQuerySystemPolicy, AuditSetSystemPolicy,
+            POLICY_AUDIT_EVENT_FAILURE, POLICY_AUDIT_EVENT_NONE, POLICY_AUDIT_EVENT_SUCCESS,
+        },
+    },
+    core::{GUID, PWSTR},
+};
+
+use crate::{
+    elevated::{Elevated, PrivilegeTokenImpl},
+    error::WinError,
+    guid::Guid,
+    mask::bit_ops,
+    utils::WideCString,
+};
+
+/// Converts the `BOOLEAN` result of the audit functions into a result.
+fn audit_result(ok: bool) -> Result<(), WinError> {
+    if !ok {
+        return Err(unsafe { GetLastError() }.into());
+    }
+    Ok(())
+}
+
+/// Copies an array of GUIDs allocated by the audit functions and frees it.
+unsafe fn take_guids(ptr: *mut GUID, count: u32) -> Vec<Guid> {
+    if ptr.is_null() {
+        return Vec::new();
+    }
+    let guids = unsafe { from_raw_parts(ptr, count as usize) }
+        .iter()
+        .map(|g| Guid::from(*g))
+        .collect();
+    unsafe { AuditFree(ptr as _) };
+    guids
+}
+
+/// Copies a name allocated by the audit functions and frees it.
+unsafe fn take_name(ptr: PWSTR) -> String {
+    let name = WideCString::from_wide_null_ptr(ptr).as_string();
+    unsafe { AuditFree(ptr as _) };
+    name
+}
+
+/// Audit settings of a subcategory.
+///
+/// All bitwise operations (`|`, `&`, `|=`, `&=`, `!`) are supported for combining settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct AuditSetting(pub u32);
+
+impl AuditSetting {
+    /// Successful attempts are audited.
+    pub const SUCCESS: Self = Self(POLICY_AUDIT_EVENT_SUCCESS as u32);
+    /// Failed attempts are audited.
+    pub const FAILURE: Self = Self(POLICY_AUDIT_EVENT_FAILURE as u32);
+    /// Clears the settings of the subcategory when set (never returned by a query).
+    pub const NONE: Self = Self(POLICY_AUDIT_EVENT_NONE as u32);
+
+    /// Returns a setting auditing nothing.
+    pub fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Checks whether all settings in `other` are enabled.
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl From<AuditSetting> for u32 {
+    fn from(setting: AuditSetting) -> Self {
+        setting.0
+    }
+}
+
+impl From<u32> for AuditSetting {
+    fn from(value: u32) -> Self {
+        AuditSetting(value)
+    }
+}
+
+bit_ops!(AuditSetting);
+
+/// An audit policy category (e.g., *Object Access*).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AuditCategory(pub Guid);
+
+impl AuditCategory {
+    /// Returns the localized display name of the category.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the category is unknown.
+    pub fn name(&self) -> Result<String, WinError> {
+        let guid = GUID::from(self.0);
+        let mut name: PWSTR = null_mut();
+        audit_result(unsafe { AuditLookupCategoryNameW(&guid, &mut name) })?;
+        Ok(unsafe { take_name(name) })
+    }
+
+    /// Returns the subcategories of the category.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the category is unknown.
+    pub fn subcategories(&self) -> Result<Vec<AuditSubcategory>, WinError> {
+        let guid = GUID::from(self.0);
+        let mut ptr: *mut GUID = null_mut();
+        let mut count = 0u32;
+        audit_result(unsafe { AuditEnumerateSubCategories(&guid, false, &mut ptr, &mut count) })?;
+        Ok(unsafe { take_guids(ptr, count) }
+            .into_iter()
+            .map(AuditSubcategory)
+            .collect())
+    }
+}
+
+impl Display for AuditCategory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// An audit policy subcategory (e.g., *File System* within *Object Access*).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AuditSubcategory(pub Guid);
+
+impl AuditSubcategory {
+    /// Object Access: File System.
+    pub const FILE_SYSTEM: Self = Self(Guid(0x0CCE921D_69AE_11D9_BED3_505054503030));
+    /// Object Access: Registry.
+    pub const REGISTRY: Self = Self(Guid(0x0CCE921E_69AE_11D9_BED3_505054503030));
+    /// Object Access: Kernel Object.
+    pub const KERNEL_OBJECT: Self = Self(Guid(0x0CCE921F_69AE_11D9_BED3_505054503030));
+    /// Object Access: SAM.
+    pub const SAM: Self = Self(Guid(0x0CCE9220_69AE_11D9_BED3_505054503030));
+    /// Object Access: Handle Manipulation.
+    pub const HANDLE_MANIPULATION: Self = Self(Guid(0x0CCE9223_69AE_11D9_BED3_505054503030));
+    /// Object Access: File Share.
+    pub const FILE_SHARE: Self = Self(Guid(0x0CCE9224_69AE_11D9_BED3_505054503030));
+    /// Object Access: Detailed File Share.
+    pub const DETAILED_FILE_SHARE: Self = Self(Guid(0x0CCE9244_69AE_11D9_BED3_505054503030));
+    /// DS Access: Directory Service Access.
+    pub const DIRECTORY_SERVICE_ACCESS: Self = Self(Guid(0x0CCE923B_69AE_11D9_BED3_505054503030));
+    /// Policy Change: Audit Policy Change.
+    pub const AUDIT_POLICY_CHANGE: Self = Self(Guid(0x0CCE922F_69AE_11D9_BED3_505054503030));
+
+    /// Returns the localized display name of the subcategory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the subcategory is unknown.
+    pub fn name(&self) -> Result<String, WinError> {
+        let guid = GUID::from(self.0);
+        let mut name: PWSTR = null_mut();
+        audit_result(unsafe { AuditLookupSubCategoryNameW(&guid, &mut name) })?;
+        Ok(unsafe { take_name(name) })
+    }
+
+    /// Queries the system audit policy of the subcategory.
+    ///
+    /// # Arguments
+    ///
+    /// * `_token` - an elevated privilege token.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the subcategory is unknown or the policy cannot be queried.
+    pub fn policy(&self, _token: &PrivilegeTokenImpl<Elevated>) -> Result<AuditSetting, WinError> {
+        let guid = GUID::from(self.0);
+        let mut info: *mut AUDIT_POLICY_INFORMATION = null_mut();
+        audit_result(unsafe { AuditQuerySystemPolicy(&guid, 1, &mut info) })?;
+
+        let setting = unsafe { (*info).AuditingInformation };
+        unsafe { AuditFree(info as _) };
+        Ok(AuditSetting(setting))
+    }
+
+    /// Sets the system audit policy of the subcategory.
+    ///
+    /// Passing [`AuditSetting::empty()`] disables auditing of the subcategory.
+    ///
+    /// # Arguments
+    ///
+    /// * `_token` - an elevated privilege token.
+    /// * `setting` - the new settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the subcategory is unknown or the policy cannot be set.
+    pub fn set_policy(&self, _token: &PrivilegeTokenImpl<Elevated>, setting: AuditSetting) -> Result<(), WinError> {
+        // an empty setting would leave the policy untouched, NONE clears it
+        let setting = if setting == AuditSetting::empty() {
+            AuditSetting::NONE
+        } else {
+            setting
+        };
+
+        let info = AUDIT_POLICY_INFORMATION {
+            AuditSubCategoryGuid: self.0.into(),
+            AuditingInformation: setting.0,
+            AuditCategoryGuid: GUID::default(),
+        };
+        audit_result(unsafe { AuditSetSystemPolicy(&info, 1) })
+    }
+}
+
+impl Display for AuditSubcategory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Returns all audit policy categories.
+///
+/// # Errors
+///
+/// Returns an error if the categories cannot be enumerated.
+pub fn categories() -> Result<Vec<AuditCategory>, WinError> {
+    let mut ptr: *mut GUID = null_mut();
+    let mut count = 0u32;
+    audit_result(unsafe { AuditEnumerateCategories(&mut ptr, &mut count) })?;
+    Ok(unsafe { take_guids(ptr, count) }
+        .into_iter()
+        .map(AuditCategory)
+        .collect())
+}
+
+/// Returns all audit policy subcategories of all categories.
+///
+/// # Errors
+///
+/// Returns an error if the subcategories cannot be enumerated.
+pub fn subcategories() -> Result<Vec<AuditSubcategory>, WinError> {
+    let mut ptr: *mut GUID = null_mut();
+    let mut count = 0u32;
+    audit_result(unsafe { AuditEnumerateSubCategories(null_mut(), true, &mut ptr, &mut count) })?;
+    Ok(unsafe { take_guids(ptr, count) }
+        .into_iter()
+        .map(AuditSubcategory)
+        .collect())
+}
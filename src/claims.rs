@@ -0,0 +1,366 @@
+//! Resource attributes (claims) of securable objects.
+//!
+//! Dynamic Access Control classifies objects with resource attributes, e.g., `Project = "X"` or
+//! `Sensitivity = 100`, which conditional ACEs and central access policies then evaluate
+//! (`@Resource.Project`). The attributes are stored as resource attribute ACEs (`RA` in SDDL)
+//! in the SACL of the object, but are read and written with `SecurityInformation::ATTRIBUTE`,
+//! which requires `READ_CONTROL` and `WRITE_DAC` access rather than the *SeSecurityPrivilege*
+//! privilege.
+//!
+//! Central access policies bind an object to a set of conditional rules maintained in Active
+//! Directory. An object refers to its policy by a scoped policy ID ACE (`SP` in SDDL) holding
+//! the policy ID (CAPID), written with `SecurityInformation::SCOPE`; [`applied_capids`] lists
+//! the policies applied to the local machine.
+//!
+//! see [MSDN](https://learn.microsoft.com/en-us/windows/win32/api/winnt/ns-winnt-claim_security_attribute_relative_v1)
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use win_acl_rs::{
+//!     acl::{AceFlags, Acl},
+//!     claims::{ClaimValues, ResourceAttribute},
+//!     sd::{SecurityDescriptor, SecurityDescriptorBuilder, SecurityInformation},
+//! };
+//!
+//! let mut sacl = Acl::new()?;
+//! sacl.add_resource_attribute(&ResourceAttribute::new("Project", ClaimValues::from("X")), AceFlags::empty())?;
+//! let sd = SecurityDescriptorBuilder::new().sacl(sacl).build()?;
+//! sd.apply_to_path("C:\\data\\plan.docx", SecurityInformation::ATTRIBUTE)?;
+//!
+//! for attribute in SecurityDescriptor::from_path("C:\\data\\plan.docx")?.resource_attributes()? {
+//!     println!("{} = {:?}", attribute.name(), attribute.values());
+//! }
+//! # Ok::<(), win_acl_rs::error::WinError>(())
+//! ```
+
+use std::fmt::Write;
+
+use windows_sys::Win32::{
+    Foundation::{ERROR_INVALID_DATA, ERROR_INVALID_PARAMETER},
+    Security::{
+        CLAIM_SECURITY_ATTRIBUTE_DISABLED, CLAIM_SECURITY_ATTRIBUTE_DISABLED_BY_DEFAULT,
+        CLAIM_SECURITY_ATTRIBUTE_MANDATORY, CLAIM_SECURITY_ATTRIBUTE_NON_INHERITABLE,
+        CLAIM_SECURITY_ATTRIBUTE_TYPE_BOOLEAN, CLAIM_SECURITY_ATTRIBUTE_TYPE_INT64,
+        CLAIM_SECURITY_ATTRIBUTE_TYPE_OCTET_STRING, CLAIM_SECURITY_ATTRIBUTE_TYPE_SID,
+        CLAIM_SECURITY_ATTRIBUTE_TYPE_STRING, CLAIM_SECURITY_ATTRIBUTE_TYPE_UINT64,
+        CLAIM_SECURITY_ATTRIBUTE_USE_FOR_DENY_ONLY, CLAIM_SECURITY_ATTRIBUTE_VALUE_CASE_SENSITIVE,
+    },
+};
+
+use crate::{error::WinError, lsa, mask::bit_ops, sid::Sid};
+
+/// The flags of a resource attribute.
+///
+/// All bitwise operations (`|`, `&`, `|=`, `&=`, `!`) are supported for combining flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[repr(transparent)]
+pub struct ClaimFlags(pub u32);
+
+impl ClaimFlags {
+    /// The attribute is not inherited by child objects.
+    pub const NON_INHERITABLE: Self = Self(CLAIM_SECURITY_ATTRIBUTE_NON_INHERITABLE);
+    /// String values are compared case-sensitively.
+    pub const CASE_SENSITIVE: Self = Self(CLAIM_SECURITY_ATTRIBUTE_VALUE_CASE_SENSITIVE);
+    /// The attribute is only considered for deny ACEs.
+    pub const USE_FOR_DENY_ONLY: Self = Self(CLAIM_SECURITY_ATTRIBUTE_USE_FOR_DENY_ONLY);
+    /// The attribute is disabled by default.
+    pub const DISABLED_BY_DEFAULT: Self = Self(CLAIM_SECURITY_ATTRIBUTE_DISABLED_BY_DEFAULT);
+    /// The attribute is disabled.
+    pub const DISABLED: Self = Self(CLAIM_SECURITY_ATTRIBUTE_DISABLED);
+    /// The attribute is mandatory.
+    pub const MANDATORY: Self = Self(CLAIM_SECURITY_ATTRIBUTE_MANDATORY);
+
+    /// No flags.
+    pub fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns `true` if all flags of `other` are set in `self`.
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl From<ClaimFlags> for u32 {
+    fn from(value: ClaimFlags) -> Self {
+        value.0
+    }
+}
+
+impl From<u32> for ClaimFlags {
+    fn from(value: u32) -> Self {
+        ClaimFlags(value)
+    }
+}
+
+bit_ops!(ClaimFlags);
+
+/// The values of a resource attribute. An attribute holds one or more values of a single type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ClaimValues {
+    /// Signed integers (`TI`).
+    Int64(Vec<i64>),
+    /// Unsigned integers (`TU`).
+    Uint64(Vec<u64>),
+    /// Strings (`TS`).
+    String(Vec<String>),
+    /// SIDs (`TD`).
+    Sid(Vec<Sid>),
+    /// Booleans (`TB`).
+    Boolean(Vec<bool>),
+    /// Octet strings (`TX`).
+    OctetString(Vec<Vec<u8>>),
+}
+
+impl ClaimValues {
+    /// Returns the number of values.
+    pub fn len(&self) -> usize {
+        match self {
+            ClaimValues::Int64(values) => values.len(),
+            ClaimValues::Uint64(values) => values.len(),
+            ClaimValues::String(values) => values.len(),
+            ClaimValues::Sid(values) => values.len(),
+            ClaimValues::Boolean(values) => values.len(),
+            ClaimValues::OctetString(values) => values.len(),
+        }
+    }
+
+    /// Returns `true` if there are no values.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl From<&str> for ClaimValues {
+    fn from(value: &str) -> Self {
+        ClaimValues::String(vec![value.to_owned()])
+    }
+}
+
+impl From<i64> for ClaimValues {
+    fn from(value: i64) -> Self {
+        ClaimValues::Int64(vec![value])
+    }
+}
+
+impl From<u64> for ClaimValues {
+    fn from(value: u64) -> Self {
+        ClaimValues::Uint64(vec![value])
+    }
+}
+
+impl From<bool> for ClaimValues {
+    fn from(value: bool) -> Self {
+        ClaimValues::Boolean(vec![value])
+    }
+}
+
+/// A resource attribute: a named, typed, possibly multi-valued claim attached to an object.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResourceAttribute {
+    name: String,
+    flags: ClaimFlags,
+    values: ClaimValues,
+}
+
+impl ResourceAttribute {
+    /// Creates a resource attribute without flags.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the attribute (e.g., `Project`), referenced as `@Resource.name`
+    ///   by conditional ACEs.
+    /// * `values` - The values of the attribute.
+    pub fn new<S>(name: S, values: ClaimValues) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            name: name.into(),
+            flags: ClaimFlags::empty(),
+            values,
+        }
+    }
+
+    /// Sets the flags of the attribute.
+    pub fn with_flags(mut self, flags: ClaimFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Returns the name of the attribute.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the flags of the attribute.
+    pub fn flags(&self) -> ClaimFlags {
+        self.flags
+    }
+
+    /// Returns the values of the attribute.
+    pub fn values(&self) -> &ClaimValues {
+        &self.values
+    }
+
+    /// Renders the attribute in the SDDL form of the application data of a resource attribute ACE,
+    /// e.g., `("Project",TS,0x0,"X","Y")`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ERROR_INVALID_PARAMETER` if the attribute has no values or the name or a string
+    /// value contains a quote, or an error if a SID cannot be converted.
+    pub fn to_sddl(&self) -> Result<String, WinError> {
+        let invalid = |what: &str| {
+            Err(WinError::new(
+                ERROR_INVALID_PARAMETER,
+                Some(format!("Invalid resource attribute {}: {}", self.name, what)),
+            ))
+        };
+        if self.values.is_empty() {
+            return invalid("no values");
+        }
+        if self.name.contains('"') {
+            return invalid("quote in the name");
+        }
+
+        let type_code = match self.values {
+            ClaimValues::Int64(_) => "TI",
+            ClaimValues::Uint64(_) => "TU",
+            ClaimValues::String(_) => "TS",
+            ClaimValues::Sid(_) => "TD",
+            ClaimValues::Boolean(_) => "TB",
+            ClaimValues::OctetString(_) => "TX",
+        };
+        let mut sddl = format!("(\"{}\",{},0x{:x}", self.name, type_code, self.flags.0);
+        match &self.values {
+            ClaimValues::Int64(values) => values.iter().for_each(|v| {
+                let _ = write!(sddl, ",{}", v);
+            }),
+            ClaimValues::Uint64(values) => values.iter().for_each(|v| {
+                let _ = write!(sddl, ",{}", v);
+            }),
+            ClaimValues::String(values) => {
+                for value in values {
+                    if value.contains('"') {
+                        return invalid("quote in a value");
+                    }
+                    let _ = write!(sddl, ",\"{}\"", value);
+                }
+            }
+            ClaimValues::Sid(values) => {
+                for value in values {
+                    let _ = write!(sddl, ",SID({})", value.to_string()?);
+                }
+            }
+            ClaimValues::Boolean(values) => values.iter().for_each(|v| {
+                let _ = write!(sddl, ",{}", *v as u8);
+            }),
+            ClaimValues::OctetString(values) => {
+                for value in values {
+                    sddl.push(',');
+                    value.iter().for_each(|byte| {
+                        let _ = write!(sddl, "{:02x}", byte);
+                    });
+                }
+            }
+        }
+        sddl.push(')');
+        Ok(sddl)
+    }
+
+    /// Decodes the application data of a resource attribute ACE (`CLAIM_SECURITY_ATTRIBUTE_RELATIVE_V1`).
+    pub(crate) fn from_relative(data: &[u8]) -> Result<Self, WinError> {
+        let malformed = || WinError::new(ERROR_INVALID_DATA, Some("Malformed resource attribute".to_owned()));
+        let u16_at = |offset: usize| {
+            data.get(offset..offset + 2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]))
+                .ok_or_else(malformed)
+        };
+        let u32_at = |offset: usize| {
+            data.get(offset..offset + 4)
+                .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+                .ok_or_else(malformed)
+        };
+        let u64_at = |offset: usize| {
+            data.get(offset..offset + 8)
+                .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+                .ok_or_else(malformed)
+        };
+        let string_at = |offset: usize| {
+            let units: Vec<u16> = data
+                .get(offset..)
+                .ok_or_else(malformed)?
+                .chunks_exact(2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]))
+                .take_while(|&unit| unit != 0)
+                .collect();
+            Ok::<_, WinError>(String::from_utf16_lossy(&units))
+        };
+        // an octet string (and a SID) is stored as its length followed by its bytes
+        let octets_at = |offset: usize| {
+            let len = u32_at(offset)? as usize;
+            data.get(offset + 4..offset + 4 + len).ok_or_else(malformed)
+        };
+
+        let name = string_at(u32_at(0)? as usize)?;
+        let value_type = u16_at(4)?;
+        let flags = ClaimFlags(u32_at(8)?);
+        let count = u32_at(12)? as usize;
+        let offsets = (0..count)
+            .map(|i| u32_at(16 + 4 * i).map(|offset| offset as usize))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let values = match value_type {
+            CLAIM_SECURITY_ATTRIBUTE_TYPE_INT64 => ClaimValues::Int64(
+                offsets
+                    .iter()
+                    .map(|&o| u64_at(o).map(|v| v as i64))
+                    .collect::<Result<_, _>>()?,
+            ),
+            CLAIM_SECURITY_ATTRIBUTE_TYPE_UINT64 => {
+                ClaimValues::Uint64(offsets.iter().map(|&o| u64_at(o)).collect::<Result<_, _>>()?)
+            }
+            CLAIM_SECURITY_ATTRIBUTE_TYPE_STRING => {
+                ClaimValues::String(offsets.iter().map(|&o| string_at(o)).collect::<Result<_, _>>()?)
+            }
+            CLAIM_SECURITY_ATTRIBUTE_TYPE_SID => ClaimValues::Sid(
+                offsets
+                    .iter()
+                    .map(|&o| Sid::from_bytes(octets_at(o)?))
+                    .collect::<Result<_, _>>()?,
+            ),
+            CLAIM_SECURITY_ATTRIBUTE_TYPE_BOOLEAN => ClaimValues::Boolean(
+                offsets
+                    .iter()
+                    .map(|&o| u64_at(o).map(|v| v != 0))
+                    .collect::<Result<_, _>>()?,
+            ),
+            CLAIM_SECURITY_ATTRIBUTE_TYPE_OCTET_STRING => ClaimValues::OctetString(
+                offsets
+                    .iter()
+                    .map(|&o| octets_at(o).map(<[u8]>::to_vec))
+                    .collect::<Result<_, _>>()?,
+            ),
+            _ => return Err(malformed()),
+        };
+        Ok(Self { name, flags, values })
+    }
+}
+
+/// Returns the IDs (CAPIDs) of the central access policies applied to the local machine by
+/// group policy.
+///
+/// Objects bound to a policy that is not applied on the machine (see
+/// [`SecurityDescriptorImpl::central_access_policy`](crate::sd::SecurityDescriptorImpl::central_access_policy))
+/// are checked against the recovery policy instead.
+///
+/// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/api/ntlsa/nf-ntlsa-lsagetappliedcapids)
+///
+/// # Errors
+///
+/// Returns an error if the policies cannot be queried.
+pub fn applied_capids() -> Result<Vec<Sid>, WinError> {
+    lsa::applied_capids()
+}
@@ -0,0 +1,216 @@
+//! COM security descriptors.
+//!
+//! COM servers describe who may call them (access permissions) and who may launch or activate
+//! them (launch permissions) with security descriptors whose ACEs use the `COM_RIGHTS_*` access
+//! rights (see [`ComAccess`]). COM is picky about these descriptors: they must have an owner,
+//! a group and a DACL, and every ACE must include `COM_RIGHTS_EXECUTE`.
+//!
+//! [`ComSecurityBuilder`] assembles such a descriptor from this crate's types, ready to be passed
+//! to `CoInitializeSecurity`.
+//!
+//! Servers that do not call `CoInitializeSecurity` get their permissions from the `AccessPermission`
+//! and `LaunchPermission` values of their AppID registry key, which [`AppId`] reads and writes.
+//!
+//! see [MSDN](https://learn.microsoft.com/en-us/windows/win32/com/access-control-lists-for-com)
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use win_acl_rs::{com::ComSecurityBuilder, mask::ComAccess, sid::Sid};
+//! use win_acl_rs::wellknown::{WinAuthenticatedUserSid, WinBuiltinGuestsSid};
+//!
+//! let sd = ComSecurityBuilder::new()
+//!     .allow(Sid::from_well_known_sid(WinAuthenticatedUserSid)?, ComAccess::local())
+//!     .deny(Sid::from_well_known_sid(WinBuiltinGuestsSid)?, ComAccess::local_and_remote())
+//!     .build()?;
+//!
+//! // pass `sd.as_ptr()` as the `psecdesc` argument of `CoInitializeSecurity`
+//! # Ok::<(), win_acl_rs::error::WinError>(())
+//! ```
+
+use crate::{
+    acl::Acl,
+    error::WinError,
+    guid::Guid,
+    mask::ComAccess,
+    registry::{self, Hive, RegistryPath},
+    sd::SecurityDescriptor,
+    sid::{AsSidRef, Sid},
+    wellknown::WinBuiltinAdministratorsSid,
+};
+
+/// A builder of COM access and launch permission security descriptors.
+///
+/// Access-denied entries are placed before access-allowed entries, regardless of the order
+/// they were added in. `COM_RIGHTS_EXECUTE` is added to every entry.
+///
+/// The owner and the group default to `BUILTIN\Administrators`.
+#[derive(Debug, Clone, Default)]
+pub struct ComSecurityBuilder {
+    owner: Option<Sid>,
+    group: Option<Sid>,
+    denied: Vec<(Sid, ComAccess)>,
+    allowed: Vec<(Sid, ComAccess)>,
+}
+
+impl ComSecurityBuilder {
+    /// Creates a builder with no entries.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the owner of the security descriptor.
+    pub fn owner(mut self, sid: Sid) -> Self {
+        self.owner = Some(sid);
+        self
+    }
+
+    /// Sets the primary group of the security descriptor.
+    pub fn group(mut self, sid: Sid) -> Self {
+        self.group = Some(sid);
+        self
+    }
+
+    /// Grants COM rights to a security principal.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid` - The security principal.
+    /// * `rights` - The granted rights (e.g., `ComAccess::local()`).
+    pub fn allow(mut self, sid: Sid, rights: ComAccess) -> Self {
+        self.allowed.push((sid, rights | ComAccess::EXECUTE));
+        self
+    }
+
+    /// Denies COM rights to a security principal.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid` - The security principal.
+    /// * `rights` - The denied rights (e.g., `ComAccess::EXECUTE_REMOTE`).
+    pub fn deny(mut self, sid: Sid, rights: ComAccess) -> Self {
+        self.denied.push((sid, rights | ComAccess::EXECUTE));
+        self
+    }
+
+    /// Builds the security descriptor.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the default owner SID cannot be created or the descriptor
+    /// cannot be assembled.
+    pub fn build(&self) -> Result<SecurityDescriptor, WinError> {
+        let administrators = Sid::from_well_known_sid(WinBuiltinAdministratorsSid)?;
+        let owner = self.owner.as_ref().unwrap_or(&administrators);
+        let group = self.group.as_ref().unwrap_or(&administrators);
+
+        let entries = self.denied.len() + self.allowed.len();
+        let sid_max_len = self
+            .denied
+            .iter()
+            .chain(&self.allowed)
+            .map(|(sid, _)| sid.len())
+            .max()
+            .unwrap_or(0);
+
+        let mut dacl = Acl::with_capacity(entries, sid_max_len)?;
+        for (sid, rights) in &self.denied {
+            dacl.deny(*rights, sid)?;
+        }
+        for (sid, rights) in &self.allowed {
+            dacl.allow(*rights, sid)?;
+        }
+
+        SecurityDescriptor::from_components(Some(owner.as_sid_ref()), Some(group.as_sid_ref()), Some(&dacl), None)
+    }
+}
+
+const ACCESS_PERMISSION: &str = "AccessPermission";
+const LAUNCH_PERMISSION: &str = "LaunchPermission";
+
+/// A COM application identifier (AppID) and its registry-stored permissions.
+///
+/// This is what `dcomcnfg` edits on the *Security* tab of an application.
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::com::AppId;
+///
+/// let app_id = AppId::new("{00000000-0000-0000-0000-000000000000}")?;
+/// let sd = app_id.launch_permission()?;
+/// println!("{}", sd.as_sd_string()?);
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AppId {
+    guid: String,
+}
+
+impl AppId {
+    /// Creates an AppID reference.
+    ///
+    /// # Arguments
+    ///
+    /// * `guid` - The AppID GUID, with or without braces (e.g., `{00000000-0000-0000-0000-000000000000}`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `guid` is not a well-formed GUID.
+    pub fn new<S>(guid: S) -> Result<Self, WinError>
+    where
+        S: AsRef<str>,
+    {
+        Ok(Self {
+            guid: Guid::parse(guid)?.to_string(),
+        })
+    }
+
+    /// Returns the AppID GUID in its braced form.
+    pub fn guid(&self) -> &str {
+        &self.guid
+    }
+
+    /// Returns the registry key of the AppID (`HKCR\AppID\{...}`).
+    pub fn key(&self) -> RegistryPath {
+        RegistryPath::new(Hive::ClassesRoot, format!(r"AppID\{}", self.guid))
+    }
+
+    /// Reads the security descriptor controlling who can call the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the AppID does not exist or has no `AccessPermission` value,
+    /// in which case the machine-wide defaults apply.
+    pub fn access_permission(&self) -> Result<SecurityDescriptor, WinError> {
+        registry::read_sd_value(&self.key(), ACCESS_PERMISSION)
+    }
+
+    /// Writes the security descriptor controlling who can call the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the AppID does not exist or the value cannot be written.
+    pub fn set_access_permission(&self, sd: &SecurityDescriptor) -> Result<(), WinError> {
+        registry::write_sd_value(&self.key(), ACCESS_PERMISSION, sd)
+    }
+
+    /// Reads the security descriptor controlling who can launch and activate the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the AppID does not exist or has no `LaunchPermission` value,
+    /// in which case the machine-wide defaults apply.
+    pub fn launch_permission(&self) -> Result<SecurityDescriptor, WinError> {
+        registry::read_sd_value(&self.key(), LAUNCH_PERMISSION)
+    }
+
+    /// Writes the security descriptor controlling who can launch and activate the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the AppID does not exist or the value cannot be written.
+    pub fn set_launch_permission(&self, sd: &SecurityDescriptor) -> Result<(), WinError> {
+        registry::write_sd_value(&self.key(), LAUNCH_PERMISSION, sd)
+    }
+}
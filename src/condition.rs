@@ -0,0 +1,551 @@
+//! Conditional ACE expressions.
+//!
+//! Conditional ACEs (`XA`, `XD`, `XU` in SDDL) grant, deny or audit access only when a condition
+//! on the claims of the user and device and on the resource attributes of the object holds. They
+//! are the building block of Dynamic Access Control.
+//!
+//! [`Condition`] builds such expressions programmatically and renders them in SDDL or in the
+//! binary form stored in the application data of callback ACEs. [`Acl::allow_if`] and
+//! [`Acl::deny_if`] add the resulting ACEs to an ACL.
+//!
+//! see [MSDN](https://learn.microsoft.com/en-us/windows/win32/secauthz/security-descriptor-definition-language-for-conditional-aces-)
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use win_acl_rs::{
+//!     acl::{AceFlags, Acl},
+//!     condition::{Attribute, Condition},
+//!     mask::FileAccess,
+//!     sid::Sid,
+//! };
+//!
+//! let admins = Sid::from_string("S-1-5-32-544")?;
+//! let condition = Condition::member_of([admins])
+//!     .and(Attribute::resource("Project").equals("X"))
+//!     .or(Attribute::user("Department").any_of(["Finance", "Legal"]));
+//! println!("{}", condition.to_sddl()?);
+//!
+//! let mut dacl = Acl::new()?;
+//! let everyone = Sid::from_string("S-1-1-0")?;
+//! dacl.allow_if(FileAccess::READ, AceFlags::empty(), &everyone, &condition)?;
+//! # Ok::<(), win_acl_rs::error::WinError>(())
+//! ```
+//!
+//! [`Acl::allow_if`]: crate::acl::Acl::allow_if
+//! [`Acl::deny_if`]: crate::acl::Acl::deny_if
+
+use std::{fmt::Write, ops::Not};
+
+use windows_sys::Win32::Foundation::ERROR_INVALID_PARAMETER;
+
+use crate::{
+    acl::{Ace, AceFlags, AceType},
+    error::WinError,
+    mask::{AccessMask, IntoAccessMask},
+    sd::SecurityDescriptor,
+    sid::Sid,
+};
+
+/// The namespace of an attribute referenced by a condition.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AttributeScope {
+    /// A claim of the user (`@User.`).
+    User,
+    /// A claim of the device (`@Device.`).
+    Device,
+    /// A resource attribute of the object (`@Resource.`).
+    Resource,
+    /// A local attribute supplied by the application performing the access check.
+    Local,
+}
+
+/// An attribute referenced by a condition, e.g., `@User.Department`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Attribute {
+    scope: AttributeScope,
+    name: String,
+}
+
+/// An operand of a comparison.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Value {
+    /// A string literal.
+    String(String),
+    /// An integer literal.
+    Int(i64),
+    /// A SID literal (`SID(...)`).
+    Sid(Sid),
+    /// A list of values (`{...}`).
+    List(Vec<Value>),
+    /// Another attribute.
+    Attribute(Attribute),
+}
+
+/// A comparison operator.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CompareOp {
+    /// `==`
+    Equals,
+    /// `!=`
+    NotEquals,
+    /// `<`
+    LessThan,
+    /// `<=`
+    LessOrEqual,
+    /// `>`
+    GreaterThan,
+    /// `>=`
+    GreaterOrEqual,
+    /// `Contains`: the attribute holds all the values.
+    Contains,
+    /// `Any_of`: the attribute equals one of the values.
+    AnyOf,
+}
+
+/// A conditional ACE expression.
+///
+/// Expressions are combined with [`Condition::and`], [`Condition::or`] and `!`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Condition {
+    /// The user is a member of all the groups (`Member_of`).
+    MemberOf(Vec<Sid>),
+    /// The user is a member of any of the groups (`Member_of_any`).
+    MemberOfAny(Vec<Sid>),
+    /// The device is a member of all the groups (`Device_Member_of`).
+    DeviceMemberOf(Vec<Sid>),
+    /// The device is a member of any of the groups (`Device_Member_of_any`).
+    DeviceMemberOfAny(Vec<Sid>),
+    /// The attribute is present (`Exists`).
+    Exists(Attribute),
+    /// A comparison of an attribute with a value.
+    Compare(Attribute, CompareOp, Value),
+    /// Both conditions hold (`&&`).
+    And(Box<Condition>, Box<Condition>),
+    /// Either condition holds (`||`).
+    Or(Box<Condition>, Box<Condition>),
+    /// The condition does not hold (`!`).
+    Not(Box<Condition>),
+}
+
+impl Attribute {
+    /// Creates an attribute.
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - The namespace of the attribute.
+    /// * `name` - The name of the attribute (e.g., `Department` or `ad://ext/Project`).
+    pub fn new<S>(scope: AttributeScope, name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            scope,
+            name: name.into(),
+        }
+    }
+
+    /// Creates a claim of the user (`@User.name`).
+    pub fn user<S>(name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::new(AttributeScope::User, name)
+    }
+
+    /// Creates a claim of the device (`@Device.name`).
+    pub fn device<S>(name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::new(AttributeScope::Device, name)
+    }
+
+    /// Creates a resource attribute of the object (`@Resource.name`).
+    pub fn resource<S>(name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::new(AttributeScope::Resource, name)
+    }
+
+    /// Creates a local attribute.
+    pub fn local<S>(name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::new(AttributeScope::Local, name)
+    }
+
+    /// Returns the namespace of the attribute.
+    pub fn scope(&self) -> AttributeScope {
+        self.scope
+    }
+
+    /// Returns the name of the attribute.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The attribute equals the value (`==`).
+    pub fn equals<V>(self, value: V) -> Condition
+    where
+        V: Into<Value>,
+    {
+        Condition::Compare(self, CompareOp::Equals, value.into())
+    }
+
+    /// The attribute differs from the value (`!=`).
+    pub fn not_equals<V>(self, value: V) -> Condition
+    where
+        V: Into<Value>,
+    {
+        Condition::Compare(self, CompareOp::NotEquals, value.into())
+    }
+
+    /// The attribute is less than the value (`<`).
+    pub fn less_than<V>(self, value: V) -> Condition
+    where
+        V: Into<Value>,
+    {
+        Condition::Compare(self, CompareOp::LessThan, value.into())
+    }
+
+    /// The attribute is less than or equal to the value (`<=`).
+    pub fn less_or_equal<V>(self, value: V) -> Condition
+    where
+        V: Into<Value>,
+    {
+        Condition::Compare(self, CompareOp::LessOrEqual, value.into())
+    }
+
+    /// The attribute is greater than the value (`>`).
+    pub fn greater_than<V>(self, value: V) -> Condition
+    where
+        V: Into<Value>,
+    {
+        Condition::Compare(self, CompareOp::GreaterThan, value.into())
+    }
+
+    /// The attribute is greater than or equal to the value (`>=`).
+    pub fn greater_or_equal<V>(self, value: V) -> Condition
+    where
+        V: Into<Value>,
+    {
+        Condition::Compare(self, CompareOp::GreaterOrEqual, value.into())
+    }
+
+    /// The (multi-valued) attribute holds all the values (`Contains`).
+    pub fn contains<I, V>(self, values: I) -> Condition
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<Value>,
+    {
+        Condition::Compare(self, CompareOp::Contains, Value::list(values))
+    }
+
+    /// The attribute equals one of the values (`Any_of`).
+    pub fn any_of<I, V>(self, values: I) -> Condition
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<Value>,
+    {
+        Condition::Compare(self, CompareOp::AnyOf, Value::list(values))
+    }
+
+    /// The attribute is present (`Exists`).
+    pub fn exists(self) -> Condition {
+        Condition::Exists(self)
+    }
+
+    fn write_sddl(&self, sddl: &mut String) {
+        sddl.push_str(match self.scope {
+            AttributeScope::User => "@User.",
+            AttributeScope::Device => "@Device.",
+            AttributeScope::Resource => "@Resource.",
+            AttributeScope::Local => "",
+        });
+        for c in self.name.chars() {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | ':' | '/' | '.' | '$' | '#' | '@') {
+                sddl.push(c);
+            } else {
+                // other characters are escaped as UTF-16 code units
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    let _ = write!(sddl, "%{:04X}", unit);
+                }
+            }
+        }
+    }
+}
+
+impl Value {
+    /// Creates a list of values.
+    pub fn list<I, V>(values: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<Value>,
+    {
+        Value::List(values.into_iter().map(Into::into).collect())
+    }
+
+    fn write_sddl(&self, sddl: &mut String) -> Result<(), WinError> {
+        match self {
+            Value::String(s) => {
+                if s.contains('"') {
+                    return Err(WinError::new(
+                        ERROR_INVALID_PARAMETER,
+                        Some(format!("Conditional ACE strings cannot contain quotes: {}", s)),
+                    ));
+                }
+                let _ = write!(sddl, "\"{}\"", s);
+            }
+            Value::Int(i) => {
+                let _ = write!(sddl, "{}", i);
+            }
+            Value::Sid(sid) => {
+                let _ = write!(sddl, "SID({})", sid.to_string()?);
+            }
+            Value::List(values) => {
+                sddl.push('{');
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        sddl.push_str(", ");
+                    }
+                    value.write_sddl(sddl)?;
+                }
+                sddl.push('}');
+            }
+            Value::Attribute(attribute) => attribute.write_sddl(sddl),
+        }
+        Ok(())
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.to_owned())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Int(value)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        Value::Int(value as i64)
+    }
+}
+
+impl From<Sid> for Value {
+    fn from(value: Sid) -> Self {
+        Value::Sid(value)
+    }
+}
+
+impl From<Attribute> for Value {
+    fn from(value: Attribute) -> Self {
+        Value::Attribute(value)
+    }
+}
+
+impl Condition {
+    /// The user is a member of all the groups (`Member_of`).
+    pub fn member_of<I>(groups: I) -> Self
+    where
+        I: IntoIterator<Item = Sid>,
+    {
+        Condition::MemberOf(groups.into_iter().collect())
+    }
+
+    /// The user is a member of any of the groups (`Member_of_any`).
+    pub fn member_of_any<I>(groups: I) -> Self
+    where
+        I: IntoIterator<Item = Sid>,
+    {
+        Condition::MemberOfAny(groups.into_iter().collect())
+    }
+
+    /// The device is a member of all the groups (`Device_Member_of`).
+    pub fn device_member_of<I>(groups: I) -> Self
+    where
+        I: IntoIterator<Item = Sid>,
+    {
+        Condition::DeviceMemberOf(groups.into_iter().collect())
+    }
+
+    /// The device is a member of any of the groups (`Device_Member_of_any`).
+    pub fn device_member_of_any<I>(groups: I) -> Self
+    where
+        I: IntoIterator<Item = Sid>,
+    {
+        Condition::DeviceMemberOfAny(groups.into_iter().collect())
+    }
+
+    /// Both this condition and `other` hold (`&&`).
+    pub fn and(self, other: Condition) -> Self {
+        Condition::And(Box::new(self), Box::new(other))
+    }
+
+    /// This condition or `other` holds (`||`).
+    pub fn or(self, other: Condition) -> Self {
+        Condition::Or(Box::new(self), Box::new(other))
+    }
+
+    /// Renders the condition in SDDL, e.g., `((Member_of {SID(S-1-5-32-544)}) && (@Resource.Project == "X"))`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a string contains a quote or a SID cannot be converted.
+    pub fn to_sddl(&self) -> Result<String, WinError> {
+        let mut sddl = String::new();
+        self.write_sddl(&mut sddl)?;
+        Ok(sddl)
+    }
+
+    /// Renders the condition in the binary form stored in the application data of conditional
+    /// ACEs (starting with `artx`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the condition cannot be rendered or is rejected by the system.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, WinError> {
+        let everyone = Sid::from_string("S-1-1-0")?;
+        self.with_ace(AceType::AccessAllowedCallback, AceFlags::empty(), 1, &everyone, |ace| {
+            ace.application_data().unwrap_or_default().to_vec()
+        })
+    }
+
+    /// Renders a conditional ACE in SDDL, e.g., `(XA;OICI;0x1200a9;;;S-1-1-0;(...))`.
+    ///
+    /// # Arguments
+    ///
+    /// * `ace_type` - `AccessAllowedCallback` (`XA`), `AccessDeniedCallback` (`XD`) or
+    ///   `SystemAuditCallback` (`XU`).
+    /// * `flags` - The inheritance (and, for audit ACEs, audit) flags of the ACE.
+    /// * `mask` - The access mask of the ACE.
+    /// * `sid` - The trustee of the ACE.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ERROR_INVALID_PARAMETER` if `ace_type` is not a callback type, or an error if
+    /// the condition cannot be rendered.
+    pub fn ace_sddl<M>(&self, ace_type: AceType, flags: AceFlags, mask: M, sid: &Sid) -> Result<String, WinError>
+    where
+        M: IntoAccessMask,
+    {
+        let code = match ace_type {
+            AceType::AccessAllowedCallback => "XA",
+            AceType::AccessDeniedCallback => "XD",
+            AceType::SystemAuditCallback => "XU",
+            other => {
+                return Err(WinError::new(
+                    ERROR_INVALID_PARAMETER,
+                    Some(format!("Not a conditional ACE type: {:?}", other)),
+                ));
+            }
+        };
+        Ok(format!(
+            "({};{};0x{:x};;;{};({}))",
+            code,
+            flags.to_sddl(),
+            mask.as_u32(),
+            sid.to_string()?,
+            self.to_sddl()?
+        ))
+    }
+
+    /// Returns the bytes of a conditional ACE, assembled by the system from its SDDL form.
+    pub(crate) fn ace_bytes(
+        &self,
+        ace_type: AceType,
+        flags: AceFlags,
+        mask: u32,
+        sid: &Sid,
+    ) -> Result<Vec<u8>, WinError> {
+        self.with_ace(ace_type, flags, mask, sid, |ace| ace.as_bytes().to_vec())
+    }
+
+    /// Lets the system parse the SDDL form of a conditional ACE and passes the result to `f`.
+    fn with_ace<T, F>(&self, ace_type: AceType, flags: AceFlags, mask: u32, sid: &Sid, f: F) -> Result<T, WinError>
+    where
+        F: FnOnce(&Ace<'_>) -> T,
+    {
+        let ace = self.ace_sddl(ace_type, flags, AccessMask::from(mask), sid)?;
+        let audit = ace_type == AceType::SystemAuditCallback;
+        let sd = SecurityDescriptor::from_sd_string(format!("{}{}", if audit { "S:" } else { "D:" }, ace))?;
+        let acl = if audit { sd.sacl_unchecked() } else { sd.dacl() };
+        let result = acl.as_ref().and_then(|acl| acl.into_iter().next().map(|ace| f(&ace)));
+        result.ok_or_else(|| WinError::from("Missing conditional ACE"))
+    }
+
+    fn write_sddl(&self, sddl: &mut String) -> Result<(), WinError> {
+        let membership = |name: &str, sids: &[Sid], sddl: &mut String| {
+            let _ = write!(sddl, "({} ", name);
+            Value::List(sids.iter().cloned().map(Value::Sid).collect()).write_sddl(sddl)?;
+            sddl.push(')');
+            Ok::<(), WinError>(())
+        };
+        match self {
+            Condition::MemberOf(sids) => membership("Member_of", sids, sddl)?,
+            Condition::MemberOfAny(sids) => membership("Member_of_any", sids, sddl)?,
+            Condition::DeviceMemberOf(sids) => membership("Device_Member_of", sids, sddl)?,
+            Condition::DeviceMemberOfAny(sids) => membership("Device_Member_of_any", sids, sddl)?,
+            Condition::Exists(attribute) => {
+                sddl.push_str("(Exists ");
+                attribute.write_sddl(sddl);
+                sddl.push(')');
+            }
+            Condition::Compare(attribute, op, value) => {
+                sddl.push('(');
+                attribute.write_sddl(sddl);
+                sddl.push_str(match op {
+                    CompareOp::Equals => " == ",
+                    CompareOp::NotEquals => " != ",
+                    CompareOp::LessThan => " < ",
+                    CompareOp::LessOrEqual => " <= ",
+                    CompareOp::GreaterThan => " > ",
+                    CompareOp::GreaterOrEqual => " >= ",
+                    CompareOp::Contains => " Contains ",
+                    CompareOp::AnyOf => " Any_of ",
+                });
+                value.write_sddl(sddl)?;
+                sddl.push(')');
+            }
+            Condition::And(a, b) | Condition::Or(a, b) => {
+                sddl.push('(');
+                a.write_sddl(sddl)?;
+                sddl.push_str(if matches!(self, Condition::And(..)) {
+                    " && "
+                } else {
+                    " || "
+                });
+                b.write_sddl(sddl)?;
+                sddl.push(')');
+            }
+            Condition::Not(a) => {
+                sddl.push_str("(!");
+                a.write_sddl(sddl)?;
+                sddl.push(')');
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Not for Condition {
+    type Output = Condition;
+
+    fn not(self) -> Self::Output {
+        Condition::Not(Box::new(self))
+    }
+}
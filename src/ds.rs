@@ -0,0 +1,161 @@
+//! Active Directory schema GUID resolution.
+//!
+//! Object ACEs in the security descriptors of directory service objects (`SE_DS_OBJECT`)
+//! reference attributes, classes, property sets, validated writes and extended rights by GUID.
+//! This module maps those GUIDs to readable names, so delegation reports can say
+//! "Reset Password" instead of `{00299570-246D-11D0-A768-00AA006E0529}`.
+//!
+//! A table of commonly delegated schema elements is bundled (see [`WellKnownSchema`]). Other
+//! GUIDs (e.g., attributes added by schema extensions) can be resolved with a custom
+//! [`GuidResolver`], such as a `HashMap` populated from the directory.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use std::collections::HashMap;
+//!
+//! use win_acl_rs::{
+//!     ds::{GuidResolver, WellKnownSchema},
+//!     guid::Guid,
+//! };
+//!
+//! let guid: Guid = "00299570-246d-11d0-a768-00aa006e0529".parse()?;
+//! assert_eq!(WellKnownSchema.resolve(guid).as_deref(), Some("Reset Password"));
+//!
+//! // custom names take precedence, the bundled table is the fallback
+//! let mut custom = HashMap::new();
+//! custom.insert(Guid(0x12345678_0000_0000_0000_000000000000), "myCustomAttribute".to_owned());
+//! let resolver = (custom, WellKnownSchema);
+//! assert_eq!(resolver.resolve(guid).as_deref(), Some("Reset Password"));
+//! # Ok::<(), win_acl_rs::error::WinError>(())
+//! ```
+
+use std::collections::HashMap;
+
+use crate::guid::Guid;
+
+/// The kind of a schema element referenced by an object ACE.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SchemaObjectKind {
+    /// A control access right (e.g., *Reset Password*).
+    ExtendedRight,
+    /// A set of attributes controlled as a whole (e.g., *Personal Information*).
+    PropertySet,
+    /// A validated write (e.g., *Validated write to service principal name*).
+    ValidatedWrite,
+    /// A single attribute (e.g., `member`).
+    Attribute,
+    /// An object class (e.g., `user`).
+    Class,
+}
+
+/// Maps schema GUIDs to names.
+pub trait GuidResolver {
+    /// Returns the name of the schema element identified by `guid`, or `None` if it is unknown.
+    fn resolve(&self, guid: Guid) -> Option<String>;
+}
+
+/// Resolves the schema elements from the bundled table (see [`well_known_schema_entry`]).
+#[derive(Debug, Copy, Clone, Default)]
+pub struct WellKnownSchema;
+
+impl GuidResolver for WellKnownSchema {
+    fn resolve(&self, guid: Guid) -> Option<String> {
+        well_known_schema_entry(guid).map(|(name, _)| name.to_owned())
+    }
+}
+
+impl GuidResolver for HashMap<Guid, String> {
+    fn resolve(&self, guid: Guid) -> Option<String> {
+        self.get(&guid).cloned()
+    }
+}
+
+impl<F> GuidResolver for F
+where
+    F: Fn(Guid) -> Option<String>,
+{
+    fn resolve(&self, guid: Guid) -> Option<String> {
+        self(guid)
+    }
+}
+
+/// Tries the first resolver, then the second one.
+impl<A, B> GuidResolver for (A, B)
+where
+    A: GuidResolver,
+    B: GuidResolver,
+{
+    fn resolve(&self, guid: Guid) -> Option<String> {
+        self.0.resolve(guid).or_else(|| self.1.resolve(guid))
+    }
+}
+
+/// Returns the display name and kind of a commonly delegated schema element.
+///
+/// The bundled table covers the extended rights, property sets and validated writes defined by
+/// the base Active Directory schema and the attributes and classes most often found in
+/// delegations.
+pub fn well_known_schema_entry(guid: Guid) -> Option<(&'static str, SchemaObjectKind)> {
+    WELL_KNOWN_SCHEMA
+        .iter()
+        .find(|(g, _, _)| *g == guid.0)
+        .map(|(_, name, kind)| (*name, *kind))
+}
+
+#[rustfmt::skip]
+const WELL_KNOWN_SCHEMA: &[(u128, &str, SchemaObjectKind)] = {
+    use SchemaObjectKind::*;
+    &[
+        // extended rights
+        (0x00299570_246D_11D0_A768_00AA006E0529, "Reset Password", ExtendedRight),
+        (0xAB721A53_1E2F_11D0_9819_00AA0040529B, "Change Password", ExtendedRight),
+        (0xAB721A54_1E2F_11D0_9819_00AA0040529B, "Send As", ExtendedRight),
+        (0xAB721A55_1E2F_11D0_9819_00AA0040529B, "Send To", ExtendedRight),
+        (0xAB721A56_1E2F_11D0_9819_00AA0040529B, "Receive As", ExtendedRight),
+        (0x1131F6AA_9C07_11D1_F79F_00C04FC2DCD2, "Replicating Directory Changes", ExtendedRight),
+        (0x1131F6AD_9C07_11D1_F79F_00C04FC2DCD2, "Replicating Directory Changes All", ExtendedRight),
+        (0x89E95B76_444D_4C62_991A_0FACBEDA640C, "Replicating Directory Changes In Filtered Set", ExtendedRight),
+        (0x1131F6AB_9C07_11D1_F79F_00C04FC2DCD2, "Replication Synchronization", ExtendedRight),
+        (0x1131F6AC_9C07_11D1_F79F_00C04FC2DCD2, "Manage Replication Topology", ExtendedRight),
+        (0xEDACFD8F_FFB3_11D1_B41D_00A0C968F939, "Apply Group Policy", ExtendedRight),
+        (0x0E10C968_78FB_11D2_90D4_00C04F79DC55, "Enroll", ExtendedRight),
+        (0xA05B8CC2_17BC_4802_A710_E7C15AB866A2, "AutoEnroll", ExtendedRight),
+        (0x45EC5156_DB7E_47BB_B53F_DBEB2D03C40F, "Reanimate Tombstones", ExtendedRight),
+        (0xBA33815A_4F93_4C76_87F3_57574BFF8109, "Migrate SID History", ExtendedRight),
+        (0xCCC2DC7D_A6AD_4A7A_8846_C04E3CC53501, "Unexpire Password", ExtendedRight),
+        (0x280F369C_67C7_438E_AE98_1D46F3C6F541, "Update Password Not Required Bit", ExtendedRight),
+        (0x05C74C5E_4DEB_43B4_BD9F_86664C2A7FD5, "Enable Per User Reversibly Encrypted Password", ExtendedRight),
+        // property sets
+        (0x4C164200_20C0_11D0_A768_00AA006E0529, "Account Restrictions", PropertySet),
+        (0x5F202010_79A5_11D0_9020_00C04FC2D4CF, "Logon Information", PropertySet),
+        (0xBC0AC240_79A9_11D0_9020_00C04FC2D4CF, "Group Membership", PropertySet),
+        (0x59BA2F42_79A2_11D0_9020_00C04FC2D3CF, "General Information", PropertySet),
+        (0x77B5B886_944A_11D1_AEBD_0000F80367C1, "Personal Information", PropertySet),
+        (0xE48D0154_BCF8_11D1_8702_00C04FB96050, "Public Information", PropertySet),
+        (0x037088F8_0AE1_11D2_B422_00A0C968F939, "Remote Access Information", PropertySet),
+        (0xE45795B2_9455_11D1_AEBD_0000F80367C1, "Phone and Mail Options", PropertySet),
+        (0xE45795B3_9455_11D1_AEBD_0000F80367C1, "Web Information", PropertySet),
+        (0x91E647DE_D96F_4B70_9557_D63FF4F3CCD8, "Private Information", PropertySet),
+        // validated writes
+        (0xF3A64788_5306_11D1_A9C5_0000F80367C1, "Validated write to service principal name", ValidatedWrite),
+        (0x72E39547_7B18_11D1_ADEF_00C04FD8D5CD, "Validated write to DNS host name", ValidatedWrite),
+        // attributes
+        (0xBF9679C0_0DE6_11D0_A285_00AA003049E2, "member", Attribute),
+        (0xBF967A68_0DE6_11D0_A285_00AA003049E2, "userAccountControl", Attribute),
+        (0xBF967A0A_0DE6_11D0_A285_00AA003049E2, "pwdLastSet", Attribute),
+        (0xBF967950_0DE6_11D0_A285_00AA003049E2, "description", Attribute),
+        (0xBF967953_0DE6_11D0_A285_00AA003049E2, "displayName", Attribute),
+        (0x00FBF30C_91FE_11D1_AEBC_0000F80367C1, "altSecurityIdentities", Attribute),
+        (0xF30E3BBE_9FF0_11D1_B603_0000F80367C1, "gPLink", Attribute),
+        (0xF30E3BBF_9FF0_11D1_B603_0000F80367C1, "gPOptions", Attribute),
+        (0x3F78C3E5_F79A_46BD_A0B8_9D18116DDC79, "msDS-AllowedToActOnBehalfOfOtherIdentity", Attribute),
+        (0x5B47D60F_6090_40B2_9F37_2A4DE88F3063, "msDS-KeyCredentialLink", Attribute),
+        // classes
+        (0xBF967ABA_0DE6_11D0_A285_00AA003049E2, "user", Class),
+        (0xBF967A86_0DE6_11D0_A285_00AA003049E2, "computer", Class),
+        (0xBF967A9C_0DE6_11D0_A285_00AA003049E2, "group", Class),
+        (0xBF967AA5_0DE6_11D0_A285_00AA003049E2, "organizationalUnit", Class),
+        (0x4828CC14_1437_45BC_9B07_AD6F015E5F28, "inetOrgPerson", Class),
+    ]
+};
@@ -2,23 +2,28 @@
 //! the process requires "SE_SECURITY_NAME" (*SeSecurityPrivilege*) privilege, would otherwise return WIN32_ERROR(1314) => "A required privilege is not held by the client"
 //! you can run `whoami /priv` to check it. You typically need to run the process as an Administrator and enable it using enable_se_security_privilege().
 
-use std::{ffi::OsStr, marker::PhantomData, path::Path, ptr, ptr::null_mut};
+use std::{ffi::OsStr, marker::PhantomData, os::windows::io::AsRawHandle, path::Path, ptr, ptr::null_mut};
 
 use windows_sys::Win32::{
-    Foundation::{CloseHandle, ERROR_SUCCESS, GetLastError, HANDLE, LUID},
+    Foundation::{
+        CloseHandle, ERROR_ACCESS_DENIED, ERROR_PRIVILEGE_NOT_HELD, ERROR_SUCCESS, FALSE, GetLastError, HANDLE, LUID,
+    },
     Security::{
         AdjustTokenPrivileges,
         Authorization::{SE_FILE_OBJECT, SE_OBJECT_TYPE},
-        GetTokenInformation, LookupPrivilegeValueW, OBJECT_SECURITY_INFORMATION, SE_PRIVILEGE_ENABLED,
-        SE_SECURITY_NAME, TOKEN_ADJUST_PRIVILEGES, TOKEN_ELEVATION, TOKEN_PRIVILEGES, TOKEN_QUERY, TokenElevation,
+        CheckTokenMembership, GetTokenInformation, LookupPrivilegeValueW, OBJECT_SECURITY_INFORMATION, PSID,
+        SE_PRIVILEGE_ENABLED, SE_SECURITY_NAME, TOKEN_ADJUST_PRIVILEGES, TOKEN_ELEVATION, TOKEN_PRIVILEGES,
+        TOKEN_QUERY, TokenElevation,
     },
     System::Threading::{GetCurrentProcess, OpenProcessToken},
 };
 
 use crate::{
+    acl::{Acl, AclRef},
     error::WinError,
-    sd::{ObjectSecurityEx, SecurityDescriptorImpl},
-    utils::WideCString,
+    sd::{InheritedAces, ObjectSecurityEx, SecurityDescriptorImpl, SecurityInformation, set_inheritance},
+    sid::{AsSidRef, Sid},
+    wellknown::WinBuiltinAdministratorsSid,
     winapi_bool_call,
 };
 
@@ -122,6 +127,58 @@ pub fn is_admin() -> Result<bool, WinError> {
     }
 }
 
+/// Checks if the calling thread is effectively a member of a group.
+///
+/// Uses the impersonation token of the thread, or the primary token of the process. Groups
+/// that are present only for deny purposes do not count: under UAC, a non-elevated process of
+/// an administrator is *not* a member of `Administrators`.
+///
+/// # Arguments
+///
+/// * `sid` - The SID of the group (or user) to check.
+///
+/// # Errors
+///
+/// Returns an error if the membership cannot be checked.
+///
+/// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-checktokenmembership)
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::{elevated::is_member_of, sid::Sid, wellknown::WinBuiltinUsersSid};
+///
+/// let users = Sid::from_well_known_sid(WinBuiltinUsersSid)?;
+/// println!("Member of Users: {}", is_member_of(&users)?);
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+pub fn is_member_of<'a, S>(sid: &'a S) -> Result<bool, WinError>
+where
+    S: AsSidRef<'a>,
+{
+    let mut is_member = FALSE;
+    unsafe {
+        winapi_bool_call!(CheckTokenMembership(
+            null_mut(),
+            sid.as_sid_ref().as_ptr() as PSID,
+            &mut is_member
+        ))
+    };
+    Ok(is_member != FALSE)
+}
+
+/// Checks if the calling thread is effectively a member of the local `Administrators` group.
+///
+/// Unlike [`is_admin`], which only reports whether the token is elevated, this answers whether
+/// administrator access checks will pass, taking UAC filtering into account.
+///
+/// # Errors
+///
+/// Returns an error if the membership cannot be checked.
+pub fn current_user_is_admin_member() -> Result<bool, WinError> {
+    is_member_of(&Sid::from_well_known_sid(WinBuiltinAdministratorsSid)?)
+}
+
 /// A marker trait for privilege levels.
 ///
 /// Types implementing this trait represent different privilege levels for security operations.
@@ -236,7 +293,94 @@ impl PrivilegeTokenImpl<Elevated> {
     }
 }
 
+/// Disables SACL inheritance of a file or directory (sets `SE_SACL_PROTECTED`).
+///
+/// The inherited audit ACEs are either converted to explicit ones or removed.
+///
+/// # Arguments
+///
+/// * `_token` - an elevated privilege token.
+/// * `path` - Path to the file or directory.
+/// * `inherited` - What happens to the inherited ACEs.
+///
+/// # Errors
+///
+/// Returns an error if the SACL cannot be read or written.
+pub fn disable_sacl_inheritance<P>(
+    _token: &PrivilegeTokenImpl<Elevated>,
+    path: P,
+    inherited: InheritedAces,
+) -> Result<(), WinError>
+where
+    P: AsRef<Path>,
+{
+    set_inheritance(path.as_ref(), SecurityInformation::SACL, Some(inherited))
+}
+
+/// Enables SACL inheritance of a file or directory (clears `SE_SACL_PROTECTED`).
+///
+/// # Arguments
+///
+/// * `_token` - an elevated privilege token.
+/// * `path` - Path to the file or directory.
+///
+/// # Errors
+///
+/// Returns an error if the SACL cannot be read or written.
+pub fn enable_sacl_inheritance<P>(_token: &PrivilegeTokenImpl<Elevated>, path: P) -> Result<(), WinError>
+where
+    P: AsRef<Path>,
+{
+    set_inheritance(path.as_ref(), SecurityInformation::SACL, None)
+}
+
 impl SecurityDescriptorImpl<Elevated> {
+    /// Returns the SACL (System Access Control List) of the security descriptor.
+    ///
+    /// The SACL holds the audit entries of the object (and its mandatory label). Use
+    /// [`Ace::audit_on`](crate::acl::Ace::audit_on) to see which access attempts an entry audits.
+    ///
+    /// # Returns
+    ///
+    /// `Some(Acl)` containing the SACL if present, or `None` if the security descriptor
+    /// doesn't have a SACL.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::elevated::{PrivilegeToken, SecurityDescriptorElevated};
+    ///
+    /// let token = PrivilegeToken::new().try_elevate()?;
+    /// let sd = SecurityDescriptorElevated::from_path(&token, "C:\\path\\to\\file.txt")?;
+    /// if let Some(sacl) = sd.sacl() {
+    ///     for ace in &sacl {
+    ///         if let Some(on) = ace.audit_on() {
+    ///             println!("{:?} audits 0x{:X} on {:?}", ace.sid()?, ace.mask(), on);
+    ///         }
+    ///     }
+    /// }
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn sacl(&self) -> Option<AclRef<'_>> {
+        self.sacl_unchecked()
+    }
+
+    /// Replaces the SACL of the security descriptor.
+    ///
+    /// The ACL is copied into the buffer of the security descriptor. The owner, group, DACL,
+    /// the inheritance control flags and the origin are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `sacl` - The new SACL.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the security descriptor cannot be rebuilt.
+    pub fn set_sacl(&mut self, sacl: Acl) -> Result<(), WinError> {
+        self.replace_acls(None, Some(&sacl))
+    }
+
     /// Creates a SecurityDescriptor from path to the "file object"
     ///
     /// # Arguments
@@ -251,15 +395,91 @@ impl SecurityDescriptorImpl<Elevated> {
     where
         P: AsRef<Path>,
     {
-        let wide_path = WideCString::new(OsStr::new(path.as_ref()));
-
         Self::create_sd(
-            wide_path.as_ptr(),
+            path.as_ref().as_os_str(),
             SE_FILE_OBJECT,
             OBJECT_SECURITY_INFORMATION::get_all(),
         )
     }
 
+    /// Creates a SecurityDescriptor from path to the "file object", reading only the selected components.
+    ///
+    /// # Arguments
+    ///
+    /// * `_token` - an elevated privilege token.
+    /// * `path` - Path to the file.
+    /// * `components` - The components to read (e.g., `SecurityInformation::SACL`).
+    ///
+    /// # Returns
+    ///
+    /// A `SecurityDescriptor` on success.
+    pub fn from_path_with<P>(
+        _token: &PrivilegeTokenImpl<Elevated>,
+        path: P,
+        components: SecurityInformation,
+    ) -> Result<Self, WinError>
+    where
+        P: AsRef<Path>,
+    {
+        Self::create_sd(path.as_ref().as_os_str(), SE_FILE_OBJECT, components.0)
+    }
+
+    /// Creates a SecurityDescriptor from path to the "file object", reading as much as permitted.
+    ///
+    /// The owner, group and DACL all need `READ_CONTROL` access, so they are read together. Only
+    /// the SACL is dropped when it cannot be read (e.g., the privilege is not enabled), instead
+    /// of failing outright. Useful for best-effort audits of trees with mixed permissions.
+    ///
+    /// There is no unprivileged counterpart on `SecurityDescriptor`: it never reads the SACL,
+    /// and requesting fewer of the remaining components cannot succeed where reading all of them
+    /// failed.
+    ///
+    /// # Arguments
+    ///
+    /// * `_token` - an elevated privilege token.
+    /// * `path` - Path to the file.
+    ///
+    /// # Returns
+    ///
+    /// The (possibly partial) `SecurityDescriptor` and the components that could not be read,
+    /// i.e. `SecurityInformation::SACL` or none.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the owner, group and DACL cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{
+    ///     elevated::{PrivilegeToken, SecurityDescriptorElevated},
+    ///     sd::SecurityInformation,
+    /// };
+    ///
+    /// let token = PrivilegeToken::new().try_elevate()?;
+    /// let (sd, missing) = SecurityDescriptorElevated::from_path_best_effort(&token, "C:\\Windows\\CSC")?;
+    /// if missing.contains(SecurityInformation::SACL) {
+    ///     println!("SACL is not readable");
+    /// }
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn from_path_best_effort<P>(
+        _token: &PrivilegeTokenImpl<Elevated>,
+        path: P,
+    ) -> Result<(Self, SecurityInformation), WinError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref().as_os_str();
+        match Self::create_sd(path, SE_FILE_OBJECT, OBJECT_SECURITY_INFORMATION::get_all()) {
+            Err(error) if error.code == ERROR_ACCESS_DENIED || error.code == ERROR_PRIVILEGE_NOT_HELD => {
+                let sd = Self::create_sd(path, SE_FILE_OBJECT, OBJECT_SECURITY_INFORMATION::get_safe())?;
+                Ok((sd, SecurityInformation::SACL))
+            }
+            result => result.map(|sd| (sd, SecurityInformation::empty())),
+        }
+    }
+
     /// Creates a SecurityDescriptor from object name and object type.
     ///
     /// # Arguments
@@ -281,9 +501,36 @@ impl SecurityDescriptorImpl<Elevated> {
     where
         S: AsRef<str>,
     {
-        let wide_string = WideCString::new(handle.as_ref());
         Self::create_sd(
-            wide_string.as_ptr(),
+            OsStr::new(handle.as_ref()),
+            object_type,
+            OBJECT_SECURITY_INFORMATION::get_all(),
+        )
+    }
+
+    /// Creates a SecurityDescriptor, including the SACL, from an open handle using `GetSecurityInfo`.
+    ///
+    /// The handle must have been opened with `READ_CONTROL` and `ACCESS_SYSTEM_SECURITY` access.
+    ///
+    /// # Arguments
+    ///
+    /// * `_token` - an elevated privilege token.
+    /// * `handle` - The open object.
+    /// * `object_type` - a type of the object (e.g., `SE_FILE_OBJECT` or `SE_KERNEL_OBJECT`)
+    ///
+    /// # Returns
+    ///
+    /// A `SecurityDescriptor` on success.
+    pub fn from_raw_handle<H>(
+        _token: &PrivilegeTokenImpl<Elevated>,
+        handle: &H,
+        object_type: SE_OBJECT_TYPE,
+    ) -> Result<Self, WinError>
+    where
+        H: AsRawHandle,
+    {
+        Self::create_sd_from_handle(
+            handle.as_raw_handle() as HANDLE,
             object_type,
             OBJECT_SECURITY_INFORMATION::get_all(),
         )
@@ -0,0 +1,176 @@
+//! Event log channel access.
+//!
+//! Every event log channel has a `ChannelAccess` security descriptor (stored as SDDL) controlling
+//! who can read, write and clear the channel. This module reads and writes it through the
+//! Windows Event Log configuration APIs.
+//!
+//! Changing the access of a channel requires running as an Administrator.
+//!
+//! see [MSDN](https://learn.microsoft.com/en-us/windows/win32/wes/eventmanifestschema-channeltype-complextype)
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use win_acl_rs::{eventlog, sd::SecurityDescriptor};
+//!
+//! let sd = eventlog::channel_access("Microsoft-Windows-PowerShell/Operational")?;
+//! println!("{}", sd.as_sd_string()?);
+//!
+//! // Only SYSTEM and Administrators may read the channel
+//! let restricted = SecurityDescriptor::from_sd_string("O:BAG:SYD:(A;;0xf0007;;;SY)(A;;0x7;;;BA)")?;
+//! eventlog::set_channel_access("Microsoft-Windows-PowerShell/Operational", &restricted)?;
+//! # Ok::<(), win_acl_rs::error::WinError>(())
+//! ```
+
+use std::{mem::size_of, ptr::null_mut};
+
+use windows_sys::Win32::{
+    Foundation::{ERROR_INSUFFICIENT_BUFFER, ERROR_INVALID_DATA, GetLastError},
+    System::EventLog::{
+        EVT_HANDLE, EVT_VARIANT, EVT_VARIANT_0, EvtChannelConfigAccess, EvtClose, EvtGetChannelConfigProperty,
+        EvtOpenChannelConfig, EvtSaveChannelConfig, EvtSetChannelConfigProperty, EvtVarTypeString,
+    },
+};
+
+use crate::{error::WinError, sd::SecurityDescriptor, utils::WideCString, winapi_bool_call};
+
+/// An open handle to the configuration of an event log channel.
+struct ChannelConfig {
+    handle: EVT_HANDLE,
+}
+
+impl ChannelConfig {
+    fn open(channel: &str) -> Result<Self, WinError> {
+        let wide_channel = WideCString::new(channel);
+        let handle = unsafe { EvtOpenChannelConfig(0, wide_channel.as_ptr(), 0) };
+        if handle == 0 {
+            return Err(unsafe { GetLastError() }.into());
+        }
+        Ok(Self { handle })
+    }
+
+    fn access(&self) -> Result<String, WinError> {
+        let mut used = 0u32;
+        let result =
+            unsafe { EvtGetChannelConfigProperty(self.handle, EvtChannelConfigAccess, 0, 0, null_mut(), &mut used) };
+        if result == 0 {
+            let err = unsafe { GetLastError() };
+            if err != ERROR_INSUFFICIENT_BUFFER {
+                return Err(err.into());
+            }
+        }
+
+        // u64 elements keep the variant properly aligned
+        let mut buffer = vec![0u64; (used as usize).div_ceil(size_of::<u64>())];
+        unsafe {
+            winapi_bool_call!(EvtGetChannelConfigProperty(
+                self.handle,
+                EvtChannelConfigAccess,
+                0,
+                (buffer.len() * size_of::<u64>()) as u32,
+                buffer.as_mut_ptr() as *mut EVT_VARIANT,
+                &mut used,
+            ))
+        };
+
+        let variant = unsafe { &*(buffer.as_ptr() as *const EVT_VARIANT) };
+        if variant.Type != EvtVarTypeString as u32 {
+            return Err(ERROR_INVALID_DATA.into());
+        }
+        Ok(WideCString::from_wide_null_ptr(unsafe { variant.Anonymous.StringVal }).as_string())
+    }
+
+    fn set_access(&self, sddl: &str) -> Result<(), WinError> {
+        let wide_sddl = WideCString::new(sddl);
+        let variant = EVT_VARIANT {
+            Anonymous: EVT_VARIANT_0 {
+                StringVal: wide_sddl.as_ptr(),
+            },
+            Count: 0,
+            Type: EvtVarTypeString as u32,
+        };
+
+        unsafe {
+            winapi_bool_call!(EvtSetChannelConfigProperty(
+                self.handle,
+                EvtChannelConfigAccess,
+                0,
+                &variant
+            ));
+            winapi_bool_call!(EvtSaveChannelConfig(self.handle, 0));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ChannelConfig {
+    fn drop(&mut self) {
+        unsafe { EvtClose(self.handle) };
+    }
+}
+
+/// Reads the access security descriptor of an event log channel.
+///
+/// # Arguments
+///
+/// * `channel` - The name of the channel (e.g., `Application` or `Microsoft-Windows-PowerShell/Operational`).
+///
+/// # Errors
+///
+/// Returns an error if the channel does not exist or its configuration cannot be read.
+pub fn channel_access<S>(channel: S) -> Result<SecurityDescriptor, WinError>
+where
+    S: AsRef<str>,
+{
+    let sddl = ChannelConfig::open(channel.as_ref())?.access()?;
+    SecurityDescriptor::from_sd_string(sddl)
+}
+
+/// Reads the access SDDL string of an event log channel as stored in its configuration.
+///
+/// # Errors
+///
+/// Returns an error if the channel does not exist or its configuration cannot be read.
+pub fn channel_access_sddl<S>(channel: S) -> Result<String, WinError>
+where
+    S: AsRef<str>,
+{
+    ChannelConfig::open(channel.as_ref())?.access()
+}
+
+/// Replaces the access security descriptor of an event log channel.
+///
+/// # Arguments
+///
+/// * `channel` - The name of the channel.
+/// * `sd` - The new access security descriptor.
+///
+/// # Errors
+///
+/// Returns an error if the channel does not exist or its configuration cannot be saved
+/// (e.g., the process is not running as an Administrator).
+pub fn set_channel_access<S>(channel: S, sd: &SecurityDescriptor) -> Result<(), WinError>
+where
+    S: AsRef<str>,
+{
+    let sddl = sd.as_sd_string()?;
+    ChannelConfig::open(channel.as_ref())?.set_access(&sddl)
+}
+
+/// Replaces the access of an event log channel with an SDDL string.
+///
+/// The string is parsed first, so a malformed string is rejected instead of being stored.
+/// The parsed descriptor is stored, so aliases and rights may be written in a different form.
+///
+/// # Errors
+///
+/// Returns an error if `sddl` is not a valid security descriptor string, the channel does not
+/// exist or its configuration cannot be saved.
+pub fn set_channel_access_sddl<S, T>(channel: S, sddl: T) -> Result<(), WinError>
+where
+    S: AsRef<str>,
+    T: AsRef<str>,
+{
+    let sd = SecurityDescriptor::from_sd_string(sddl)?;
+    set_channel_access(channel, &sd)
+}
@@ -0,0 +1,101 @@
+//! File system path helpers.
+//!
+//! Windows APIs limit ordinary paths to `MAX_PATH` (260) characters. Longer paths must use the
+//! extended-length form: `\\?\C:\...` for local paths and `\\?\UNC\server\share\...` for shares.
+//! The file system APIs of this crate switch to that form automatically when a path is too long,
+//! and [`to_extended_path`] exposes the conversion.
+//!
+//! see [MSDN](https://learn.microsoft.com/en-us/windows/win32/fileio/maximum-file-path-limitation)
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use std::path::Path;
+//!
+//! use win_acl_rs::fs::to_extended_path;
+//!
+//! assert_eq!(to_extended_path(r"C:\data")?, Path::new(r"\\?\C:\data"));
+//! assert_eq!(to_extended_path(r"\\server\share\data")?, Path::new(r"\\?\UNC\server\share\data"));
+//! # Ok::<(), win_acl_rs::error::WinError>(())
+//! ```
+
+use std::{
+    borrow::Cow,
+    ffi::{OsStr, OsString},
+    os::windows::ffi::{OsStrExt, OsStringExt},
+    path::{Path, PathBuf},
+};
+
+use windows_sys::Win32::Foundation::ERROR_INVALID_PARAMETER;
+
+use crate::error::WinError;
+
+/// Paths at least this long are converted to the extended-length form.
+///
+/// This is `MAX_PATH` minus the room for an 8.3 file name, the limit for directories.
+const LONG_PATH_THRESHOLD: usize = 248;
+
+const VERBATIM_PREFIX: &str = r"\\?\";
+const DEVICE_PREFIX: &str = r"\\.\";
+const NT_PREFIX: &str = r"\??\";
+const UNC_PREFIX: &str = r"\\";
+const VERBATIM_UNC_PREFIX: &str = r"\\?\UNC\";
+
+fn starts_with(wide: &[u16], prefix: &str) -> bool {
+    let prefix: Vec<u16> = prefix.encode_utf16().collect();
+    wide.starts_with(&prefix)
+}
+
+/// Converts a path to the extended-length (`\\?\`) form.
+///
+/// Relative paths are resolved against the current directory, `.` and `..` components are
+/// resolved and forward slashes are converted to backslashes, since the extended-length form
+/// is passed to the file system verbatim. UNC paths are converted to the `\\?\UNC\` form.
+/// Paths already in the extended-length or device (`\\.\`) form are returned unchanged.
+///
+/// # Errors
+///
+/// Returns an error if the path is empty or cannot be made absolute.
+pub fn to_extended_path<P>(path: P) -> Result<PathBuf, WinError>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let wide: Vec<u16> = path.as_os_str().encode_wide().collect();
+    if starts_with(&wide, VERBATIM_PREFIX) || starts_with(&wide, DEVICE_PREFIX) || starts_with(&wide, NT_PREFIX) {
+        return Ok(path.to_path_buf());
+    }
+
+    let absolute = std::path::absolute(path).map_err(|e| {
+        WinError::new(
+            e.raw_os_error().map_or(ERROR_INVALID_PARAMETER, |code| code as u32),
+            Some(format!("Cannot make the path absolute: {}", path.display())),
+        )
+    })?;
+    let absolute: Vec<u16> = absolute.as_os_str().encode_wide().collect();
+
+    let mut extended: Vec<u16> = Vec::with_capacity(absolute.len() + VERBATIM_UNC_PREFIX.len());
+    if starts_with(&absolute, VERBATIM_PREFIX) {
+        extended.extend_from_slice(&absolute);
+    } else if starts_with(&absolute, UNC_PREFIX) {
+        extended.extend(VERBATIM_UNC_PREFIX.encode_utf16());
+        extended.extend_from_slice(&absolute[UNC_PREFIX.len()..]);
+    } else {
+        extended.extend(VERBATIM_PREFIX.encode_utf16());
+        extended.extend_from_slice(&absolute);
+    }
+    Ok(OsString::from_wide(&extended).into())
+}
+
+/// Returns the name to pass to the Windows APIs for a file object: the path itself if it is
+/// short enough, its extended-length form otherwise.
+pub(crate) fn api_path(path: &OsStr) -> Cow<'_, OsStr> {
+    if path.encode_wide().count() < LONG_PATH_THRESHOLD {
+        return Cow::Borrowed(path);
+    }
+    match to_extended_path(path) {
+        Ok(extended) => Cow::Owned(extended.into_os_string()),
+        // let the API report the problem
+        Err(_) => Cow::Borrowed(path),
+    }
+}
@@ -0,0 +1,109 @@
+//! Globally unique identifiers.
+//!
+//! GUIDs identify audit subcategories, directory service object classes and attributes
+//! and other schema elements referenced by security APIs.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use win_acl_rs::guid::Guid;
+//!
+//! let guid: Guid = "{0CCE921D-69AE-11D9-BED3-505054503030}".parse()?;
+//! assert_eq!(guid, Guid(0x0CCE921D_69AE_11D9_BED3_505054503030));
+//! assert_eq!(guid.to_string(), "{0CCE921D-69AE-11D9-BED3-505054503030}");
+//! # Ok::<(), win_acl_rs::error::WinError>(())
+//! ```
+
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use windows_sys::{Win32::Foundation::ERROR_INVALID_PARAMETER, core::GUID};
+
+use crate::error::WinError;
+
+/// A globally unique identifier, stored as a 128-bit integer in its textual (big-endian) order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[repr(transparent)]
+pub struct Guid(pub u128);
+
+impl Guid {
+    /// Parses a GUID in the `XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX` form, optionally enclosed in braces.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string is not a well-formed GUID.
+    pub fn parse<S>(s: S) -> Result<Self, WinError>
+    where
+        S: AsRef<str>,
+    {
+        let s = s.as_ref();
+        let bare = s.strip_prefix('{').and_then(|g| g.strip_suffix('}')).unwrap_or(s);
+
+        let well_formed = bare.len() == 36
+            && bare.char_indices().all(|(i, c)| match i {
+                8 | 13 | 18 | 23 => c == '-',
+                _ => c.is_ascii_hexdigit(),
+            });
+        let value = well_formed
+            .then(|| u128::from_str_radix(&bare.replace('-', ""), 16).ok())
+            .flatten();
+
+        value
+            .map(Guid)
+            .ok_or_else(|| WinError::new(ERROR_INVALID_PARAMETER, Some(format!("Invalid GUID: {}", s))))
+    }
+}
+
+impl Display for Guid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let v = self.0;
+        write!(
+            f,
+            "{{{:08X}-{:04X}-{:04X}-{:04X}-{:012X}}}",
+            v >> 96,
+            (v >> 80) & 0xFFFF,
+            (v >> 64) & 0xFFFF,
+            (v >> 48) & 0xFFFF,
+            v & 0xFFFF_FFFF_FFFF
+        )
+    }
+}
+
+impl FromStr for Guid {
+    type Err = WinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Guid::parse(s)
+    }
+}
+
+impl From<u128> for Guid {
+    fn from(value: u128) -> Self {
+        Guid(value)
+    }
+}
+
+impl From<Guid> for u128 {
+    fn from(guid: Guid) -> Self {
+        guid.0
+    }
+}
+
+impl From<GUID> for Guid {
+    fn from(guid: GUID) -> Self {
+        Guid(
+            (guid.data1 as u128) << 96
+                | (guid.data2 as u128) << 80
+                | (guid.data3 as u128) << 64
+                | u64::from_be_bytes(guid.data4) as u128,
+        )
+    }
+}
+
+impl From<Guid> for GUID {
+    fn from(guid: Guid) -> Self {
+        GUID::from_u128(guid.0)
+    }
+}
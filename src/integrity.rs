@@ -0,0 +1,331 @@
+//! Mandatory integrity control.
+//!
+//! Every process runs at an integrity level (e.g., *Medium* for standard user processes, *High*
+//! for elevated ones) and securable objects may carry a mandatory label with an integrity level
+//! and a policy. A process cannot write to (and, depending on the policy, read or execute) an
+//! object labeled with a higher integrity level than its own, regardless of the object's DACL.
+//!
+//! This module compares integrity levels and evaluates the label policies, which is what sandbox
+//! tooling needs to reason about, e.g., whether a medium integrity process can modify an object
+//! labeled high.
+//!
+//! see [MSDN](https://learn.microsoft.com/en-us/windows/win32/secauthz/mandatory-integrity-control)
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use win_acl_rs::integrity::{IntegrityLevel, MandatoryLabel, MandatoryPolicy};
+//!
+//! let current = IntegrityLevel::current_process()?;
+//! let label = MandatoryLabel::new(IntegrityLevel::HIGH, MandatoryPolicy::NO_WRITE_UP);
+//!
+//! if current < IntegrityLevel::HIGH && !label.can_write(current) {
+//!     println!("{} cannot write to an object labeled {}", current, label.level());
+//! }
+//! # Ok::<(), win_acl_rs::error::WinError>(())
+//! ```
+
+use std::{
+    fmt::{Display, Formatter},
+    mem::size_of,
+    ptr::null_mut,
+};
+
+use windows_sys::Win32::{
+    Foundation::{CloseHandle, ERROR_INSUFFICIENT_BUFFER, ERROR_INVALID_SID, GetLastError, HANDLE},
+    Security::{
+        GetSidIdentifierAuthority, GetSidSubAuthority, GetSidSubAuthorityCount, GetTokenInformation, PSID,
+        TOKEN_MANDATORY_LABEL, TOKEN_QUERY, TokenIntegrityLevel,
+    },
+    System::{
+        SystemServices::{
+            SECURITY_MANDATORY_HIGH_RID, SECURITY_MANDATORY_LOW_RID, SECURITY_MANDATORY_MEDIUM_PLUS_RID,
+            SECURITY_MANDATORY_MEDIUM_RID, SECURITY_MANDATORY_PROTECTED_PROCESS_RID, SECURITY_MANDATORY_SYSTEM_RID,
+            SECURITY_MANDATORY_UNTRUSTED_RID, SYSTEM_MANDATORY_LABEL_NO_EXECUTE_UP, SYSTEM_MANDATORY_LABEL_NO_READ_UP,
+            SYSTEM_MANDATORY_LABEL_NO_WRITE_UP,
+        },
+        Threading::{GetCurrentProcess, OpenProcess, OpenProcessToken, PROCESS_QUERY_LIMITED_INFORMATION},
+    },
+};
+
+use crate::{
+    acl::{AceFlags, Acl},
+    error::WinError,
+    mask::bit_ops,
+    sid::{AsSidRef, Sid},
+    winapi_bool_call,
+};
+
+/// The identifier authority of mandatory label SIDs (`S-1-16-*`).
+const MANDATORY_LABEL_AUTHORITY: [u8; 6] = [0, 0, 0, 0, 0, 16];
+
+/// An integrity level, identified by the relative identifier of its mandatory label SID.
+///
+/// Integrity levels are ordered, so they can be compared with the usual operators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct IntegrityLevel(pub u32);
+
+impl IntegrityLevel {
+    /// Untrusted integrity level (`S-1-16-0`).
+    pub const UNTRUSTED: Self = Self(SECURITY_MANDATORY_UNTRUSTED_RID as u32);
+    /// Low integrity level (`S-1-16-4096`), used by sandboxed processes.
+    pub const LOW: Self = Self(SECURITY_MANDATORY_LOW_RID as u32);
+    /// Medium integrity level (`S-1-16-8192`), used by standard user processes.
+    pub const MEDIUM: Self = Self(SECURITY_MANDATORY_MEDIUM_RID as u32);
+    /// Medium plus integrity level (`S-1-16-8448`).
+    pub const MEDIUM_PLUS: Self = Self(SECURITY_MANDATORY_MEDIUM_PLUS_RID);
+    /// High integrity level (`S-1-16-12288`), used by elevated processes.
+    pub const HIGH: Self = Self(SECURITY_MANDATORY_HIGH_RID as u32);
+    /// System integrity level (`S-1-16-16384`), used by system services.
+    pub const SYSTEM: Self = Self(SECURITY_MANDATORY_SYSTEM_RID as u32);
+    /// Protected process integrity level (`S-1-16-20480`).
+    pub const PROTECTED_PROCESS: Self = Self(SECURITY_MANDATORY_PROTECTED_PROCESS_RID as u32);
+
+    /// Reads the integrity level from a mandatory label SID (`S-1-16-*`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `ERROR_INVALID_SID` if the SID is not a mandatory label SID.
+    pub fn from_sid<'a, S>(sid: &'a S) -> Result<Self, WinError>
+    where
+        S: AsSidRef<'a>,
+    {
+        unsafe { Self::from_sid_ptr(sid.as_sid_ref().as_ptr() as PSID) }
+    }
+
+    /// Returns the mandatory label SID of the integrity level.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the SID cannot be created.
+    pub fn to_sid(&self) -> Result<Sid, WinError> {
+        Sid::from_string(format!("S-1-16-{}", self.0))
+    }
+
+    /// Returns the integrity level of the current process.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the process token cannot be queried.
+    pub fn current_process() -> Result<Self, WinError> {
+        unsafe { Self::of_process_handle(GetCurrentProcess()) }
+    }
+
+    /// Returns the integrity level of a process.
+    ///
+    /// # Arguments
+    ///
+    /// * `pid` - The identifier of the process.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the process cannot be opened or its token cannot be queried.
+    pub fn of_process(pid: u32) -> Result<Self, WinError> {
+        let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) };
+        if process.is_null() {
+            return Err(unsafe { GetLastError() }.into());
+        }
+        let level = unsafe { Self::of_process_handle(process) };
+        unsafe { CloseHandle(process) };
+        level
+    }
+
+    unsafe fn of_process_handle(process: HANDLE) -> Result<Self, WinError> {
+        let mut token: HANDLE = null_mut();
+        unsafe { winapi_bool_call!(OpenProcessToken(process, TOKEN_QUERY, &mut token)) };
+
+        let mut size = 0u32;
+        let result = unsafe { GetTokenInformation(token, TokenIntegrityLevel, null_mut(), 0, &mut size) };
+        if result == 0 {
+            let err = unsafe { GetLastError() };
+            if err != ERROR_INSUFFICIENT_BUFFER {
+                unsafe { CloseHandle(token) };
+                return Err(err.into());
+            }
+        }
+
+        // u64 elements keep the label properly aligned
+        let mut buffer = vec![0u64; (size as usize).div_ceil(size_of::<u64>())];
+        unsafe {
+            winapi_bool_call!(
+                GetTokenInformation(token, TokenIntegrityLevel, buffer.as_mut_ptr() as _, size, &mut size),
+                {
+                    CloseHandle(token);
+                }
+            );
+            CloseHandle(token);
+        }
+
+        let label = unsafe { &*(buffer.as_ptr() as *const TOKEN_MANDATORY_LABEL) };
+        unsafe { Self::from_sid_ptr(label.Label.Sid) }
+    }
+
+    unsafe fn from_sid_ptr(sid: PSID) -> Result<Self, WinError> {
+        unsafe {
+            let authority = &*GetSidIdentifierAuthority(sid);
+            let count = *GetSidSubAuthorityCount(sid);
+            if authority.Value != MANDATORY_LABEL_AUTHORITY || count == 0 {
+                return Err(ERROR_INVALID_SID.into());
+            }
+            Ok(Self(*GetSidSubAuthority(sid, count as u32 - 1)))
+        }
+    }
+}
+
+impl Display for IntegrityLevel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::UNTRUSTED => f.write_str("Untrusted"),
+            Self::LOW => f.write_str("Low"),
+            Self::MEDIUM => f.write_str("Medium"),
+            Self::MEDIUM_PLUS => f.write_str("Medium Plus"),
+            Self::HIGH => f.write_str("High"),
+            Self::SYSTEM => f.write_str("System"),
+            Self::PROTECTED_PROCESS => f.write_str("Protected Process"),
+            Self(rid) => write!(f, "S-1-16-{}", rid),
+        }
+    }
+}
+
+impl From<IntegrityLevel> for u32 {
+    fn from(level: IntegrityLevel) -> Self {
+        level.0
+    }
+}
+
+impl From<u32> for IntegrityLevel {
+    fn from(value: u32) -> Self {
+        IntegrityLevel(value)
+    }
+}
+
+/// The policy of a mandatory label, restricting access by lower integrity subjects.
+///
+/// All bitwise operations (`|`, `&`, `|=`, `&=`, `!`) are supported for combining policies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct MandatoryPolicy(pub u32);
+
+impl MandatoryPolicy {
+    /// Lower integrity subjects cannot write to the object.
+    pub const NO_WRITE_UP: Self = Self(SYSTEM_MANDATORY_LABEL_NO_WRITE_UP);
+    /// Lower integrity subjects cannot read the object.
+    pub const NO_READ_UP: Self = Self(SYSTEM_MANDATORY_LABEL_NO_READ_UP);
+    /// Lower integrity subjects cannot execute the object.
+    pub const NO_EXECUTE_UP: Self = Self(SYSTEM_MANDATORY_LABEL_NO_EXECUTE_UP);
+
+    /// Checks whether all policies in `other` are set.
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl From<MandatoryPolicy> for u32 {
+    fn from(policy: MandatoryPolicy) -> Self {
+        policy.0
+    }
+}
+
+impl From<u32> for MandatoryPolicy {
+    fn from(value: u32) -> Self {
+        MandatoryPolicy(value)
+    }
+}
+
+bit_ops!(MandatoryPolicy);
+
+/// A mandatory label of an object: an integrity level and a policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MandatoryLabel {
+    level: IntegrityLevel,
+    policy: MandatoryPolicy,
+}
+
+impl MandatoryLabel {
+    /// Creates a mandatory label.
+    pub fn new(level: IntegrityLevel, policy: MandatoryPolicy) -> Self {
+        Self { level, policy }
+    }
+
+    /// Returns the label Windows assumes for objects without an explicit label:
+    /// medium integrity level with the `NO_WRITE_UP` policy.
+    pub fn implicit() -> Self {
+        Self::new(IntegrityLevel::MEDIUM, MandatoryPolicy::NO_WRITE_UP)
+    }
+
+    /// Returns the integrity level of the label.
+    pub fn level(&self) -> IntegrityLevel {
+        self.level
+    }
+
+    /// Returns the policy of the label.
+    pub fn policy(&self) -> MandatoryPolicy {
+        self.policy
+    }
+
+    /// Checks whether a subject at `subject` integrity level may write to the labeled object.
+    pub fn can_write(&self, subject: IntegrityLevel) -> bool {
+        self.allows(subject, MandatoryPolicy::NO_WRITE_UP)
+    }
+
+    /// Checks whether a subject at `subject` integrity level may read the labeled object.
+    pub fn can_read(&self, subject: IntegrityLevel) -> bool {
+        self.allows(subject, MandatoryPolicy::NO_READ_UP)
+    }
+
+    /// Checks whether a subject at `subject` integrity level may execute the labeled object.
+    pub fn can_execute(&self, subject: IntegrityLevel) -> bool {
+        self.allows(subject, MandatoryPolicy::NO_EXECUTE_UP)
+    }
+
+    fn allows(&self, subject: IntegrityLevel, restriction: MandatoryPolicy) -> bool {
+        subject >= self.level || !self.policy.contains(restriction)
+    }
+
+    /// Decodes the mandatory label stored in a SACL.
+    ///
+    /// # Returns
+    ///
+    /// The label of the first label ACE, or `None` if the SACL has no label ACE.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the SACL cannot be read or the label ACE is malformed.
+    pub fn from_sacl(sacl: &Acl) -> Result<Option<Self>, WinError> {
+        label_of(sacl)
+    }
+
+    /// Creates a SACL holding only this label, ready to be written with `SecurityInformation::LABEL`.
+    ///
+    /// # Arguments
+    ///
+    /// * `flags` - The inheritance flags of the label ACE (e.g., `AceFlags::OBJECT_INHERIT |
+    ///   AceFlags::CONTAINER_INHERIT` to label a directory and its contents).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ACL cannot be created.
+    pub fn to_sacl(&self, flags: AceFlags) -> Result<Acl, WinError> {
+        let mut sacl = Acl::empty()?;
+        sacl.add_mandatory_label(*self, flags)?;
+        Ok(sacl)
+    }
+}
+
+/// Returns the mandatory label stored in a SACL, if any.
+pub(crate) fn label_of(sacl: &Acl) -> Result<Option<MandatoryLabel>, WinError> {
+    for ace in sacl.try_iter() {
+        if let Some(label) = ace?.mandatory_label()? {
+            return Ok(Some(label));
+        }
+    }
+    Ok(None)
+}
+
+/// Checks whether a subject at `from` integrity level may write to an object labeled `to`.
+///
+/// This is a shorthand for [`MandatoryLabel::can_write`].
+pub fn can_write_up(from: IntegrityLevel, to: &MandatoryLabel) -> bool {
+    to.can_write(from)
+}
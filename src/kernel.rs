@@ -0,0 +1,237 @@
+//! Kernel object security.
+//!
+//! Processes, threads, mutexes, events, semaphores and file mappings are secured like files,
+//! but are usually reached through handles rather than names (`SE_KERNEL_OBJECT`). [`KernelObject`]
+//! opens such objects with the access needed to read or change their security and reads and
+//! writes their security descriptors through the handle.
+//!
+//! Handles opened elsewhere (e.g., `std::process::Child`) can be used directly with
+//! [`SecurityDescriptor::from_raw_handle`] and [`SecurityDescriptorImpl::apply_to_raw_handle`]
+//! and `SE_KERNEL_OBJECT`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use win_acl_rs::{
+//!     kernel::KernelObject,
+//!     mask::AccessMask,
+//!     sd::{SecurityDescriptor, SecurityInformation},
+//! };
+//!
+//! // lock a named mutex down to SYSTEM and Administrators
+//! let mutex = KernelObject::open_mutex(r"Global\MyAppMutex", AccessMask::READ_CONTROL | AccessMask::WRITE_DAC)?;
+//! println!("{}", mutex.security()?.as_sd_string()?);
+//! let sd: SecurityDescriptor = "D:(A;;GA;;;SY)(A;;GA;;;BA)".parse()?;
+//! mutex.apply(&sd, SecurityInformation::DACL)?;
+//! # Ok::<(), win_acl_rs::error::WinError>(())
+//! ```
+
+use std::{
+    ffi::OsStr,
+    os::windows::io::{AsRawHandle, FromRawHandle, OwnedHandle, RawHandle},
+};
+
+use windows_sys::Win32::{
+    Foundation::{FALSE, GetLastError, HANDLE},
+    System::{
+        Memory::OpenFileMappingW,
+        Threading::{OpenEventW, OpenMutexW, OpenProcess, OpenSemaphoreW, OpenThread},
+    },
+};
+
+use crate::{
+    SE_KERNEL_OBJECT,
+    elevated::PrivilegeLevel,
+    error::WinError,
+    mask::AccessMask,
+    sd::{SecurityDescriptor, SecurityDescriptorImpl, SecurityInformation},
+    utils::WideCString,
+};
+
+/// The kind of a kernel object.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum KernelObjectKind {
+    /// A process.
+    Process,
+    /// A thread.
+    Thread,
+    /// A mutex.
+    Mutex,
+    /// An event.
+    Event,
+    /// A semaphore.
+    Semaphore,
+    /// A file mapping (section).
+    FileMapping,
+}
+
+/// An open kernel object. The handle is closed when dropped.
+#[derive(Debug)]
+pub struct KernelObject {
+    handle: OwnedHandle,
+    kind: KernelObjectKind,
+}
+
+impl KernelObject {
+    /// Opens a process.
+    ///
+    /// # Arguments
+    ///
+    /// * `pid` - The identifier of the process.
+    /// * `access` - The requested access, e.g., `READ_CONTROL` to read the security descriptor
+    ///   and `WRITE_DAC` to change the DACL.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the process cannot be opened with the requested access.
+    pub fn open_process(pid: u32, access: AccessMask) -> Result<Self, WinError> {
+        let handle = unsafe { OpenProcess(access.0, FALSE, pid) };
+        Self::from_opened(handle, KernelObjectKind::Process)
+    }
+
+    /// Opens a thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `tid` - The identifier of the thread.
+    /// * `access` - The requested access.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the thread cannot be opened with the requested access.
+    pub fn open_thread(tid: u32, access: AccessMask) -> Result<Self, WinError> {
+        let handle = unsafe { OpenThread(access.0, FALSE, tid) };
+        Self::from_opened(handle, KernelObjectKind::Thread)
+    }
+
+    /// Opens a named mutex.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the mutex, optionally prefixed with `Global\` or `Local\`.
+    /// * `access` - The requested access.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no such mutex or it cannot be opened with the requested access.
+    pub fn open_mutex<S>(name: S, access: AccessMask) -> Result<Self, WinError>
+    where
+        S: AsRef<str>,
+    {
+        let name = WideCString::new(OsStr::new(name.as_ref()));
+        let handle = unsafe { OpenMutexW(access.0, FALSE, name.as_ptr()) };
+        Self::from_opened(handle, KernelObjectKind::Mutex)
+    }
+
+    /// Opens a named event.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the event, optionally prefixed with `Global\` or `Local\`.
+    /// * `access` - The requested access.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no such event or it cannot be opened with the requested access.
+    pub fn open_event<S>(name: S, access: AccessMask) -> Result<Self, WinError>
+    where
+        S: AsRef<str>,
+    {
+        let name = WideCString::new(OsStr::new(name.as_ref()));
+        let handle = unsafe { OpenEventW(access.0, FALSE, name.as_ptr()) };
+        Self::from_opened(handle, KernelObjectKind::Event)
+    }
+
+    /// Opens a named semaphore.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the semaphore, optionally prefixed with `Global\` or `Local\`.
+    /// * `access` - The requested access.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no such semaphore or it cannot be opened with the requested access.
+    pub fn open_semaphore<S>(name: S, access: AccessMask) -> Result<Self, WinError>
+    where
+        S: AsRef<str>,
+    {
+        let name = WideCString::new(OsStr::new(name.as_ref()));
+        let handle = unsafe { OpenSemaphoreW(access.0, FALSE, name.as_ptr()) };
+        Self::from_opened(handle, KernelObjectKind::Semaphore)
+    }
+
+    /// Opens a named file mapping (section).
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the file mapping, optionally prefixed with `Global\` or `Local\`.
+    /// * `access` - The requested access.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no such file mapping or it cannot be opened with the requested access.
+    pub fn open_file_mapping<S>(name: S, access: AccessMask) -> Result<Self, WinError>
+    where
+        S: AsRef<str>,
+    {
+        let name = WideCString::new(OsStr::new(name.as_ref()));
+        let handle = unsafe { OpenFileMappingW(access.0, FALSE, name.as_ptr()) };
+        Self::from_opened(handle, KernelObjectKind::FileMapping)
+    }
+
+    /// Wraps a handle of a kernel object opened elsewhere.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The handle, closed when the object is dropped.
+    /// * `kind` - The kind of the object.
+    pub fn from_handle(handle: OwnedHandle, kind: KernelObjectKind) -> Self {
+        Self { handle, kind }
+    }
+
+    fn from_opened(handle: HANDLE, kind: KernelObjectKind) -> Result<Self, WinError> {
+        if handle.is_null() {
+            return Err(unsafe { GetLastError() }.into());
+        }
+        let handle = unsafe { OwnedHandle::from_raw_handle(handle as RawHandle) };
+        Ok(Self { handle, kind })
+    }
+
+    /// Returns the kind of the object.
+    pub fn kind(&self) -> KernelObjectKind {
+        self.kind
+    }
+
+    /// Reads the security descriptor of the object.
+    ///
+    /// The object must have been opened with `READ_CONTROL` access.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the security descriptor cannot be read.
+    pub fn security(&self) -> Result<SecurityDescriptor, WinError> {
+        SecurityDescriptor::from_raw_handle(self, SE_KERNEL_OBJECT)
+    }
+
+    /// Writes the selected components of a security descriptor to the object.
+    ///
+    /// The object must have been opened with `WRITE_DAC` (DACL) or `WRITE_OWNER` (owner, group)
+    /// access, depending on `components`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the security descriptor cannot be written.
+    pub fn apply<P>(&self, sd: &SecurityDescriptorImpl<P>, components: SecurityInformation) -> Result<(), WinError>
+    where
+        P: PrivilegeLevel,
+    {
+        sd.apply_to_raw_handle(self, SE_KERNEL_OBJECT, components)
+    }
+}
+
+impl AsRawHandle for KernelObject {
+    fn as_raw_handle(&self) -> RawHandle {
+        self.handle.as_raw_handle()
+    }
+}
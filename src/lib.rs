@@ -20,13 +20,31 @@
 #![cfg(windows)]
 
 pub mod acl;
+pub mod audit;
+pub mod auditpol;
+pub mod claims;
+pub mod com;
+pub mod condition;
+pub mod ds;
 pub mod elevated;
+pub mod eventlog;
+pub mod fs;
+pub mod guid;
+pub mod integrity;
+pub mod kernel;
+mod lsa;
 pub mod mask;
+pub mod registry;
+pub mod remote;
+pub mod report;
 pub mod sd;
+pub mod share;
 pub mod sid;
+pub mod task;
 pub mod trustee;
 mod utils;
 pub mod wellknown;
+mod window;
 
 mod macros;
 
@@ -38,7 +56,11 @@ pub use windows_sys::Win32::Security::Authorization::{
 
 /// Error definitions for Windows API operations.
 pub mod error {
-    use std::fmt::{Debug, Display, Formatter};
+    use std::{
+        backtrace::Backtrace,
+        fmt::{Debug, Display, Formatter},
+        sync::Arc,
+    };
 
     use windows_sys::Win32::Foundation::WIN32_ERROR;
 
@@ -55,6 +77,11 @@ pub mod error {
     /// Error codes are typically displayed in hexadecimal format (e.g., `0x00000005` for
     /// `ERROR_ACCESS_DENIED`).
     ///
+    /// With the `backtrace` feature enabled, a backtrace is captured when the error is created
+    /// (see [`std::backtrace::Backtrace::capture`] for the environment variables controlling it).
+    /// Errors are created with [`WinError::new`] (or the `From` conversions) whether or not the
+    /// feature is enabled, so enabling it does not change the API.
+    ///
     /// See [MSDN](https://learn.microsoft.com/en-us/windows/win32/debug/system-error-codes--0-499-)
     /// for information about Windows error codes.
     ///
@@ -63,20 +90,45 @@ pub mod error {
     /// ```no_run
     /// use win_acl_rs::error::WinError;
     ///
-    /// let error = WinError {
-    ///     code: 0x00000005, // ERROR_ACCESS_DENIED
-    ///     message: None,
-    /// };
+    /// let error = WinError::new(0x00000005, None); // ERROR_ACCESS_DENIED
     /// println!("Error: {}", error);
     /// ```
-    #[derive(Clone, Eq, PartialEq, Default)]
+    #[derive(Clone, Default)]
     pub struct WinError {
         /// The Windows error code (HRESULT) in hexadecimal format.
         pub code: u32,
         /// An optional human-readable error message, used when the error code alone is insufficient.
         pub message: Option<String>,
+        // always present so that the `backtrace` feature is additive, captured only with the feature
+        backtrace: Option<Arc<Backtrace>>,
+    }
+
+    impl WinError {
+        /// Creates an error from an error code and an optional message.
+        pub fn new(code: u32, message: Option<String>) -> Self {
+            WinError {
+                code,
+                message,
+                backtrace: cfg!(feature = "backtrace").then(|| Arc::new(Backtrace::capture())),
+            }
+        }
+
+        /// Returns the backtrace captured when the error was created, if any.
+        #[cfg(feature = "backtrace")]
+        pub fn backtrace(&self) -> Option<&Backtrace> {
+            self.backtrace.as_deref()
+        }
     }
 
+    // the backtrace is not part of the identity of the error
+    impl PartialEq for WinError {
+        fn eq(&self, other: &Self) -> bool {
+            self.code == other.code && self.message == other.message
+        }
+    }
+
+    impl Eq for WinError {}
+
     impl Display for WinError {
         fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
             if let Some(msg) = &self.message {
@@ -88,37 +140,31 @@ pub mod error {
 
     impl Debug for WinError {
         fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-            f.debug_struct("WinError")
-                .field("code", &format_args!("HRESULT: {:#010x}", self.code))
-                .field("message", &format_args!("{:?}", self.message))
-                .finish()
+            let mut fmt = f.debug_struct("WinError");
+            fmt.field("code", &format_args!("HRESULT: {:#010x}", self.code))
+                .field("message", &format_args!("{:?}", self.message));
+            if let Some(backtrace) = &self.backtrace {
+                fmt.field("backtrace", &format_args!("{}", backtrace));
+            }
+            fmt.finish()
         }
     }
 
     impl From<WIN32_ERROR> for WinError {
         fn from(value: WIN32_ERROR) -> Self {
-            WinError {
-                code: value,
-                message: None,
-            }
+            WinError::new(value, None)
         }
     }
 
     impl From<String> for WinError {
         fn from(value: String) -> Self {
-            WinError {
-                code: 0,
-                message: Some(value),
-            }
+            WinError::new(0, Some(value))
         }
     }
 
     impl From<&str> for WinError {
         fn from(value: &str) -> Self {
-            WinError {
-                code: 0,
-                message: Some(value.to_owned()),
-            }
+            WinError::new(0, Some(value.to_owned()))
         }
     }
 }
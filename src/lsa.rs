@@ -0,0 +1,97 @@
+//! Local Security Authority (LSA) policy queries.
+
+use std::{ptr::null, slice::from_raw_parts};
+
+use windows_sys::Win32::{
+    Foundation::{NTSTATUS, STATUS_SUCCESS},
+    Security::Authentication::Identity::{
+        LSA_HANDLE, LSA_OBJECT_ATTRIBUTES, LsaClose, LsaFreeMemory, LsaGetAppliedCAPIDs, LsaNtStatusToWinError,
+        LsaOpenPolicy, LsaQueryInformationPolicy, POLICY_ACCOUNT_DOMAIN_INFO, POLICY_PRIMARY_DOMAIN_INFO,
+        POLICY_VIEW_LOCAL_INFORMATION, PolicyAccountDomainInformation, PolicyPrimaryDomainInformation,
+    },
+};
+
+use crate::{
+    error::WinError,
+    sid::{Sid, SidRef},
+};
+
+/// Converts an `NTSTATUS` returned by the LSA functions into a result.
+fn lsa_result(status: NTSTATUS) -> Result<(), WinError> {
+    if status != STATUS_SUCCESS {
+        return Err(unsafe { LsaNtStatusToWinError(status) }.into());
+    }
+    Ok(())
+}
+
+/// Returns the IDs of the central access policies applied to the local machine.
+pub(crate) fn applied_capids() -> Result<Vec<Sid>, WinError> {
+    let mut capids = std::ptr::null_mut();
+    let mut count = 0u32;
+    lsa_result(unsafe { LsaGetAppliedCAPIDs(null(), &mut capids, &mut count) })?;
+    if capids.is_null() {
+        return Ok(Vec::new());
+    }
+
+    let sids = unsafe { from_raw_parts(capids, count as usize) }
+        .iter()
+        .map(|&sid_ptr| Sid::from_bytes(unsafe { SidRef::from_ptr(sid_ptr as _) }.as_bytes()))
+        .collect();
+
+    unsafe { LsaFreeMemory(capids as _) };
+    sids
+}
+
+/// An open handle to the local LSA policy object.
+pub(crate) struct LsaPolicy {
+    handle: LSA_HANDLE,
+}
+
+impl LsaPolicy {
+    /// Opens the local policy object for querying.
+    pub(crate) fn open() -> Result<Self, WinError> {
+        let attributes = LSA_OBJECT_ATTRIBUTES::default();
+        let mut handle: LSA_HANDLE = 0;
+        lsa_result(unsafe { LsaOpenPolicy(null(), &attributes, POLICY_VIEW_LOCAL_INFORMATION as u32, &mut handle) })?;
+        Ok(Self { handle })
+    }
+
+    /// Returns the SID of the domain the computer is joined to, or `None` if the computer
+    /// is not a member of a domain.
+    pub(crate) fn primary_domain_sid(&self) -> Result<Option<Sid>, WinError> {
+        let mut buffer = std::ptr::null_mut();
+        lsa_result(unsafe { LsaQueryInformationPolicy(self.handle, PolicyPrimaryDomainInformation, &mut buffer) })?;
+
+        let info = buffer as *const POLICY_PRIMARY_DOMAIN_INFO;
+        let sid = unsafe {
+            let sid_ptr = (*info).Sid;
+            if sid_ptr.is_null() {
+                Ok(None)
+            } else {
+                Sid::from_bytes(SidRef::from_ptr(sid_ptr as _).as_bytes()).map(Some)
+            }
+        };
+
+        unsafe { LsaFreeMemory(buffer) };
+        sid
+    }
+
+    /// Returns the SID of the account domain of the computer: the SID of the local accounts,
+    /// or the domain SID on a domain controller.
+    pub(crate) fn account_domain_sid(&self) -> Result<Sid, WinError> {
+        let mut buffer = std::ptr::null_mut();
+        lsa_result(unsafe { LsaQueryInformationPolicy(self.handle, PolicyAccountDomainInformation, &mut buffer) })?;
+
+        let info = buffer as *const POLICY_ACCOUNT_DOMAIN_INFO;
+        let sid = unsafe { Sid::from_bytes(SidRef::from_ptr((*info).DomainSid as _).as_bytes()) };
+
+        unsafe { LsaFreeMemory(buffer) };
+        sid
+    }
+}
+
+impl Drop for LsaPolicy {
+    fn drop(&mut self) {
+        unsafe { LsaClose(self.handle) };
+    }
+}
@@ -10,6 +10,7 @@
 //! - **Registry access masks** (`RegistryAccess`) - Specific to registry keys
 //! - **Service access masks** (`ServiceAccess`) - Specific to Windows services
 //! - **Printer access masks** (`PrinterAccess`) - Specific to printer objects
+//! - **COM access masks** (`ComAccess`) - Specific to COM access and launch permissions
 //!
 //! # Examples
 //!
@@ -41,6 +42,10 @@ use windows_sys::Win32::{
     },
     Storage::FileSystem::{FILE_ALL_ACCESS, FILE_GENERIC_EXECUTE, FILE_GENERIC_READ, FILE_GENERIC_WRITE},
     System::{
+        Com::{
+            COM_RIGHTS_ACTIVATE_LOCAL, COM_RIGHTS_ACTIVATE_REMOTE, COM_RIGHTS_EXECUTE, COM_RIGHTS_EXECUTE_LOCAL,
+            COM_RIGHTS_EXECUTE_REMOTE,
+        },
         Registry::{
             KEY_ALL_ACCESS, KEY_CREATE_SUB_KEY, KEY_ENUMERATE_SUB_KEYS, KEY_NOTIFY, KEY_QUERY_VALUE, KEY_READ,
             KEY_SET_VALUE, KEY_WRITE,
@@ -85,6 +90,8 @@ macro_rules! bit_ops {
     };
 }
 
+pub(crate) use bit_ops;
+
 /// A trait for types that can be converted to 32b mask
 ///
 /// This trait allows flexible usage of either `AccessMask`, `FileMask`, etc... or 32bit (`u32`, `i32`) raw values as a mask.
@@ -107,6 +114,21 @@ impl Mask for i32 {
     }
 }
 
+/// A typed access mask (`AccessMask`, `FileAccess`, etc...).
+///
+/// Unlike [`Mask`], this trait is not implemented for raw integers, so an unrelated value cannot
+/// be passed by accident where it is required (e.g., [`Acl::allow`](crate::acl::Acl::allow)).
+/// Raw values can still be wrapped explicitly, e.g., `AccessMask::from(0x1F01FF)`.
+pub trait IntoAccessMask: Mask {
+    /// Converts the mask to a generic `AccessMask`.
+    fn into_access_mask(self) -> AccessMask
+    where
+        Self: Sized,
+    {
+        AccessMask(self.as_u32())
+    }
+}
+
 /// A bitmask of generic access rights for ACL entries.
 ///
 /// This type provides convenient access to standard Windows access rights that
@@ -211,6 +233,8 @@ impl Mask for AccessMask {
     }
 }
 
+impl IntoAccessMask for AccessMask {}
+
 bit_ops!(AccessMask);
 
 /// File object-specific access rights.
@@ -259,6 +283,8 @@ impl Mask for FileAccess {
     }
 }
 
+impl IntoAccessMask for FileAccess {}
+
 bit_ops!(FileAccess);
 
 /// Registry key access rights.
@@ -315,6 +341,8 @@ impl Mask for RegistryAccess {
     }
 }
 
+impl IntoAccessMask for RegistryAccess {}
+
 bit_ops!(RegistryAccess);
 
 /// Windows service access rights.
@@ -373,6 +401,8 @@ impl Mask for ServiceAccess {
     }
 }
 
+impl IntoAccessMask for ServiceAccess {}
+
 bit_ops!(ServiceAccess);
 
 /// Printer object access rights.
@@ -425,4 +455,73 @@ impl Mask for PrinterAccess {
     }
 }
 
+impl IntoAccessMask for PrinterAccess {}
+
 bit_ops!(PrinterAccess);
+
+/// COM access and launch rights.
+///
+/// These are the access rights used in the security descriptors that control who can call
+/// (access permissions) or launch and activate (launch permissions) a COM server.
+/// Every ACE must include `EXECUTE`.
+///
+/// All bitwise operations (`|`, `&`, `|=`, `&=`, `!`) are supported for combining
+/// access rights.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct ComAccess(pub u32);
+
+impl ComAccess {
+    /// Generic execute right, required in every COM ACE.
+    pub const EXECUTE: Self = Self(COM_RIGHTS_EXECUTE);
+    /// Call the server or launch it from the local machine.
+    pub const EXECUTE_LOCAL: Self = Self(COM_RIGHTS_EXECUTE_LOCAL);
+    /// Call the server or launch it from a remote machine.
+    pub const EXECUTE_REMOTE: Self = Self(COM_RIGHTS_EXECUTE_REMOTE);
+    /// Activate the server from the local machine.
+    pub const ACTIVATE_LOCAL: Self = Self(COM_RIGHTS_ACTIVATE_LOCAL);
+    /// Activate the server from a remote machine.
+    pub const ACTIVATE_REMOTE: Self = Self(COM_RIGHTS_ACTIVATE_REMOTE);
+
+    /// Creates an access mask allowing local calls.
+    ///
+    /// Includes `EXECUTE` and `EXECUTE_LOCAL` rights.
+    pub fn local() -> Self {
+        Self::EXECUTE | Self::EXECUTE_LOCAL
+    }
+
+    /// Creates an access mask allowing local and remote calls.
+    ///
+    /// Includes `EXECUTE`, `EXECUTE_LOCAL` and `EXECUTE_REMOTE` rights.
+    pub fn local_and_remote() -> Self {
+        Self::EXECUTE | Self::EXECUTE_LOCAL | Self::EXECUTE_REMOTE
+    }
+}
+
+impl From<ComAccess> for u32 {
+    fn from(mask: ComAccess) -> Self {
+        mask.0
+    }
+}
+
+impl From<u32> for ComAccess {
+    fn from(value: u32) -> Self {
+        ComAccess(value)
+    }
+}
+
+impl From<i32> for ComAccess {
+    fn from(value: i32) -> Self {
+        ComAccess(value as u32)
+    }
+}
+
+impl Mask for ComAccess {
+    fn as_u32(&self) -> u32 {
+        self.0
+    }
+}
+
+impl IntoAccessMask for ComAccess {}
+
+bit_ops!(ComAccess);
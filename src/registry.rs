@@ -0,0 +1,468 @@
+//! Registry key naming helpers.
+//!
+//! `GetNamedSecurityInfoW` and friends expect registry keys to be named using a special
+//! convention (`MACHINE\SOFTWARE\...`, `CURRENT_USER\...`, ...) rather than the familiar
+//! `HKEY_LOCAL_MACHINE\SOFTWARE\...` or `HKLM\SOFTWARE\...` forms. This module parses the
+//! familiar forms into a [`RegistryPath`] that knows the hive, the subkey, the registry view
+//! and how to render the object name and object type expected by the security APIs.
+//!
+//! Keys of a remote machine are addressed by prefixing the path with the machine name
+//! (`\\SERVER\HKLM\...`, see [`RegistryPath::on_machine`]). Only the `HKLM` and `HKU` hives
+//! are reachable remotely and the *Remote Registry* service must be running on the target.
+//!
+//! It also reads and writes security descriptors stored in registry values
+//! (see [`read_sd_value`] and [`write_sd_value`]).
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use win_acl_rs::registry::{Hive, RegView, RegistryPath};
+//!
+//! let path: RegistryPath = r"HKLM\SOFTWARE\MyApp".parse()?;
+//! assert_eq!(path.hive(), Hive::LocalMachine);
+//! assert_eq!(path.subkey(), r"SOFTWARE\MyApp");
+//! assert_eq!(path.object_name(), r"MACHINE\SOFTWARE\MyApp");
+//!
+//! // Target the 32-bit view of the registry
+//! let path = path.with_view(RegView::Wow6432);
+//!
+//! // Target the same key on a remote machine
+//! let remote = path.on_machine("SERVER01");
+//! assert_eq!(remote.object_name(), r"\\SERVER01\MACHINE\SOFTWARE\MyApp");
+//! # Ok::<(), win_acl_rs::error::WinError>(())
+//! ```
+
+use std::{
+    fmt::{Display, Formatter},
+    ptr::null_mut,
+    str::FromStr,
+};
+
+use windows_sys::Win32::{
+    Foundation::ERROR_INVALID_PARAMETER,
+    Security::Authorization::{SE_OBJECT_TYPE, SE_REGISTRY_KEY, SE_REGISTRY_WOW64_32KEY, SE_REGISTRY_WOW64_64KEY},
+    System::Registry::{
+        HKEY, HKEY_CLASSES_ROOT, HKEY_CURRENT_CONFIG, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, HKEY_USERS, KEY_SET_VALUE,
+        KEY_WOW64_32KEY, KEY_WOW64_64KEY, REG_BINARY, REG_ROUTINE_FLAGS, REG_SAM_FLAGS, RRF_RT_REG_BINARY,
+        RRF_SUBKEY_WOW6432KEY, RRF_SUBKEY_WOW6464KEY, RegCloseKey, RegConnectRegistryW, RegGetValueW, RegOpenKeyExW,
+        RegSetValueExW,
+    },
+};
+
+use crate::{error::WinError, sd::SecurityDescriptor, utils::WideCString, winapi_call};
+
+/// A predefined registry hive (root key).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Hive {
+    /// `HKEY_CLASSES_ROOT` (`HKCR`).
+    ClassesRoot,
+    /// `HKEY_CURRENT_USER` (`HKCU`).
+    CurrentUser,
+    /// `HKEY_LOCAL_MACHINE` (`HKLM`).
+    LocalMachine,
+    /// `HKEY_USERS` (`HKU`).
+    Users,
+    /// `HKEY_CURRENT_CONFIG` (`HKCC`).
+    ///
+    /// This hive is an alias of `HKLM\SYSTEM\CurrentControlSet\Hardware Profiles\Current`.
+    CurrentConfig,
+}
+
+impl Hive {
+    /// Returns the object name prefix expected by the Windows security APIs
+    /// (e.g., `MACHINE` for [`Hive::LocalMachine`]).
+    ///
+    /// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/api/accctrl/ne-accctrl-se_object_type)
+    pub fn object_prefix(&self) -> &'static str {
+        match self {
+            Hive::ClassesRoot => "CLASSES_ROOT",
+            Hive::CurrentUser => "CURRENT_USER",
+            Hive::LocalMachine => "MACHINE",
+            Hive::Users => "USERS",
+            Hive::CurrentConfig => r"MACHINE\SYSTEM\CurrentControlSet\Hardware Profiles\Current",
+        }
+    }
+
+    /// Returns the full `HKEY_*` name of the hive.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Hive::ClassesRoot => "HKEY_CLASSES_ROOT",
+            Hive::CurrentUser => "HKEY_CURRENT_USER",
+            Hive::LocalMachine => "HKEY_LOCAL_MACHINE",
+            Hive::Users => "HKEY_USERS",
+            Hive::CurrentConfig => "HKEY_CURRENT_CONFIG",
+        }
+    }
+
+    /// Returns the predefined registry handle of the hive.
+    fn hkey(&self) -> HKEY {
+        match self {
+            Hive::ClassesRoot => HKEY_CLASSES_ROOT,
+            Hive::CurrentUser => HKEY_CURRENT_USER,
+            Hive::LocalMachine => HKEY_LOCAL_MACHINE,
+            Hive::Users => HKEY_USERS,
+            Hive::CurrentConfig => HKEY_CURRENT_CONFIG,
+        }
+    }
+
+    /// Parses a hive name. Accepts the full (`HKEY_LOCAL_MACHINE`), abbreviated (`HKLM`)
+    /// and security API (`MACHINE`) forms, case-insensitively.
+    fn from_name(name: &str) -> Option<Self> {
+        let hive = match name.to_ascii_uppercase().as_str() {
+            "HKEY_CLASSES_ROOT" | "HKCR" | "CLASSES_ROOT" => Hive::ClassesRoot,
+            "HKEY_CURRENT_USER" | "HKCU" | "CURRENT_USER" => Hive::CurrentUser,
+            "HKEY_LOCAL_MACHINE" | "HKLM" | "MACHINE" => Hive::LocalMachine,
+            "HKEY_USERS" | "HKU" | "USERS" => Hive::Users,
+            "HKEY_CURRENT_CONFIG" | "HKCC" | "CURRENT_CONFIG" => Hive::CurrentConfig,
+            _ => return None,
+        };
+        Some(hive)
+    }
+}
+
+impl Display for Hive {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Hive {
+    type Err = WinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Hive::from_name(s)
+            .ok_or_else(|| WinError::new(ERROR_INVALID_PARAMETER, Some(format!("Unknown registry hive: {}", s))))
+    }
+}
+
+/// The registry view used to resolve a key on 64-bit Windows.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum RegView {
+    /// The native view of the calling process (`SE_REGISTRY_KEY`).
+    #[default]
+    Default,
+    /// The 32-bit (WOW64) view of the registry (`SE_REGISTRY_WOW64_32KEY`).
+    Wow6432,
+    /// The 64-bit view of the registry (`SE_REGISTRY_WOW64_64KEY`).
+    Wow6464,
+}
+
+impl RegView {
+    /// Returns the `SE_OBJECT_TYPE` corresponding to this view.
+    pub fn object_type(&self) -> SE_OBJECT_TYPE {
+        match self {
+            RegView::Default => SE_REGISTRY_KEY,
+            RegView::Wow6432 => SE_REGISTRY_WOW64_32KEY,
+            RegView::Wow6464 => SE_REGISTRY_WOW64_64KEY,
+        }
+    }
+
+    fn sam_flags(&self) -> REG_SAM_FLAGS {
+        match self {
+            RegView::Default => 0,
+            RegView::Wow6432 => KEY_WOW64_32KEY,
+            RegView::Wow6464 => KEY_WOW64_64KEY,
+        }
+    }
+
+    fn routine_flags(&self) -> REG_ROUTINE_FLAGS {
+        match self {
+            RegView::Default => 0,
+            RegView::Wow6432 => RRF_SUBKEY_WOW6432KEY,
+            RegView::Wow6464 => RRF_SUBKEY_WOW6464KEY,
+        }
+    }
+}
+
+/// A parsed registry key path.
+///
+/// Holds the machine (for remote keys), the hive, the subkey relative to the hive and the
+/// registry view, and renders the object name and object type expected by the security APIs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RegistryPath {
+    machine: Option<String>,
+    hive: Hive,
+    subkey: String,
+    view: RegView,
+}
+
+impl RegistryPath {
+    /// Creates a registry path from a hive and a subkey in the default view.
+    ///
+    /// # Arguments
+    ///
+    /// * `hive` - The root key.
+    /// * `subkey` - The path of the key relative to the hive (e.g., `SOFTWARE\MyApp`).
+    pub fn new<S>(hive: Hive, subkey: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        Self {
+            machine: None,
+            hive,
+            subkey: subkey.as_ref().trim_matches('\\').to_owned(),
+            view: RegView::Default,
+        }
+    }
+
+    /// Parses a registry path such as `HKLM\SOFTWARE\MyApp`,
+    /// `HKEY_CURRENT_USER\Software\MyApp` or `MACHINE\SOFTWARE\MyApp`.
+    ///
+    /// A path prefixed with a machine name (`\\SERVER\HKLM\SOFTWARE\MyApp`) refers to a key
+    /// of a remote machine.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path does not start with a known hive.
+    pub fn parse<S>(path: S) -> Result<Self, WinError>
+    where
+        S: AsRef<str>,
+    {
+        let path = path.as_ref();
+        let (machine, path) = match path.strip_prefix(r"\\") {
+            Some(remote) => {
+                let (machine, path) = remote.split_once('\\').unwrap_or((remote, ""));
+                (Some(machine), path)
+            }
+            None => (None, path.trim_start_matches('\\')),
+        };
+        let (hive, subkey) = path.split_once('\\').unwrap_or((path, ""));
+        let parsed = Self::new(hive.parse()?, subkey);
+        Ok(match machine {
+            Some(machine) => parsed.on_machine(machine),
+            None => parsed,
+        })
+    }
+
+    /// Returns the same path using a specific registry view.
+    pub fn with_view(mut self, view: RegView) -> Self {
+        self.view = view;
+        self
+    }
+
+    /// Returns the same path on a remote machine.
+    ///
+    /// # Arguments
+    ///
+    /// * `machine` - The name of the machine, with or without the leading `\\`.
+    pub fn on_machine<S>(mut self, machine: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.machine = Some(machine.as_ref().trim_start_matches('\\').to_owned());
+        self
+    }
+
+    /// Returns the name of the remote machine, or `None` for a local key.
+    pub fn machine(&self) -> Option<&str> {
+        self.machine.as_deref()
+    }
+
+    /// Returns the hive of the path.
+    pub fn hive(&self) -> Hive {
+        self.hive
+    }
+
+    /// Returns the subkey relative to the hive.
+    pub fn subkey(&self) -> &str {
+        &self.subkey
+    }
+
+    /// Returns the registry view.
+    pub fn view(&self) -> RegView {
+        self.view
+    }
+
+    /// Returns the object name expected by `GetNamedSecurityInfoW` / `SetNamedSecurityInfoW`
+    /// (e.g., `MACHINE\SOFTWARE\MyApp` or `\\SERVER\MACHINE\SOFTWARE\MyApp`).
+    pub fn object_name(&self) -> String {
+        let mut name = String::new();
+        if let Some(machine) = &self.machine {
+            name.push_str(&format!(r"\\{}\", machine));
+        }
+        name.push_str(self.hive.object_prefix());
+        if !self.subkey.is_empty() {
+            name.push('\\');
+            name.push_str(&self.subkey);
+        }
+        name
+    }
+
+    /// Opens the root key of the path: the predefined hive handle for local keys,
+    /// a connection to the hive of the remote machine otherwise.
+    fn root_key(&self) -> Result<RootKey, WinError> {
+        let Some(machine) = &self.machine else {
+            return Ok(RootKey {
+                hkey: self.hive.hkey(),
+                remote: false,
+            });
+        };
+        let wide_machine = WideCString::new(machine);
+        let mut hkey: HKEY = null_mut();
+        unsafe { winapi_call!(RegConnectRegistryW(wide_machine.as_ptr(), self.hive.hkey(), &mut hkey)) };
+        Ok(RootKey { hkey, remote: true })
+    }
+
+    /// Returns the object type matching the registry view of this path.
+    pub fn object_type(&self) -> SE_OBJECT_TYPE {
+        self.view.object_type()
+    }
+}
+
+impl FromStr for RegistryPath {
+    type Err = WinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RegistryPath::parse(s)
+    }
+}
+
+impl Display for RegistryPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(machine) = &self.machine {
+            write!(f, r"\\{}\", machine)?;
+        }
+        if self.subkey.is_empty() {
+            write!(f, "{}", self.hive)
+        } else {
+            write!(f, r"{}\{}", self.hive, self.subkey)
+        }
+    }
+}
+
+/// The root key of a registry path, closed on drop if it is a remote connection.
+struct RootKey {
+    hkey: HKEY,
+    remote: bool,
+}
+
+impl Drop for RootKey {
+    fn drop(&mut self) {
+        if self.remote {
+            unsafe { RegCloseKey(self.hkey) };
+        }
+    }
+}
+
+/// Reads a security descriptor stored in a `REG_BINARY` registry value.
+///
+/// Many components store self-relative security descriptors in the registry (COM permissions,
+/// scheduled tasks, service and WMI settings, ...). This reads and decodes such a value.
+///
+/// # Arguments
+///
+/// * `key` - The registry key holding the value.
+/// * `value` - The name of the value.
+///
+/// # Errors
+///
+/// Returns an error if the key or value does not exist, is not `REG_BINARY`, or does not
+/// contain a valid security descriptor.
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::registry::{RegistryPath, read_sd_value};
+///
+/// let key = RegistryPath::parse(r"HKLM\SOFTWARE\Microsoft\Ole")?;
+/// let sd = read_sd_value(&key, "DefaultLaunchPermission")?;
+/// println!("{}", sd.as_sd_string()?);
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+pub fn read_sd_value<S>(key: &RegistryPath, value: S) -> Result<SecurityDescriptor, WinError>
+where
+    S: AsRef<str>,
+{
+    let bytes = read_binary_value(key, value.as_ref())?;
+    SecurityDescriptor::from_bytes(&bytes)
+}
+
+/// Writes a security descriptor to a `REG_BINARY` registry value in self-relative form.
+///
+/// # Arguments
+///
+/// * `key` - The registry key holding the value. The key must exist.
+/// * `value` - The name of the value. It is created if it does not exist.
+/// * `sd` - The security descriptor to store.
+///
+/// # Errors
+///
+/// Returns an error if the key does not exist or the value cannot be written.
+pub fn write_sd_value<S>(key: &RegistryPath, value: S, sd: &SecurityDescriptor) -> Result<(), WinError>
+where
+    S: AsRef<str>,
+{
+    write_binary_value(key, value.as_ref(), &sd.to_bytes())
+}
+
+/// Reads a `REG_BINARY` value of a registry key.
+fn read_binary_value(path: &RegistryPath, value: &str) -> Result<Vec<u8>, WinError> {
+    let wide_subkey = WideCString::new(path.subkey());
+    let wide_value = WideCString::new(value);
+    let flags = RRF_RT_REG_BINARY | path.view().routine_flags();
+    let root = path.root_key()?;
+
+    let mut size = 0u32;
+    unsafe {
+        winapi_call!(RegGetValueW(
+            root.hkey,
+            wide_subkey.as_ptr(),
+            wide_value.as_ptr(),
+            flags,
+            null_mut(),
+            null_mut(),
+            &mut size,
+        ))
+    };
+
+    let mut data = vec![0u8; size as usize];
+    unsafe {
+        winapi_call!(RegGetValueW(
+            root.hkey,
+            wide_subkey.as_ptr(),
+            wide_value.as_ptr(),
+            flags,
+            null_mut(),
+            data.as_mut_ptr() as _,
+            &mut size,
+        ))
+    };
+    data.truncate(size as usize);
+    Ok(data)
+}
+
+/// Writes a `REG_BINARY` value of an existing registry key.
+fn write_binary_value(path: &RegistryPath, value: &str, data: &[u8]) -> Result<(), WinError> {
+    let wide_subkey = WideCString::new(path.subkey());
+    let wide_value = WideCString::new(value);
+
+    let root = path.root_key()?;
+    let mut hkey: HKEY = null_mut();
+    unsafe {
+        winapi_call!(RegOpenKeyExW(
+            root.hkey,
+            wide_subkey.as_ptr(),
+            0,
+            KEY_SET_VALUE | path.view().sam_flags(),
+            &mut hkey,
+        ))
+    };
+
+    unsafe {
+        winapi_call!(
+            RegSetValueExW(
+                hkey,
+                wide_value.as_ptr(),
+                0,
+                REG_BINARY,
+                data.as_ptr(),
+                data.len() as u32
+            ),
+            {
+                RegCloseKey(hkey);
+            }
+        );
+        RegCloseKey(hkey);
+    }
+    Ok(())
+}
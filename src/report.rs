@@ -0,0 +1,303 @@
+//! Permission inventory reports.
+//!
+//! A [`Report`] collects one row per ACE of the inspected objects (path, owner, trustee, ACE
+//! type, rights, inheritance) with an optional finding, and renders the rows as CSV for
+//! spreadsheets or as a standalone HTML page for sharing with reviewers.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use win_acl_rs::{report::Report, sd::SecurityDescriptor};
+//!
+//! let mut report = Report::new("Share permissions");
+//! for path in ["C:\\data", "C:\\data\\finance"] {
+//!     report.add_descriptor(path, &SecurityDescriptor::from_path(path)?)?;
+//! }
+//! for row in report.rows_mut() {
+//!     if row.trustee == "Everyone" {
+//!         row.finding = Some("Access granted to Everyone".to_owned());
+//!     }
+//! }
+//! std::fs::write("report.csv", report.to_csv()).unwrap();
+//! std::fs::write("report.html", report.to_html()).unwrap();
+//! # Ok::<(), win_acl_rs::error::WinError>(())
+//! ```
+
+use std::{borrow::Cow, fmt::Write, path::Path};
+
+use windows_sys::Win32::Security::{
+    CONTAINER_INHERIT_ACE, INHERIT_ONLY_ACE, INHERITED_ACE, NO_PROPAGATE_INHERIT_ACE, OBJECT_INHERIT_ACE,
+};
+
+use crate::{
+    acl::{Ace, AceType},
+    elevated::PrivilegeLevel,
+    error::WinError,
+    sd::SecurityDescriptorImpl,
+    sid::{Sid, SidRef, account::cached_name},
+    wellknown::friendly_name,
+};
+
+/// A row of a permission report: one ACE of one object.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ReportRow {
+    /// The path (or name) of the object.
+    pub path: String,
+    /// The owner of the object (`DOMAIN\name`, or the SID string if it cannot be resolved).
+    pub owner: String,
+    /// The trustee of the ACE (`DOMAIN\name`, or the SID string if it cannot be resolved).
+    pub trustee: String,
+    /// The type of the ACE (`Allow`, `Deny`, `Audit`).
+    pub ace_type: String,
+    /// The access mask of the ACE (e.g., `0x001F01FF`).
+    pub rights: String,
+    /// The inheritance flags of the ACE in `icacls` notation (e.g., `(OI)(CI)(ID)`).
+    pub inheritance: String,
+    /// A finding attached to the row, if any.
+    pub finding: Option<String>,
+}
+
+impl ReportRow {
+    const HEADERS: [&'static str; 7] = ["Path", "Owner", "Trustee", "Type", "Rights", "Inheritance", "Finding"];
+
+    fn fields(&self) -> [&str; 7] {
+        [
+            &self.path,
+            &self.owner,
+            &self.trustee,
+            &self.ace_type,
+            &self.rights,
+            &self.inheritance,
+            self.finding.as_deref().unwrap_or(""),
+        ]
+    }
+}
+
+/// A permission report.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Report {
+    title: String,
+    rows: Vec<ReportRow>,
+}
+
+impl Report {
+    /// Creates an empty report.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - The title of the HTML report.
+    pub fn new<S>(title: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            title: title.into(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Returns the title of the report.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Returns the rows of the report.
+    pub fn rows(&self) -> &[ReportRow] {
+        &self.rows
+    }
+
+    /// Returns the rows of the report for attaching findings.
+    pub fn rows_mut(&mut self) -> &mut [ReportRow] {
+        &mut self.rows
+    }
+
+    /// Adds a row.
+    pub fn push(&mut self, row: ReportRow) {
+        self.rows.push(row);
+    }
+
+    /// Adds one row per DACL entry of a security descriptor.
+    ///
+    /// An object without a DACL gets a single row with the finding *No DACL (full access to everyone)*.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path (or name) of the object.
+    /// * `sd` - The security descriptor of the object.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the DACL cannot be read.
+    pub fn add_descriptor<P, L>(&mut self, path: P, sd: &SecurityDescriptorImpl<L>) -> Result<(), WinError>
+    where
+        P: AsRef<Path>,
+        L: PrivilegeLevel,
+    {
+        let path = path.as_ref().display().to_string();
+        let owner = sd.owner_sid().map(account_name).unwrap_or_default();
+
+        let Some(dacl) = sd.dacl() else {
+            self.rows.push(ReportRow {
+                path,
+                owner,
+                finding: Some("No DACL (full access to everyone)".to_owned()),
+                ..Default::default()
+            });
+            return Ok(());
+        };
+        for ace in dacl.try_iter() {
+            let ace = ace?;
+            self.rows.push(ReportRow {
+                path: path.clone(),
+                owner: owner.clone(),
+                trustee: ace.sid().map(|sid| sid_name(&sid)).unwrap_or_default(),
+                ace_type: ace_type_name(ace.ace_type()),
+                rights: format!("0x{:08X}", ace.mask()),
+                inheritance: inheritance(&ace),
+                finding: None,
+            });
+        }
+        Ok(())
+    }
+
+    /// Renders the report as CSV (RFC 4180), with a header row.
+    ///
+    /// Fields starting with `=`, `+`, `-` or `@` (e.g., an object name chosen by an attacker) are
+    /// prefixed with `'`, so spreadsheets do not evaluate them as formulas.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        csv_line(&mut csv, ReportRow::HEADERS);
+        for row in &self.rows {
+            csv_line(&mut csv, row.fields());
+        }
+        csv
+    }
+
+    /// Renders the report as a standalone HTML page with a single table.
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        let title = html_escape(&self.title);
+        let _ = write!(
+            html,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>\n\
+             body {{ font-family: sans-serif; }}\n\
+             table {{ border-collapse: collapse; }}\n\
+             th, td {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; }}\n\
+             th {{ background: #eee; }}\n\
+             tr.finding td {{ background: #fdd; }}\n\
+             </style>\n</head>\n<body>\n<h1>{title}</h1>\n<table>\n<tr>"
+        );
+        for header in ReportRow::HEADERS {
+            let _ = write!(html, "<th>{}</th>", header);
+        }
+        html.push_str("</tr>\n");
+        for row in &self.rows {
+            html.push_str(if row.finding.is_some() {
+                "<tr class=\"finding\">"
+            } else {
+                "<tr>"
+            });
+            for field in row.fields() {
+                let _ = write!(html, "<td>{}</td>", html_escape(field));
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>\n</body>\n</html>\n");
+        html
+    }
+}
+
+fn account_name(sid: SidRef<'_>) -> String {
+    match Sid::from_bytes(sid.as_bytes()) {
+        Ok(sid) => sid_name(&sid),
+        Err(_) => String::new(),
+    }
+}
+
+fn sid_name(sid: &Sid) -> String {
+    if let Some(name) = friendly_name(sid) {
+        return name.to_owned();
+    }
+    cached_name(sid).unwrap_or_else(|| sid.to_string().unwrap_or_default())
+}
+
+fn ace_type_name(ace_type: AceType) -> String {
+    match ace_type {
+        AceType::AccessAllowed => "Allow".to_owned(),
+        AceType::AccessDenied => "Deny".to_owned(),
+        AceType::SystemAudit => "Audit".to_owned(),
+        AceType::AccessAllowedObject => "Allow (object)".to_owned(),
+        AceType::AccessDeniedObject => "Deny (object)".to_owned(),
+        AceType::SystemAuditObject => "Audit (object)".to_owned(),
+        AceType::AccessAllowedCallback => "Allow (conditional)".to_owned(),
+        AceType::AccessDeniedCallback => "Deny (conditional)".to_owned(),
+        AceType::SystemAuditCallback => "Audit (conditional)".to_owned(),
+        AceType::SystemAlarm => "Alarm".to_owned(),
+        AceType::SystemAlarmObject => "Alarm (object)".to_owned(),
+        AceType::SystemAlarmCallback => "Alarm (conditional)".to_owned(),
+        AceType::AccessAllowedCallbackObject => "Allow (conditional object)".to_owned(),
+        AceType::AccessDeniedCallbackObject => "Deny (conditional object)".to_owned(),
+        AceType::SystemAuditCallbackObject => "Audit (conditional object)".to_owned(),
+        AceType::SystemAlarmCallbackObject => "Alarm (conditional object)".to_owned(),
+        AceType::AccessAllowedCompound => "Allow (compound)".to_owned(),
+        AceType::SystemMandatoryLabel => "Mandatory label".to_owned(),
+        AceType::SystemResourceAttribute => "Resource attribute".to_owned(),
+        AceType::SystemScopedPolicyId => "Central access policy".to_owned(),
+        AceType::SystemProcessTrustLabel => "Trust label".to_owned(),
+        AceType::SystemAccessFilter => "Access filter".to_owned(),
+        AceType::Unknown(t) => format!("Unknown ({})", t),
+    }
+}
+
+fn inheritance(ace: &Ace<'_>) -> String {
+    const FLAGS: [(u32, &str); 5] = [
+        (OBJECT_INHERIT_ACE, "(OI)"),
+        (CONTAINER_INHERIT_ACE, "(CI)"),
+        (INHERIT_ONLY_ACE, "(IO)"),
+        (NO_PROPAGATE_INHERIT_ACE, "(NP)"),
+        (INHERITED_ACE, "(ID)"),
+    ];
+    let flags = ace.header_flags() as u32;
+    FLAGS
+        .iter()
+        .filter(|(flag, _)| flags & flag != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+fn csv_line<'a, I>(csv: &mut String, fields: I)
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    for (i, field) in fields.into_iter().enumerate() {
+        if i > 0 {
+            csv.push(',');
+        }
+        let field = if field.starts_with(['=', '+', '-', '@']) {
+            Cow::Owned(format!("'{}", field))
+        } else {
+            Cow::Borrowed(field)
+        };
+        if field.contains([',', '"', '\r', '\n']) {
+            let _ = write!(csv, "\"{}\"", field.replace('"', "\"\""));
+        } else {
+            csv.push_str(&field);
+        }
+    }
+    csv.push_str("\r\n");
+}
+
+fn html_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
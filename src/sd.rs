@@ -11,37 +11,115 @@
 
 #![allow(non_snake_case)]
 
-use std::{ffi::OsStr, marker::PhantomData, path::Path, ptr::null_mut, slice::from_raw_parts, str::FromStr};
+use std::{
+    borrow::Cow,
+    ffi::{OsStr, OsString},
+    marker::PhantomData,
+    os::windows::io::AsRawHandle,
+    path::Path,
+    ptr::null_mut,
+    slice::from_raw_parts,
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 use windows_sys::{
     Win32::{
-        Foundation::TRUE,
+        Foundation::{
+            ERROR_INSUFFICIENT_BUFFER, ERROR_INVALID_PARAMETER, ERROR_INVALID_SECURITY_DESCR, ERROR_OUTOFMEMORY, FALSE,
+            GetLastError, HANDLE, TRUE,
+        },
         Security::{
-            ACL,
+            ACCESS_ALLOWED_ACE, ACL, ATTRIBUTE_SECURITY_INFORMATION,
             Authorization::{
                 ConvertSecurityDescriptorToStringSecurityDescriptorW,
-                ConvertStringSecurityDescriptorToSecurityDescriptorW, GetNamedSecurityInfoW, SDDL_REVISION_1,
-                SE_FILE_OBJECT, SE_OBJECT_TYPE,
+                ConvertStringSecurityDescriptorToSecurityDescriptorW, GetNamedSecurityInfoW, GetSecurityInfo,
+                SDDL_REVISION_1, SE_FILE_OBJECT, SE_OBJECT_TYPE, SE_SERVICE, SE_WINDOW_OBJECT, SetNamedSecurityInfoW,
+                SetSecurityInfo,
             },
-            DACL_SECURITY_INFORMATION, GROUP_SECURITY_INFORMATION, GetSecurityDescriptorDacl,
-            GetSecurityDescriptorGroup, GetSecurityDescriptorOwner, GetSecurityDescriptorSacl,
-            IsValidSecurityDescriptor, OBJECT_SECURITY_INFORMATION, OWNER_SECURITY_INFORMATION, PSECURITY_DESCRIPTOR,
-            PSID, SACL_SECURITY_INFORMATION,
+            DACL_SECURITY_INFORMATION, GROUP_SECURITY_INFORMATION, GetSecurityDescriptorControl,
+            GetSecurityDescriptorDacl, GetSecurityDescriptorGroup, GetSecurityDescriptorLength,
+            GetSecurityDescriptorOwner, GetSecurityDescriptorSacl, InitializeSecurityDescriptor,
+            IsValidSecurityDescriptor, LABEL_SECURITY_INFORMATION, MakeSelfRelativeSD, OBJECT_SECURITY_INFORMATION,
+            OWNER_SECURITY_INFORMATION, PROTECTED_DACL_SECURITY_INFORMATION, PROTECTED_SACL_SECURITY_INFORMATION,
+            PSECURITY_DESCRIPTOR, PSID, SACL_SECURITY_INFORMATION, SCOPE_SECURITY_INFORMATION,
+            SE_DACL_AUTO_INHERIT_REQ, SE_DACL_AUTO_INHERITED, SE_DACL_DEFAULTED, SE_DACL_PRESENT, SE_DACL_PROTECTED,
+            SE_GROUP_DEFAULTED, SE_OWNER_DEFAULTED, SE_RM_CONTROL_VALID, SE_SACL_AUTO_INHERIT_REQ,
+            SE_SACL_AUTO_INHERITED, SE_SACL_DEFAULTED, SE_SACL_PRESENT, SE_SACL_PROTECTED, SE_SELF_RELATIVE,
+            SECURITY_DESCRIPTOR, SECURITY_DESCRIPTOR_RELATIVE, SetSecurityDescriptorControl, SetSecurityDescriptorDacl,
+            SetSecurityDescriptorGroup, SetSecurityDescriptorOwner, SetSecurityDescriptorSacl,
+            UNPROTECTED_DACL_SECURITY_INFORMATION, UNPROTECTED_SACL_SECURITY_INFORMATION,
+        },
+        System::{
+            Memory::{LMEM_FIXED, LocalAlloc},
+            SystemServices::SECURITY_DESCRIPTOR_REVISION,
         },
     },
-    core::{BOOL, PCWSTR},
+    core::BOOL,
 };
 
 use crate::{
-    acl::Acl,
+    acl::{self, Ace, AceFlags, AceType, Acl, AclRef},
     assert_free,
+    claims::ResourceAttribute,
     elevated::{Elevated, PrivilegeLevel, PrivilegeTokenImpl, Unprivileged},
     error::WinError,
-    sid::SidRef,
+    fs,
+    guid::Guid,
+    integrity::{self, MandatoryLabel},
+    mask::{AccessMask, IntoAccessMask, bit_ops},
+    registry::{Hive, RegView, RegistryPath},
+    sid::{AsSidRef, Sid, SidRef},
     utils::WideCString,
     winapi_bool_call, winapi_call,
+    window::{self, WindowObject},
 };
 
+/// The control flags describing DACL and SACL inheritance, which are kept when a descriptor is rebuilt.
+const INHERITANCE_CONTROL: SdControlFlags = SdControlFlags(
+    SE_DACL_AUTO_INHERIT_REQ
+        | SE_SACL_AUTO_INHERIT_REQ
+        | SE_DACL_AUTO_INHERITED
+        | SE_SACL_AUTO_INHERITED
+        | SE_DACL_PROTECTED
+        | SE_SACL_PROTECTED,
+);
+
+/// Checks that a buffer holds a self-relative security descriptor whose components all lie
+/// within the buffer, so the pointers derived from their offsets stay in bounds.
+fn is_well_formed_self_relative(bytes: &[u8]) -> bool {
+    let read_u16 = |offset: usize| bytes.get(offset..offset + 2).map(|b| u16::from_le_bytes([b[0], b[1]]));
+    let read_u32 = |offset: usize| {
+        bytes
+            .get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize)
+    };
+    // a SID is 8 bytes followed by its sub-authorities
+    let sid_fits = |offset: usize| {
+        offset == 0
+            || bytes
+                .get(offset + 1)
+                .is_some_and(|&count| offset + 8 + 4 * count as usize <= bytes.len())
+    };
+    // an ACL records its size, ACEs included
+    let acl_fits =
+        |offset: usize| offset == 0 || read_u16(offset + 2).is_some_and(|size| offset + size as usize <= bytes.len());
+
+    if bytes.len() < size_of::<SECURITY_DESCRIPTOR_RELATIVE>() {
+        return false;
+    }
+    let control = SdControlFlags(read_u16(2).unwrap_or_default());
+    let (Some(owner), Some(group), Some(sacl), Some(dacl)) = (read_u32(4), read_u32(8), read_u32(12), read_u32(16))
+    else {
+        return false;
+    };
+    control.contains(SdControlFlags::SELF_RELATIVE)
+        && sid_fits(owner)
+        && sid_fits(group)
+        && (!control.contains(SdControlFlags::SACL_PRESENT) || acl_fits(sacl))
+        && (!control.contains(SdControlFlags::DACL_PRESENT) || acl_fits(dacl))
+}
+
 /// A type alias for an unprivileged security descriptor.
 ///
 /// This type can read standard security information but cannot access SACL data.
@@ -83,9 +161,198 @@ pub struct SecurityDescriptorImpl<P: PrivilegeLevel = Unprivileged> {
     group_sid_ptr: PSID,
     dacl_ptr: *mut ACL,
     sacl_ptr: *mut ACL,
+    origin: Option<ObjectOrigin>,
     _priv: PhantomData<P>,
 }
 
+/// The named object a security descriptor was read from.
+///
+/// Security descriptors created by [`SecurityDescriptorImpl::from_path`] or
+/// [`SecurityDescriptorImpl::from_handle`] remember their origin, so they can be re-read with
+/// [`SecurityDescriptorImpl::refresh`] or written back with [`SecurityDescriptorImpl::reapply`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectOrigin {
+    name: OsString,
+    object_type: SE_OBJECT_TYPE,
+    components: SecurityInformation,
+}
+
+impl ObjectOrigin {
+    /// Returns the name of the object (path, registry key, service name, ...).
+    pub fn name(&self) -> &OsStr {
+        &self.name
+    }
+
+    /// Returns the type of the object.
+    pub fn object_type(&self) -> SE_OBJECT_TYPE {
+        self.object_type
+    }
+
+    /// Returns the components that were requested when the object was read.
+    pub fn components(&self) -> SecurityInformation {
+        self.components
+    }
+}
+
+/// Selects the components of a security descriptor to read or write.
+///
+/// This is a typed wrapper around the Windows `SECURITY_INFORMATION` flags.
+/// All bitwise operations (`|`, `&`, `|=`, `&=`, `!`) are supported for combining flags.
+///
+/// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/secauthz/security-information)
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::sd::SecurityInformation;
+///
+/// let components = SecurityInformation::OWNER | SecurityInformation::DACL;
+/// assert!(components.contains(SecurityInformation::DACL));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct SecurityInformation(pub u32);
+
+impl SecurityInformation {
+    /// The owner SID.
+    pub const OWNER: Self = Self(OWNER_SECURITY_INFORMATION);
+    /// The primary group SID.
+    pub const GROUP: Self = Self(GROUP_SECURITY_INFORMATION);
+    /// The DACL.
+    pub const DACL: Self = Self(DACL_SECURITY_INFORMATION);
+    /// The SACL (requires `SE_SECURITY_NAME` privilege).
+    pub const SACL: Self = Self(SACL_SECURITY_INFORMATION);
+    /// The mandatory integrity label.
+    pub const LABEL: Self = Self(LABEL_SECURITY_INFORMATION);
+    /// The resource attributes (claims) stored in the SACL.
+    pub const ATTRIBUTE: Self = Self(ATTRIBUTE_SECURITY_INFORMATION);
+    /// The central access policy (scoped policy ID) stored in the SACL.
+    pub const SCOPE: Self = Self(SCOPE_SECURITY_INFORMATION);
+    /// Protects the DACL from inheriting ACEs (write only).
+    pub const PROTECTED_DACL: Self = Self(PROTECTED_DACL_SECURITY_INFORMATION);
+    /// Protects the SACL from inheriting ACEs (write only).
+    pub const PROTECTED_SACL: Self = Self(PROTECTED_SACL_SECURITY_INFORMATION);
+    /// Allows the DACL to inherit ACEs from the parent (write only).
+    pub const UNPROTECTED_DACL: Self = Self(UNPROTECTED_DACL_SECURITY_INFORMATION);
+    /// Allows the SACL to inherit ACEs from the parent (write only).
+    pub const UNPROTECTED_SACL: Self = Self(UNPROTECTED_SACL_SECURITY_INFORMATION);
+
+    /// No components.
+    pub fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Owner, group and DACL, i.e. everything that can be accessed without elevated privileges.
+    pub fn standard() -> Self {
+        Self(OBJECT_SECURITY_INFORMATION::get_safe())
+    }
+
+    /// Owner, group, DACL and SACL.
+    pub fn all() -> Self {
+        Self(OBJECT_SECURITY_INFORMATION::get_all())
+    }
+
+    /// Returns `true` if all flags of `other` are set in `self`.
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl From<SecurityInformation> for u32 {
+    fn from(value: SecurityInformation) -> Self {
+        value.0
+    }
+}
+
+impl From<u32> for SecurityInformation {
+    fn from(value: u32) -> Self {
+        SecurityInformation(value)
+    }
+}
+
+bit_ops!(SecurityInformation);
+
+/// The control flags of a security descriptor.
+///
+/// This is a typed wrapper around the Windows `SECURITY_DESCRIPTOR_CONTROL` bits.
+/// All bitwise operations (`|`, `&`, `|=`, `&=`, `!`) are supported for combining flags.
+///
+/// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/secauthz/security-descriptor-control)
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::sd::{SdControlFlags, SecurityDescriptor};
+///
+/// let sd = SecurityDescriptor::from_path("C:\\path\\to\\file.txt")?;
+/// if sd.control()?.contains(SdControlFlags::DACL_PROTECTED) {
+///     println!("inheritance is disabled");
+/// }
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct SdControlFlags(pub u16);
+
+impl SdControlFlags {
+    /// The owner was provided by a default mechanism.
+    pub const OWNER_DEFAULTED: Self = Self(SE_OWNER_DEFAULTED);
+    /// The group was provided by a default mechanism.
+    pub const GROUP_DEFAULTED: Self = Self(SE_GROUP_DEFAULTED);
+    /// The security descriptor has a DACL (a null DACL if the pointer is null).
+    pub const DACL_PRESENT: Self = Self(SE_DACL_PRESENT);
+    /// The DACL was provided by a default mechanism.
+    pub const DACL_DEFAULTED: Self = Self(SE_DACL_DEFAULTED);
+    /// The security descriptor has a SACL.
+    pub const SACL_PRESENT: Self = Self(SE_SACL_PRESENT);
+    /// The SACL was provided by a default mechanism.
+    pub const SACL_DEFAULTED: Self = Self(SE_SACL_DEFAULTED);
+    /// The ACEs of the DACL were obtained from an untrusted source.
+    pub const DACL_UNTRUSTED: Self = Self(0x0040);
+    /// The server ACL was used in place of the client provided ACL.
+    pub const SERVER_SECURITY: Self = Self(0x0080);
+    /// The DACL is requested to propagate inheritable ACEs to existing child objects.
+    pub const DACL_AUTO_INHERIT_REQ: Self = Self(SE_DACL_AUTO_INHERIT_REQ);
+    /// The SACL is requested to propagate inheritable ACEs to existing child objects.
+    pub const SACL_AUTO_INHERIT_REQ: Self = Self(SE_SACL_AUTO_INHERIT_REQ);
+    /// The DACL supports automatic propagation of inheritable ACEs.
+    pub const DACL_AUTO_INHERITED: Self = Self(SE_DACL_AUTO_INHERITED);
+    /// The SACL supports automatic propagation of inheritable ACEs.
+    pub const SACL_AUTO_INHERITED: Self = Self(SE_SACL_AUTO_INHERITED);
+    /// The DACL does not inherit ACEs from the parent.
+    pub const DACL_PROTECTED: Self = Self(SE_DACL_PROTECTED);
+    /// The SACL does not inherit ACEs from the parent.
+    pub const SACL_PROTECTED: Self = Self(SE_SACL_PROTECTED);
+    /// The resource manager control bits are valid.
+    pub const RM_CONTROL_VALID: Self = Self(SE_RM_CONTROL_VALID);
+    /// The security descriptor is in self-relative format.
+    pub const SELF_RELATIVE: Self = Self(SE_SELF_RELATIVE);
+
+    /// No flags.
+    pub fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns `true` if all flags of `other` are set in `self`.
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl From<SdControlFlags> for u16 {
+    fn from(value: SdControlFlags) -> Self {
+        value.0
+    }
+}
+
+impl From<u16> for SdControlFlags {
+    fn from(value: u16) -> Self {
+        SdControlFlags(value)
+    }
+}
+
+bit_ops!(SdControlFlags);
+
 impl SecurityDescriptorImpl<Unprivileged> {
     /// Upgrades this security descriptor to an elevated one that can access SACL.
     ///
@@ -99,19 +366,25 @@ impl SecurityDescriptorImpl<Unprivileged> {
     /// # Returns
     ///
     /// An `SecurityDescriptorImpl<Elevated>` that can access SACL information.
-    pub fn upgrade(self, _token: &PrivilegeTokenImpl<Elevated>) -> SecurityDescriptorImpl<Elevated> {
-        SecurityDescriptorImpl {
+    pub fn upgrade(mut self, _token: &PrivilegeTokenImpl<Elevated>) -> SecurityDescriptorImpl<Elevated> {
+        let upgraded = SecurityDescriptorImpl {
             sd_ptr: self.sd_ptr,
             owner_sid_ptr: self.owner_sid_ptr,
             group_sid_ptr: self.group_sid_ptr,
             dacl_ptr: self.dacl_ptr,
             sacl_ptr: self.sacl_ptr,
+            origin: self.origin.take(),
             _priv: PhantomData,
-        }
+        };
+        // ownership of the buffer moved to the upgraded descriptor
+        std::mem::forget(self);
+        upgraded
     }
 
     /// Creates a SecurityDescriptor from path to the "file object"
     ///
+    /// Paths longer than `MAX_PATH` are converted to the extended-length form (see [`crate::fs`]).
+    ///
     /// # Arguments
     ///
     /// * `path` - Path to the file.
@@ -123,15 +396,55 @@ impl SecurityDescriptorImpl<Unprivileged> {
     where
         P: AsRef<Path>,
     {
-        let wide_path = WideCString::new(OsStr::new(path.as_ref()));
-
         Self::create_sd(
-            wide_path.as_ptr(),
+            path.as_ref().as_os_str(),
             SE_FILE_OBJECT,
             OBJECT_SECURITY_INFORMATION::get_safe(),
         )
     }
 
+    /// Creates a SecurityDescriptor from path to the "file object", reading only the selected components.
+    ///
+    /// Requesting fewer components avoids unnecessary queries, e.g., reading only the owner is much
+    /// faster on network shares. Components that were not requested are absent from the descriptor.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the file.
+    /// * `components` - The components to read (e.g., `SecurityInformation::OWNER`).
+    ///
+    /// # Returns
+    ///
+    /// A `SecurityDescriptor` on success.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ERROR_INVALID_PARAMETER` if `components` selects the SACL (use
+    /// [`SecurityDescriptorElevated::from_path_with`](crate::elevated::SecurityDescriptorElevated)),
+    /// or an error if the object cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sd::{SecurityDescriptor, SecurityInformation};
+    ///
+    /// let sd = SecurityDescriptor::from_path_with("\\\\server\\share\\file.txt", SecurityInformation::OWNER)?;
+    /// assert!(sd.dacl().is_none());
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn from_path_with<P>(path: P, components: SecurityInformation) -> Result<Self, WinError>
+    where
+        P: AsRef<Path>,
+    {
+        if components.contains(SecurityInformation::SACL) {
+            return Err(WinError::new(
+                ERROR_INVALID_PARAMETER,
+                Some("Reading the SACL requires an elevated security descriptor".to_owned()),
+            ));
+        }
+        Self::create_sd(path.as_ref().as_os_str(), SE_FILE_OBJECT, components.0)
+    }
+
     /// Creates a SecurityDescriptor from object name and object type.
     ///
     /// # Arguments
@@ -148,15 +461,203 @@ impl SecurityDescriptorImpl<Unprivileged> {
     where
         S: AsRef<str>,
     {
-        let wide_string = WideCString::new(handle.as_ref());
         Self::create_sd(
-            wide_string.as_ptr(),
+            OsStr::new(handle.as_ref()),
+            object_type,
+            OBJECT_SECURITY_INFORMATION::get_safe(),
+        )
+    }
+
+    /// Creates a SecurityDescriptor from a registry key.
+    ///
+    /// Builds the object name (`MACHINE\SOFTWARE\...`) and object type (including the WOW64
+    /// view) expected by the security APIs, see [`RegistryPath`].
+    ///
+    /// # Arguments
+    ///
+    /// * `hive` - The hive of the key.
+    /// * `subkey` - The path of the key relative to the hive (e.g., `Software\MyApp`).
+    /// * `view` - The registry view to resolve the key in.
+    ///
+    /// # Returns
+    ///
+    /// A `SecurityDescriptor` on success.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{
+    ///     registry::{Hive, RegView},
+    ///     sd::SecurityDescriptor,
+    /// };
+    ///
+    /// let sd = SecurityDescriptor::from_registry_key(Hive::LocalMachine, r"Software\MyApp", RegView::Wow6432)?;
+    /// println!("{}", sd.as_sd_string()?);
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn from_registry_key<S>(hive: Hive, subkey: S, view: RegView) -> Result<Self, WinError>
+    where
+        S: AsRef<str>,
+    {
+        let path = RegistryPath::new(hive, subkey.as_ref()).with_view(view);
+        Self::create_sd(
+            OsStr::new(&path.object_name()),
+            path.object_type(),
+            OBJECT_SECURITY_INFORMATION::get_safe(),
+        )
+    }
+
+    /// Creates a SecurityDescriptor from a Windows service.
+    ///
+    /// The service is opened through the service control manager with `READ_CONTROL` access,
+    /// which members of the *Users* group have for most services.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the service (not its display name), optionally qualified with
+    ///   a remote machine (`\\SERVER\Spooler`, see [`RemoteHost::object_name`](crate::remote::RemoteHost::object_name)).
+    ///
+    /// # Returns
+    ///
+    /// A `SecurityDescriptor` on success.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ERROR_SERVICE_DOES_NOT_EXIST` if there is no such service, or an error if the
+    /// service cannot be opened.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sd::SecurityDescriptor;
+    ///
+    /// let sd = SecurityDescriptor::from_service("Spooler")?;
+    /// println!("{}", sd.as_sd_string()?);
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn from_service<S>(name: S) -> Result<Self, WinError>
+    where
+        S: AsRef<str>,
+    {
+        Self::create_sd(
+            OsStr::new(name.as_ref()),
+            SE_SERVICE,
+            OBJECT_SECURITY_INFORMATION::get_safe(),
+        )
+    }
+
+    /// Creates a SecurityDescriptor from a window station.
+    ///
+    /// The window station is opened with `READ_CONTROL` access. Services hardening the interactive
+    /// window station typically inspect `WinSta0`.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the window station (e.g., `WinSta0`).
+    ///
+    /// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/winstation/window-station-security-and-access-rights)
+    ///
+    /// # Returns
+    ///
+    /// A `SecurityDescriptor` on success.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sd::SecurityDescriptor;
+    ///
+    /// let sd = SecurityDescriptor::from_window_station("WinSta0")?;
+    /// println!("{}", sd.as_sd_string()?);
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn from_window_station<S>(name: S) -> Result<Self, WinError>
+    where
+        S: AsRef<str>,
+    {
+        let components = SecurityInformation::standard();
+        let station = WindowObject::open_window_station(name.as_ref(), window::read_access(components))?;
+        Self::create_sd_from_handle(station.as_raw_handle() as HANDLE, SE_WINDOW_OBJECT, components.0)
+    }
+
+    /// Creates a SecurityDescriptor from a desktop of the window station of the calling process.
+    ///
+    /// The desktop is opened with `READ_CONTROL` access.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the desktop (e.g., `Default` or `Winlogon`).
+    ///
+    /// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/winstation/desktop-security-and-access-rights)
+    ///
+    /// # Returns
+    ///
+    /// A `SecurityDescriptor` on success.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sd::SecurityDescriptor;
+    ///
+    /// let sd = SecurityDescriptor::from_desktop("Default")?;
+    /// println!("{}", sd.as_sd_string()?);
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn from_desktop<S>(name: S) -> Result<Self, WinError>
+    where
+        S: AsRef<str>,
+    {
+        let components = SecurityInformation::standard();
+        let desktop = WindowObject::open_desktop(name.as_ref(), window::read_access(components))?;
+        Self::create_sd_from_handle(desktop.as_raw_handle() as HANDLE, SE_WINDOW_OBJECT, components.0)
+    }
+
+    /// Creates a SecurityDescriptor from an open handle using `GetSecurityInfo`.
+    ///
+    /// This reads the security of objects that are already open (e.g., a `std::fs::File`,
+    /// a child process) or that have no name at all (anonymous kernel objects). The handle must
+    /// have been opened with `READ_CONTROL` access.
+    ///
+    /// The descriptor has no [`origin`](Self::origin), as the handle may be closed at any time.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The open object.
+    /// * `object_type` - a type of the object (e.g., `SE_FILE_OBJECT` or `SE_KERNEL_OBJECT`)
+    ///
+    /// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/api/aclapi/nf-aclapi-getsecurityinfo)
+    ///
+    /// # Returns
+    ///
+    /// A `SecurityDescriptor` on success.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    ///
+    /// use win_acl_rs::{SE_FILE_OBJECT, sd::SecurityDescriptor};
+    ///
+    /// let file = File::open("C:\\path\\to\\file.txt").unwrap();
+    /// let sd = SecurityDescriptor::from_raw_handle(&file, SE_FILE_OBJECT)?;
+    /// println!("{}", sd.as_sd_string()?);
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn from_raw_handle<H>(handle: &H, object_type: SE_OBJECT_TYPE) -> Result<Self, WinError>
+    where
+        H: AsRawHandle,
+    {
+        Self::create_sd_from_handle(
+            handle.as_raw_handle() as HANDLE,
             object_type,
             OBJECT_SECURITY_INFORMATION::get_safe(),
         )
     }
 }
 
+// SAFETY: the descriptor exclusively owns its LocalAlloc'd buffer and all the cached pointers point into it,
+// so it can be moved to another thread.
+unsafe impl<P: PrivilegeLevel> Send for SecurityDescriptorImpl<P> {}
+
 impl<P: PrivilegeLevel> Drop for SecurityDescriptorImpl<P> {
     fn drop(&mut self) {
         unsafe {
@@ -180,6 +681,7 @@ impl<P: PrivilegeLevel> std::fmt::Debug for SecurityDescriptorImpl<P> {
             .field("group_sid_ptr", &self.group_sid_ptr)
             .field("dacl_ptr", &self.dacl_ptr)
             .field("sacl_ptr", &self.sacl_ptr)
+            .field("origin", &self.origin)
             .finish()
     }
 }
@@ -194,6 +696,16 @@ impl<P: PrivilegeLevel> SecurityDescriptorImpl<P> {
         unsafe { IsValidSecurityDescriptor(psd) == TRUE }
     }
 
+    /// Returns the control flags of the security descriptor.
+    ///
+    /// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getsecuritydescriptorcontrol)
+    pub fn control(&self) -> Result<SdControlFlags, WinError> {
+        let mut control = 0u16;
+        let mut revision = 0u32;
+        unsafe { winapi_bool_call!(GetSecurityDescriptorControl(self.sd_ptr, &mut control, &mut revision)) };
+        Ok(SdControlFlags(control))
+    }
+
     /// Indicates that the SID of the owner of the security descriptor was provided by a default mechanism.
     pub fn owner_defaulted(&self) -> Result<bool, WinError> {
         let mut _owner_sid_ptr: PSID = null_mut();
@@ -300,10 +812,6 @@ impl<P: PrivilegeLevel> SecurityDescriptorImpl<P> {
         let wide_str = WideCString::new(sd_string.as_ref());
 
         let mut sd_ptr: PSECURITY_DESCRIPTOR = null_mut();
-        let mut dacl_ptr: *mut ACL = null_mut();
-        let mut sacl_ptr: *mut ACL = null_mut();
-        let mut owner_sid_ptr: PSID = null_mut();
-        let mut group_sid_ptr: PSID = null_mut();
 
         unsafe {
             winapi_bool_call!(ConvertStringSecurityDescriptorToSecurityDescriptorW(
@@ -317,67 +825,428 @@ impl<P: PrivilegeLevel> SecurityDescriptorImpl<P> {
         #[cfg(debug_assertions)]
         println!("IsValidSecurityDescriptor: {}", Self::is_sd_valid(sd_ptr));
 
-        let mut _owner_defaulted: BOOL = 0;
-        let mut _group_defaulted: BOOL = 0;
-        let mut _dacl_present: BOOL = 0;
-        let mut _dacl_defaulted: BOOL = 0;
-        let mut _sacl_present: BOOL = 0;
-        let mut _sacl_defaulted: BOOL = 0;
-
-        unsafe {
-            winapi_bool_call!(GetSecurityDescriptorOwner(
-                sd_ptr,
-                &mut owner_sid_ptr,
-                &mut _owner_defaulted
-            ));
+        unsafe { Self::from_owned_ptr(sd_ptr, None) }
+    }
 
-            winapi_bool_call!(GetSecurityDescriptorGroup(
-                sd_ptr,
-                &mut group_sid_ptr,
-                &mut _group_defaulted
-            ));
+    /// Creates a security descriptor from its binary (self-relative) form.
+    ///
+    /// This is the form security descriptors are persisted in: registry values, the
+    /// `ntSecurityDescriptor` attribute of directory objects, files written with [`Self::to_bytes`], ...
+    /// The bytes are copied, so the buffer does not need to be aligned.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The self-relative security descriptor.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ERROR_INVALID_SECURITY_DESCR` if the bytes are not a well-formed self-relative
+    /// security descriptor (e.g., a component lies outside of the buffer).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sd::SecurityDescriptor;
+    ///
+    /// let sd: SecurityDescriptor = "O:BAD:(A;;FA;;;SY)".parse()?;
+    /// let bytes = sd.to_bytes();
+    /// let copy = SecurityDescriptor::from_bytes(&bytes)?;
+    /// assert_eq!(copy.as_sd_string()?, "O:BAD:(A;;FA;;;SY)");
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WinError> {
+        if !is_well_formed_self_relative(bytes) {
+            return Err(ERROR_INVALID_SECURITY_DESCR.into());
+        }
 
-            winapi_bool_call!(GetSecurityDescriptorDacl(
-                sd_ptr,
-                &mut _dacl_present,
-                &mut dacl_ptr,
-                &mut _dacl_defaulted,
-            ));
+        let sd_ptr = unsafe { LocalAlloc(LMEM_FIXED, bytes.len()) };
+        if sd_ptr.is_null() {
+            return Err(ERROR_OUTOFMEMORY.into());
+        }
+        unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), sd_ptr as *mut u8, bytes.len()) };
 
-            winapi_bool_call!(GetSecurityDescriptorSacl(
-                sd_ptr,
-                &mut _sacl_present,
-                &mut sacl_ptr,
-                &mut _sacl_defaulted,
-            ))
-        };
+        if !Self::is_sd_valid(sd_ptr) {
+            unsafe { assert_free!(sd_ptr, "SecurityDescriptorImpl::from_bytes()") };
+            return Err(ERROR_INVALID_SECURITY_DESCR.into());
+        }
 
-        Ok(Self {
-            sd_ptr,
-            dacl_ptr,
-            sacl_ptr,
-            owner_sid_ptr,
-            group_sid_ptr,
-            _priv: PhantomData,
-        })
+        unsafe { Self::from_owned_ptr(sd_ptr, None) }
     }
 
-    /// Converts security descriptor into a string format
+    /// Checks whether two security descriptors have the same content: the same owner, group,
+    /// DACL and SACL (same ACEs in the same order), regardless of where they are stored or which
+    /// object they were read from.
     ///
-    /// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/secauthz/security-descriptor-string-format)
+    /// Components that were not read (e.g., the SACL of an unprivileged descriptor) compare
+    /// as absent. Control flags such as the DACL protection are not compared.
     ///
-    /// # Returns
+    /// # Errors
     ///
-    /// A `String` on success.
-    pub fn as_sd_string(&self) -> Result<String, WinError> {
-        let mut buf_ptr: *mut u16 = null_mut();
-        let mut buf_len: u32 = 0;
+    /// Returns an error if an ACL cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sd::SecurityDescriptor;
+    ///
+    /// let baseline = SecurityDescriptor::from_path("C:\\data")?;
+    /// // ... later ...
+    /// let current = SecurityDescriptor::from_path("C:\\data")?;
+    /// if !current.equivalent(&baseline)? {
+    ///     println!("permissions drifted");
+    /// }
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn equivalent<Q: PrivilegeLevel>(&self, other: &SecurityDescriptorImpl<Q>) -> Result<bool, WinError> {
+        let sid_bytes = |sid: Option<SidRef<'_>>| sid.map(|sid| sid.to_vec());
+        let acl_bytes = |acl: Option<AclRef<'_>>| acl.map(|acl| acl.ace_bytes().map(<[u8]>::to_vec)).transpose();
+
+        Ok(sid_bytes(self.owner_sid()) == sid_bytes(other.owner_sid())
+            && sid_bytes(self.group_sid()) == sid_bytes(other.group_sid())
+            && acl_bytes(self.dacl())? == acl_bytes(other.dacl())?
+            && acl_bytes(self.sacl_unchecked())? == acl_bytes(other.sacl_unchecked())?)
+    }
+
+    /// Returns a stable hash of the content of the security descriptor.
+    ///
+    /// The hash covers the owner, the group and the ACEs of the DACL and SACL, canonicalized so
+    /// that descriptors granting the same access hash the same: consecutive ACEs of the same type
+    /// (whose order does not affect access checks) are hashed in sorted order, the entries of the
+    /// SACL are hashed in sorted order and unused ACL space and ACE padding are ignored. Control
+    /// flags are not hashed.
+    ///
+    /// The hash is a 64-bit FNV-1a, stable across processes, machines and versions of this crate,
+    /// so baselines can be stored and compared later. Equal hashes indicate (but do not prove)
+    /// equal content.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an ACL or one of its ACEs cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sd::SecurityDescriptor;
+    ///
+    /// let baseline = 0x1234_5678_9ABC_DEF0;
+    /// if SecurityDescriptor::from_path("C:\\data")?.content_hash()? != baseline {
+    ///     println!("permissions drifted");
+    /// }
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn content_hash(&self) -> Result<u64, WinError> {
+        let mut hasher = Fnv1a::new();
+        for sid in [self.owner_sid(), self.group_sid()] {
+            match sid {
+                Some(sid) => hasher.write_chunk(sid.as_bytes()),
+                None => hasher.write_absent(),
+            }
+        }
+        for (acl, sorted) in [(self.dacl(), false), (self.sacl_unchecked(), true)] {
+            let Some(acl) = acl else {
+                hasher.write_absent();
+                continue;
+            };
+            let mut aces = Vec::new();
+            for ace in acl.try_iter() {
+                let ace = ace?;
+                aces.push((ace.ace_type(), canonical_ace(&ace)?));
+            }
+            if sorted {
+                aces.sort();
+            } else {
+                aces.chunk_by_mut(|a, b| a.0 == b.0).for_each(<[_]>::sort);
+            }
+            hasher.write_len(aces.len());
+            for (_, ace) in aces {
+                hasher.write_chunk(&ace);
+            }
+        }
+        Ok(hasher.finish())
+    }
+
+    /// Creates an independent deep copy of the security descriptor.
+    ///
+    /// The copy owns its own buffer holding the owner, group and ACLs, and keeps the origin,
+    /// so it can serve as a snapshot before the original is modified.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the copy cannot be allocated.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{acl::Acl, sd::{SecurityDescriptor, SecurityInformation}};
+    ///
+    /// let mut sd = SecurityDescriptor::from_path("C:\\path\\to\\file.txt")?;
+    /// let snapshot = sd.try_clone()?;
+    /// sd.set_dacl(Acl::new()?)?;
+    /// sd.reapply(SecurityInformation::DACL)?;
+    /// // ... and undo
+    /// snapshot.reapply(SecurityInformation::DACL)?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn try_clone(&self) -> Result<Self, WinError> {
+        let mut copy = Self::from_bytes(&self.to_bytes())?;
+        copy.origin = self.origin.clone();
+        Ok(copy)
+    }
+
+    /// Computes the security descriptor resulting from a set of modifications, without touching
+    /// the object.
+    ///
+    /// This is a dry run: tools can show a preview of what will change (e.g., the SDDL of the
+    /// result or an [`Report`](crate::report::Report) of it) and apply the result afterward with
+    /// [`Self::reapply`], which works since the planned descriptor keeps the origin of this one.
+    ///
+    /// The modifications are applied in order. Granted and denied entries are inserted at their
+    /// canonical position in the DACL (denies before allows, explicit before inherited), so the
+    /// preview matches what Windows enforces; a descriptor without a DACL gets a new DACL holding
+    /// only the added entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `modifications` - The modifications to plan.
+    ///
+    /// # Returns
+    ///
+    /// The resulting security descriptor.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the resulting descriptor cannot be assembled.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{
+    ///     mask::FileAccess,
+    ///     sd::{Modification, SecurityDescriptor, SecurityInformation},
+    ///     sid::Sid,
+    /// };
+    ///
+    /// let sd = SecurityDescriptor::from_path("C:\\data\\reports")?;
+    /// let planned = sd.plan(&[Modification::grant(Sid::from_string("S-1-5-32-545")?, FileAccess::READ)])?;
+    /// println!("before: {}\nafter:  {}", sd.as_sd_string()?, planned.as_sd_string()?);
+    /// planned.reapply(SecurityInformation::DACL)?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn plan(&self, modifications: &[Modification]) -> Result<Self, WinError> {
+        let mut owner = self.owner_sid();
+        let mut group = self.group_sid();
+        let mut dacl = self.dacl().map(|dacl| dacl.copy_with_extra(0)).transpose()?;
+
+        for modification in modifications {
+            match modification {
+                Modification::Owner(sid) => owner = Some(sid.as_sid_ref()),
+                Modification::Group(sid) => group = Some(sid.as_sid_ref()),
+                Modification::Grant { trustee, mask } | Modification::Deny { trustee, mask } => {
+                    let extra = size_of::<ACCESS_ALLOWED_ACE>() + trustee.len();
+                    let mut grown = match &dacl {
+                        Some(dacl) => dacl.copy_with_extra(extra)?,
+                        None => Acl::with_capacity(1, trustee.len())?,
+                    };
+                    // at the canonical position, so that a deny takes effect before existing allows
+                    let ace_type = if matches!(modification, Modification::Grant { .. }) {
+                        AceType::AccessAllowed
+                    } else {
+                        AceType::AccessDenied
+                    };
+                    grown.add_canonical(ace_type, AccessMask::from(*mask), AceFlags::empty(), trustee)?;
+                    dacl = Some(grown);
+                }
+            }
+        }
+
+        let sacl = self.sacl_unchecked();
+        let control = self.control()? & INHERITANCE_CONTROL;
+        let mut planned = Self::from_components_with_control(owner, group, dacl.as_ref(), sacl.as_deref(), control)?;
+        planned.origin = self.origin.clone();
+        Ok(planned)
+    }
+
+    /// Returns the binary (self-relative) form of the security descriptor.
+    ///
+    /// The bytes can be persisted or sent to another process and read back with [`Self::from_bytes`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let len = unsafe { GetSecurityDescriptorLength(self.sd_ptr) } as usize;
+        unsafe { from_raw_parts(self.sd_ptr as *const u8, len) }.to_vec()
+    }
+
+    /// Builds a self-relative security descriptor from its components.
+    ///
+    /// The components are assembled into an absolute security descriptor first, which is then
+    /// converted to a self-relative one, so the result owns copies of all the components.
+    pub(crate) fn from_components(
+        owner: Option<SidRef<'_>>,
+        group: Option<SidRef<'_>>,
+        dacl: Option<&Acl>,
+        sacl: Option<&Acl>,
+    ) -> Result<Self, WinError> {
+        Self::from_components_with_control(owner, group, dacl, sacl, SdControlFlags::empty())
+    }
+
+    /// Builds a self-relative security descriptor from its components, setting the given
+    /// control flags (e.g., `SdControlFlags::DACL_PROTECTED`).
+    pub(crate) fn from_components_with_control(
+        owner: Option<SidRef<'_>>,
+        group: Option<SidRef<'_>>,
+        dacl: Option<&Acl>,
+        sacl: Option<&Acl>,
+        control: SdControlFlags,
+    ) -> Result<Self, WinError> {
+        let mut absolute: SECURITY_DESCRIPTOR = unsafe { std::mem::zeroed() };
+        let absolute_ptr: PSECURITY_DESCRIPTOR = &mut absolute as *mut _ as _;
+
+        unsafe {
+            winapi_bool_call!(InitializeSecurityDescriptor(absolute_ptr, SECURITY_DESCRIPTOR_REVISION));
+            winapi_bool_call!(SetSecurityDescriptorOwner(
+                absolute_ptr,
+                owner.map_or(null_mut(), |sid| sid.as_ptr() as _),
+                FALSE
+            ));
+            winapi_bool_call!(SetSecurityDescriptorGroup(
+                absolute_ptr,
+                group.map_or(null_mut(), |sid| sid.as_ptr() as _),
+                FALSE
+            ));
+            winapi_bool_call!(SetSecurityDescriptorDacl(
+                absolute_ptr,
+                dacl.is_some() as BOOL,
+                dacl.map_or(null_mut(), |acl| acl.as_ptr()),
+                FALSE
+            ));
+            winapi_bool_call!(SetSecurityDescriptorSacl(
+                absolute_ptr,
+                sacl.is_some() as BOOL,
+                sacl.map_or(null_mut(), |acl| acl.as_ptr()),
+                FALSE
+            ));
+            if control != SdControlFlags::empty() {
+                winapi_bool_call!(SetSecurityDescriptorControl(absolute_ptr, control.0, control.0));
+            }
+        }
+
+        let mut len: u32 = 0;
+        let result = unsafe { MakeSelfRelativeSD(absolute_ptr, null_mut(), &mut len) };
+        if result == FALSE {
+            let err = unsafe { GetLastError() };
+            if err != ERROR_INSUFFICIENT_BUFFER {
+                return Err(err.into());
+            }
+        }
+
+        let sd_ptr = unsafe { LocalAlloc(LMEM_FIXED, len as usize) };
+        if sd_ptr.is_null() {
+            return Err(ERROR_OUTOFMEMORY.into());
+        }
+        unsafe {
+            winapi_bool_call!(MakeSelfRelativeSD(absolute_ptr, sd_ptr, &mut len), {
+                assert_free!(sd_ptr, "SecurityDescriptorImpl::from_components()");
+            });
+            Self::from_owned_ptr(sd_ptr, None)
+        }
+    }
+
+    /// Takes ownership of a `LocalAlloc`'d self-relative security descriptor and caches the pointers
+    /// to its components.
+    ///
+    /// The buffer is freed if the components cannot be read.
+    unsafe fn from_owned_ptr(sd_ptr: PSECURITY_DESCRIPTOR, origin: Option<ObjectOrigin>) -> Result<Self, WinError> {
+        let mut sd = Self {
+            sd_ptr,
+            owner_sid_ptr: null_mut(),
+            group_sid_ptr: null_mut(),
+            dacl_ptr: null_mut(),
+            sacl_ptr: null_mut(),
+            origin,
+            _priv: PhantomData,
+        };
+
+        let mut _owner_defaulted: BOOL = 0;
+        let mut _group_defaulted: BOOL = 0;
+        let mut _dacl_present: BOOL = 0;
+        let mut _dacl_defaulted: BOOL = 0;
+        let mut _sacl_present: BOOL = 0;
+        let mut _sacl_defaulted: BOOL = 0;
+
+        unsafe {
+            winapi_bool_call!(GetSecurityDescriptorOwner(
+                sd_ptr,
+                &mut sd.owner_sid_ptr,
+                &mut _owner_defaulted
+            ));
+
+            winapi_bool_call!(GetSecurityDescriptorGroup(
+                sd_ptr,
+                &mut sd.group_sid_ptr,
+                &mut _group_defaulted
+            ));
+
+            winapi_bool_call!(GetSecurityDescriptorDacl(
+                sd_ptr,
+                &mut _dacl_present,
+                &mut sd.dacl_ptr,
+                &mut _dacl_defaulted,
+            ));
+
+            winapi_bool_call!(GetSecurityDescriptorSacl(
+                sd_ptr,
+                &mut _sacl_present,
+                &mut sd.sacl_ptr,
+                &mut _sacl_defaulted,
+            ))
+        };
+
+        Ok(sd)
+    }
+
+    /// Converts security descriptor into a string format
+    ///
+    /// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/secauthz/security-descriptor-string-format)
+    ///
+    /// # Returns
+    ///
+    /// A `String` on success.
+    pub fn as_sd_string(&self) -> Result<String, WinError> {
+        self.as_sd_string_with(SecurityInformation(OBJECT_SECURITY_INFORMATION::get_all()))
+    }
+
+    /// Converts the selected components of the security descriptor into a string format
+    ///
+    /// Only the selected components are emitted, e.g., `SecurityInformation::DACL` yields just the
+    /// `D:` part, so SACL details do not end up in logs by accident. Selecting
+    /// `SecurityInformation::LABEL` emits the mandatory label in the `S:` part.
+    ///
+    /// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/secauthz/security-descriptor-string-format)
+    ///
+    /// # Arguments
+    ///
+    /// * `components` - The components to stringify.
+    ///
+    /// # Returns
+    ///
+    /// A `String` on success.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sd::{SecurityDescriptor, SecurityInformation};
+    ///
+    /// let sd = SecurityDescriptor::from_path("C:\\path\\to\\file.txt")?;
+    /// println!("{}", sd.as_sd_string_with(SecurityInformation::OWNER | SecurityInformation::GROUP)?);
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn as_sd_string_with(&self, components: SecurityInformation) -> Result<String, WinError> {
+        let mut buf_ptr: *mut u16 = null_mut();
+        let mut buf_len: u32 = 0;
 
         unsafe {
             winapi_bool_call!(ConvertSecurityDescriptorToStringSecurityDescriptorW(
                 self.sd_ptr,
                 SDDL_REVISION_1,
-                OBJECT_SECURITY_INFORMATION::get_all(),
+                components.0,
                 &mut buf_ptr,
                 &mut buf_len,
             ))
@@ -387,12 +1256,20 @@ impl<P: PrivilegeLevel> SecurityDescriptorImpl<P> {
         let string = WideCString::from_wide_slice(slice);
 
         if !buf_ptr.is_null() {
-            unsafe { assert_free!(buf_ptr, "SecurityDescriptorImpl::as_sd_string()") };
+            unsafe { assert_free!(buf_ptr, "SecurityDescriptorImpl::as_sd_string_with()") };
         }
 
         Ok(string.as_string())
     }
 
+    /// Returns the raw pointer to the self-relative security descriptor.
+    ///
+    /// Useful for passing the descriptor to Windows APIs not wrapped by this crate
+    /// (e.g., `CoInitializeSecurity`). The pointer is valid for the lifetime of `self`.
+    pub fn as_ptr(&self) -> PSECURITY_DESCRIPTOR {
+        self.sd_ptr
+    }
+
     /// Returns the owner SID of the security descriptor.
     ///
     /// The owner is the security principal that owns the object and has special permissions
@@ -431,48 +1308,1548 @@ impl<P: PrivilegeLevel> SecurityDescriptorImpl<P> {
     ///
     /// # Returns
     ///
-    /// `Some(Acl)` containing the DACL if present, or `None` if the security descriptor
+    /// `Some(AclRef)` borrowing the DACL if present, or `None` if the security descriptor
     /// doesn't have a DACL.
-    pub fn dacl(&self) -> Option<Acl> {
+    pub fn dacl(&self) -> Option<AclRef<'_>> {
         if self.dacl_ptr.is_null() {
             None
         } else {
-            Some(unsafe { Acl::from_ptr(self.dacl_ptr) })
+            Some(unsafe { AclRef::from_ptr(self.dacl_ptr) })
         }
     }
 
-    pub(crate) fn create_sd(
-        obj_name: PCWSTR,
-        obj_type: SE_OBJECT_TYPE,
-        flags: OBJECT_SECURITY_INFORMATION,
-    ) -> Result<Self, WinError> {
-        let mut sd_ptr: PSECURITY_DESCRIPTOR = null_mut();
-        let mut dacl_ptr: *mut ACL = null_mut();
-        let mut sacl_ptr: *mut ACL = null_mut();
-        let mut owner_sid_ptr: PSID = null_mut();
-        let mut group_sid_ptr: PSID = null_mut();
+    /// Replaces the DACL of the security descriptor.
+    ///
+    /// The ACL is copied into the buffer of the security descriptor, so the descriptor stays
+    /// self-contained and can be applied (see [`Self::apply_to_path`]) or serialized afterward.
+    /// The owner, group, SACL, the inheritance control flags and the origin are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `dacl` - The new DACL.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the security descriptor cannot be rebuilt.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{
+    ///     acl::Acl,
+    ///     mask::AccessMask,
+    ///     sd::{SecurityDescriptor, SecurityInformation},
+    ///     sid::Sid,
+    ///     wellknown::WinBuiltinAdministratorsSid,
+    /// };
+    ///
+    /// let mut sd = SecurityDescriptor::from_path("C:\\path\\to\\file.txt")?;
+    /// let mut dacl = Acl::new()?;
+    /// dacl.allow(AccessMask::full(), &Sid::from_well_known_sid(WinBuiltinAdministratorsSid)?)?;
+    /// sd.set_dacl(dacl)?;
+    /// sd.reapply(SecurityInformation::DACL)?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn set_dacl(&mut self, dacl: Acl) -> Result<(), WinError> {
+        self.replace_acls(Some(&dacl), None)
+    }
 
-        unsafe {
-            winapi_call!(GetNamedSecurityInfoW(
-                obj_name,
-                obj_type,
-                flags,
-                &mut owner_sid_ptr,
-                &mut group_sid_ptr,
-                &mut dacl_ptr,
-                &mut sacl_ptr,
-                &mut sd_ptr,
-            ))
+    /// Replaces the given ACLs, keeping the other components, the inheritance control flags
+    /// and the origin.
+    pub(crate) fn replace_acls(&mut self, dacl: Option<&Acl>, sacl: Option<&Acl>) -> Result<(), WinError> {
+        let control = self.control()? & INHERITANCE_CONTROL;
+        let rebuilt = {
+            let current_dacl = self.dacl();
+            let current_sacl = self.sacl_unchecked();
+            Self::from_components_with_control(
+                self.owner_sid(),
+                self.group_sid(),
+                dacl.or(current_dacl.as_deref()),
+                sacl.or(current_sacl.as_deref()),
+                control,
+            )?
         };
+        let origin = self.origin.take();
+        *self = rebuilt;
+        self.origin = origin;
+        Ok(())
+    }
 
-        Ok(Self {
-            sd_ptr,
-            dacl_ptr,
-            sacl_ptr,
-            owner_sid_ptr,
-            group_sid_ptr,
-            _priv: PhantomData,
-        })
+    /// Returns the SACL regardless of the privilege level the descriptor was read with.
+    pub(crate) fn sacl_unchecked(&self) -> Option<AclRef<'_>> {
+        if self.sacl_ptr.is_null() {
+            None
+        } else {
+            Some(unsafe { AclRef::from_ptr(self.sacl_ptr) })
+        }
+    }
+
+    /// Returns the named object this security descriptor was read from, if any.
+    ///
+    /// Security descriptors parsed from a string have no origin.
+    pub fn origin(&self) -> Option<&ObjectOrigin> {
+        self.origin.as_ref()
+    }
+
+    /// Re-reads the security descriptor from the object it was originally read from.
+    ///
+    /// The same components that were requested originally are read again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the security descriptor has no origin (see [`Self::origin`])
+    /// or if the object cannot be read.
+    pub fn refresh(&mut self) -> Result<(), WinError> {
+        let origin = self.origin.as_ref().ok_or(Self::NO_ORIGIN)?;
+        *self = Self::create_sd(&origin.name, origin.object_type, origin.components.0)?;
+        Ok(())
+    }
+
+    /// Returns the mandatory integrity label of the object.
+    ///
+    /// The label is stored in the SACL, but reading it only requires `READ_CONTROL` access
+    /// (`LABEL_SECURITY_INFORMATION`), not the *SeSecurityPrivilege* privilege, so it is available
+    /// for unprivileged descriptors as well. If the descriptor was read from a named object
+    /// (see [`Self::origin`]) without the label, the label is queried from the object; otherwise
+    /// it is read from the SACL of the descriptor itself (e.g., one parsed from `S:(ML;;NW;;;LW)`).
+    ///
+    /// # Returns
+    ///
+    /// `Some(MandatoryLabel)` with the integrity level and policy of the label, or `None` if the
+    /// object has no explicit label, in which case [`MandatoryLabel::implicit`] applies.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the label cannot be read or is malformed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{
+    ///     integrity::{IntegrityLevel, MandatoryLabel},
+    ///     sd::SecurityDescriptor,
+    /// };
+    ///
+    /// let sd = SecurityDescriptor::from_path("C:\\path\\to\\file.txt")?;
+    /// let label = sd.integrity_label()?.unwrap_or_else(MandatoryLabel::implicit);
+    /// if label.level() < IntegrityLevel::MEDIUM {
+    ///     println!("writable by {} integrity processes", label.level());
+    /// }
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn integrity_label(&self) -> Result<Option<MandatoryLabel>, WinError> {
+        match &self.origin {
+            Some(origin) if !origin.components.contains(SecurityInformation::LABEL) => {
+                let labeled = Self::create_sd(&origin.name, origin.object_type, LABEL_SECURITY_INFORMATION)?;
+                labeled
+                    .sacl_unchecked()
+                    .map_or(Ok(None), |sacl| integrity::label_of(&sacl))
+            }
+            _ => self
+                .sacl_unchecked()
+                .map_or(Ok(None), |sacl| integrity::label_of(&sacl)),
+        }
+    }
+
+    /// Returns the resource attributes (claims) of the object.
+    ///
+    /// Like the integrity label, the attributes are stored in the SACL but reading them only
+    /// requires `READ_CONTROL` access (`ATTRIBUTE_SECURITY_INFORMATION`). If this descriptor was
+    /// read from an object without them, they are queried from the object; otherwise they are
+    /// decoded from the SACL of this descriptor.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the attributes cannot be read or are malformed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sd::SecurityDescriptor;
+    ///
+    /// let sd = SecurityDescriptor::from_path("C:\\data\\plan.docx")?;
+    /// for attribute in sd.resource_attributes()? {
+    ///     println!("{} = {:?}", attribute.name(), attribute.values());
+    /// }
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn resource_attributes(&self) -> Result<Vec<ResourceAttribute>, WinError> {
+        let attributes = |sacl: Option<AclRef<'_>>| -> Result<Vec<ResourceAttribute>, WinError> {
+            let mut attributes = Vec::new();
+            if let Some(sacl) = sacl {
+                for ace in sacl.try_iter() {
+                    attributes.extend(ace?.resource_attribute()?);
+                }
+            }
+            Ok(attributes)
+        };
+        match &self.origin {
+            Some(origin) if !origin.components.contains(SecurityInformation::ATTRIBUTE) => {
+                let sd = Self::create_sd(&origin.name, origin.object_type, ATTRIBUTE_SECURITY_INFORMATION)?;
+                attributes(sd.sacl_unchecked())
+            }
+            _ => attributes(self.sacl_unchecked()),
+        }
+    }
+
+    /// Returns the ID (CAPID) of the central access policy applied to the object.
+    ///
+    /// The policy is bound by a scoped policy ID ACE in the SACL, read with
+    /// `SCOPE_SECURITY_INFORMATION` and `READ_CONTROL` access. If this descriptor was read from
+    /// an object without it, it is queried from the object; otherwise it is decoded from the SACL
+    /// of this descriptor.
+    ///
+    /// # Returns
+    ///
+    /// The CAPID, or `None` if no central access policy is applied.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the policy cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{claims, sd::SecurityDescriptor};
+    ///
+    /// let sd = SecurityDescriptor::from_path("C:\\data\\plan.docx")?;
+    /// if let Some(capid) = sd.central_access_policy()? {
+    ///     let applied = claims::applied_capids()?.contains(&capid);
+    ///     println!("{:?} (applied on this machine: {})", capid, applied);
+    /// }
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn central_access_policy(&self) -> Result<Option<Sid>, WinError> {
+        let capid = |sacl: Option<AclRef<'_>>| -> Result<Option<Sid>, WinError> {
+            if let Some(sacl) = sacl {
+                for ace in sacl.try_iter() {
+                    if let Some(capid) = ace?.scoped_policy_id()? {
+                        return Ok(Some(capid));
+                    }
+                }
+            }
+            Ok(None)
+        };
+        match &self.origin {
+            Some(origin) if !origin.components.contains(SecurityInformation::SCOPE) => {
+                let sd = Self::create_sd(&origin.name, origin.object_type, SCOPE_SECURITY_INFORMATION)?;
+                capid(sd.sacl_unchecked())
+            }
+            _ => capid(self.sacl_unchecked()),
+        }
+    }
+
+    /// Writes the selected components of this security descriptor back to the object it was
+    /// originally read from.
+    ///
+    /// # Arguments
+    ///
+    /// * `components` - The components to write (e.g., `SecurityInformation::DACL`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the security descriptor has no origin (see [`Self::origin`])
+    /// or if the object cannot be written. Returns `ERROR_INVALID_PARAMETER` if a selected
+    /// component is not present in the descriptor.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sd::{SecurityDescriptor, SecurityInformation};
+    ///
+    /// let mut sd = SecurityDescriptor::from_path("C:\\path\\to\\file.txt")?;
+    /// // ... modify the descriptor ...
+    /// sd.reapply(SecurityInformation::DACL)?;
+    /// sd.refresh()?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn reapply(&self, components: SecurityInformation) -> Result<(), WinError> {
+        let origin = self.origin.as_ref().ok_or(Self::NO_ORIGIN)?;
+        self.set_named(&origin.name, origin.object_type, components)
+    }
+
+    /// Writes the selected components of this security descriptor to a file or directory.
+    ///
+    /// Writing the SACL requires the *SeSecurityPrivilege* privilege (see
+    /// [`PrivilegeToken::try_elevate`](crate::elevated::PrivilegeToken)), writing an owner other
+    /// than the caller requires *SeTakeOwnershipPrivilege* or *SeRestorePrivilege*.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the file or directory.
+    /// * `components` - The components to write (e.g., `SecurityInformation::DACL`), optionally
+    ///   combined with the protection flags (e.g., `SecurityInformation::PROTECTED_DACL`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object cannot be written. Returns `ERROR_INVALID_PARAMETER` if a
+    /// selected component is not present in the descriptor (e.g., a descriptor read without its
+    /// DACL), rather than writing a NULL DACL that grants everyone full access.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sd::{SecurityDescriptor, SecurityInformation};
+    ///
+    /// let sd = SecurityDescriptor::from_path("C:\\path\\to\\template.txt")?;
+    /// sd.apply_to_path("C:\\path\\to\\file.txt", SecurityInformation::DACL)?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn apply_to_path<Q>(&self, path: Q, components: SecurityInformation) -> Result<(), WinError>
+    where
+        Q: AsRef<Path>,
+    {
+        self.set_named(path.as_ref().as_os_str(), SE_FILE_OBJECT, components)
+    }
+
+    /// Writes the selected components of this security descriptor to a named object.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - name of the object. This could be many things (path to the file or directory, to network share, name of the printer, registry key, ...)
+    /// * `object_type` - a type of the object
+    /// * `components` - The components to write (e.g., `SecurityInformation::DACL`).
+    ///
+    /// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/api/aclapi/nf-aclapi-setnamedsecurityinfow)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object cannot be written.
+    pub fn apply_to_handle_name<S>(
+        &self,
+        handle: S,
+        object_type: SE_OBJECT_TYPE,
+        components: SecurityInformation,
+    ) -> Result<(), WinError>
+    where
+        S: AsRef<str>,
+    {
+        self.set_named(OsStr::new(handle.as_ref()), object_type, components)
+    }
+
+    /// Writes the selected components of this security descriptor to a Windows service.
+    ///
+    /// The service is opened through the service control manager with the access the components
+    /// require: `WRITE_DAC` for the DACL, `WRITE_OWNER` for the owner and group and
+    /// `ACCESS_SYSTEM_SECURITY` for the SACL. By default, only Administrators and `SYSTEM` have
+    /// that access.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the service (not its display name), optionally qualified with
+    ///   a remote machine (`\\SERVER\Spooler`).
+    /// * `components` - The components to write (e.g., `SecurityInformation::DACL`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `ERROR_SERVICE_DOES_NOT_EXIST` if there is no such service, or an error if the
+    /// service cannot be written.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sd::{SecurityDescriptor, SecurityInformation};
+    ///
+    /// // only SYSTEM and Administrators may control the service, Authenticated Users may query it
+    /// let sd: SecurityDescriptor = "D:(A;;CCLCSWRPWPDTLOCRRC;;;SY)(A;;CCDCLCSWRPWPDTLOCRSDRCWDWO;;;BA)(A;;CCLCSWLOCRRC;;;AU)".parse()?;
+    /// sd.apply_to_service("MyService", SecurityInformation::DACL)?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn apply_to_service<S>(&self, name: S, components: SecurityInformation) -> Result<(), WinError>
+    where
+        S: AsRef<str>,
+    {
+        self.set_named(OsStr::new(name.as_ref()), SE_SERVICE, components)
+    }
+
+    /// Writes the selected components of this security descriptor to a window station.
+    ///
+    /// The window station is opened with the access the components require (e.g., `WRITE_DAC`
+    /// for the DACL).
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the window station (e.g., `WinSta0`).
+    /// * `components` - The components to write (e.g., `SecurityInformation::DACL`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the window station cannot be opened or written.
+    pub fn apply_to_window_station<S>(&self, name: S, components: SecurityInformation) -> Result<(), WinError>
+    where
+        S: AsRef<str>,
+    {
+        let station = WindowObject::open_window_station(name.as_ref(), window::write_access(components))?;
+        self.apply_to_raw_handle(&station, SE_WINDOW_OBJECT, components)
+    }
+
+    /// Writes the selected components of this security descriptor to a desktop of the window
+    /// station of the calling process.
+    ///
+    /// The desktop is opened with the access the components require (e.g., `WRITE_DAC` for the DACL).
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the desktop (e.g., `Default`).
+    /// * `components` - The components to write (e.g., `SecurityInformation::DACL`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the desktop cannot be opened or written.
+    pub fn apply_to_desktop<S>(&self, name: S, components: SecurityInformation) -> Result<(), WinError>
+    where
+        S: AsRef<str>,
+    {
+        let desktop = WindowObject::open_desktop(name.as_ref(), window::write_access(components))?;
+        self.apply_to_raw_handle(&desktop, SE_WINDOW_OBJECT, components)
+    }
+
+    /// Writes the selected components of this security descriptor to an open handle using `SetSecurityInfo`.
+    ///
+    /// This is the only way to secure objects without a name, such as anonymous kernel objects.
+    /// The handle must have been opened with `WRITE_DAC` (DACL), `WRITE_OWNER` (owner, group)
+    /// or `ACCESS_SYSTEM_SECURITY` (SACL) access, depending on `components`.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - The open object.
+    /// * `object_type` - a type of the object (e.g., `SE_FILE_OBJECT` or `SE_KERNEL_OBJECT`)
+    /// * `components` - The components to write (e.g., `SecurityInformation::DACL`).
+    ///
+    /// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/api/aclapi/nf-aclapi-setsecurityinfo)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object cannot be written.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::{fs::OpenOptions, os::windows::fs::OpenOptionsExt};
+    ///
+    /// use win_acl_rs::{
+    ///     SE_FILE_OBJECT,
+    ///     mask::AccessMask,
+    ///     sd::{SecurityDescriptor, SecurityInformation},
+    /// };
+    ///
+    /// let file = OpenOptions::new()
+    ///     .access_mode((AccessMask::READ_CONTROL | AccessMask::WRITE_DAC).0)
+    ///     .open("C:\\path\\to\\file.txt")
+    ///     .unwrap();
+    /// let sd = SecurityDescriptor::from_path("C:\\path\\to\\template.txt")?;
+    /// sd.apply_to_raw_handle(&file, SE_FILE_OBJECT, SecurityInformation::DACL)?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn apply_to_raw_handle<H>(
+        &self,
+        handle: &H,
+        object_type: SE_OBJECT_TYPE,
+        components: SecurityInformation,
+    ) -> Result<(), WinError>
+    where
+        H: AsRawHandle,
+    {
+        let (owner, group, dacl, sacl) = self.pointers_for(components)?;
+
+        unsafe {
+            winapi_call!(SetSecurityInfo(
+                handle.as_raw_handle() as HANDLE,
+                object_type,
+                components.0,
+                owner,
+                group,
+                dacl,
+                sacl
+            ))
+        };
+        Ok(())
+    }
+
+    const NO_ORIGIN: &'static str = "Security descriptor was not read from a named object";
+
+    /// Writes the selected components to a named object using `SetNamedSecurityInfoW`.
+    pub(crate) fn set_named(
+        &self,
+        obj_name: &OsStr,
+        obj_type: SE_OBJECT_TYPE,
+        components: SecurityInformation,
+    ) -> Result<(), WinError> {
+        let wide_name = WideCString::new(&Self::api_name(obj_name, obj_type));
+        let (owner, group, dacl, sacl) = self.pointers_for(components)?;
+
+        unsafe {
+            winapi_call!(SetNamedSecurityInfoW(
+                wide_name.as_ptr(),
+                obj_type,
+                components.0,
+                owner,
+                group,
+                dacl,
+                sacl
+            ))
+        };
+        Ok(())
+    }
+
+    /// Extends `components` with the flags that keep the DACL and SACL protection of this
+    /// descriptor when it is written, so a restored descriptor inherits exactly like the original.
+    fn with_protection(&self, components: SecurityInformation) -> Result<SecurityInformation, WinError> {
+        let control = self.control()?;
+        let mut components = components
+            & !(SecurityInformation::PROTECTED_DACL
+                | SecurityInformation::UNPROTECTED_DACL
+                | SecurityInformation::PROTECTED_SACL
+                | SecurityInformation::UNPROTECTED_SACL);
+        if components.contains(SecurityInformation::DACL) {
+            components |= if control.contains(SdControlFlags::DACL_PROTECTED) {
+                SecurityInformation::PROTECTED_DACL
+            } else {
+                SecurityInformation::UNPROTECTED_DACL
+            };
+        }
+        if components.contains(SecurityInformation::SACL) {
+            components |= if control.contains(SdControlFlags::SACL_PROTECTED) {
+                SecurityInformation::PROTECTED_SACL
+            } else {
+                SecurityInformation::UNPROTECTED_SACL
+            };
+        }
+        Ok(components)
+    }
+
+    /// Returns the owner, group, DACL and SACL pointers, nulling the ones not selected by `components`.
+    ///
+    /// Fails with `ERROR_INVALID_PARAMETER` if a selected component is missing from the descriptor:
+    /// a null DACL pointer would otherwise be written as a NULL DACL, granting everyone full access.
+    fn pointers_for(&self, components: SecurityInformation) -> Result<(PSID, PSID, *const ACL, *const ACL), WinError> {
+        let control = self.control()?;
+        let missing = |component: &str| {
+            WinError::new(
+                ERROR_INVALID_PARAMETER,
+                Some(format!("The security descriptor has no {} to apply", component)),
+            )
+        };
+
+        let owner = if components.contains(SecurityInformation::OWNER) {
+            if self.owner_sid_ptr.is_null() {
+                return Err(missing("owner"));
+            }
+            self.owner_sid_ptr
+        } else {
+            null_mut()
+        };
+        let group = if components.contains(SecurityInformation::GROUP) {
+            if self.group_sid_ptr.is_null() {
+                return Err(missing("group"));
+            }
+            self.group_sid_ptr
+        } else {
+            null_mut()
+        };
+        // a present NULL DACL is written as is, an absent one is refused
+        let dacl = if components.contains(SecurityInformation::DACL) {
+            if !control.contains(SdControlFlags::DACL_PRESENT) {
+                return Err(missing("DACL"));
+            }
+            self.dacl_ptr
+        } else {
+            null_mut()
+        };
+        let sacl = if components.contains(SecurityInformation::SACL)
+            || components.contains(SecurityInformation::LABEL)
+            || components.contains(SecurityInformation::ATTRIBUTE)
+            || components.contains(SecurityInformation::SCOPE)
+        {
+            if !control.contains(SdControlFlags::SACL_PRESENT) {
+                return Err(missing("SACL"));
+            }
+            self.sacl_ptr
+        } else {
+            null_mut()
+        };
+        Ok((owner, group, dacl, sacl))
+    }
+
+    /// Returns the name to pass to the named security functions, switching long file paths
+    /// to the extended-length form.
+    fn api_name(obj_name: &OsStr, obj_type: SE_OBJECT_TYPE) -> Cow<'_, OsStr> {
+        if obj_type == SE_FILE_OBJECT {
+            fs::api_path(obj_name)
+        } else {
+            Cow::Borrowed(obj_name)
+        }
+    }
+
+    pub(crate) fn create_sd(
+        obj_name: &OsStr,
+        obj_type: SE_OBJECT_TYPE,
+        flags: OBJECT_SECURITY_INFORMATION,
+    ) -> Result<Self, WinError> {
+        let wide_name = WideCString::new(&Self::api_name(obj_name, obj_type));
+        let mut sd_ptr: PSECURITY_DESCRIPTOR = null_mut();
+        let mut dacl_ptr: *mut ACL = null_mut();
+        let mut sacl_ptr: *mut ACL = null_mut();
+        let mut owner_sid_ptr: PSID = null_mut();
+        let mut group_sid_ptr: PSID = null_mut();
+
+        unsafe {
+            winapi_call!(GetNamedSecurityInfoW(
+                wide_name.as_ptr(),
+                obj_type,
+                flags,
+                &mut owner_sid_ptr,
+                &mut group_sid_ptr,
+                &mut dacl_ptr,
+                &mut sacl_ptr,
+                &mut sd_ptr,
+            ))
+        };
+
+        Ok(Self {
+            sd_ptr,
+            dacl_ptr,
+            sacl_ptr,
+            owner_sid_ptr,
+            group_sid_ptr,
+            origin: Some(ObjectOrigin {
+                name: obj_name.to_owned(),
+                object_type: obj_type,
+                components: SecurityInformation(flags),
+            }),
+            _priv: PhantomData,
+        })
+    }
+
+    pub(crate) fn create_sd_from_handle(
+        handle: HANDLE,
+        obj_type: SE_OBJECT_TYPE,
+        flags: OBJECT_SECURITY_INFORMATION,
+    ) -> Result<Self, WinError> {
+        let mut sd_ptr: PSECURITY_DESCRIPTOR = null_mut();
+        let mut dacl_ptr: *mut ACL = null_mut();
+        let mut sacl_ptr: *mut ACL = null_mut();
+        let mut owner_sid_ptr: PSID = null_mut();
+        let mut group_sid_ptr: PSID = null_mut();
+
+        unsafe {
+            winapi_call!(GetSecurityInfo(
+                handle,
+                obj_type,
+                flags,
+                &mut owner_sid_ptr,
+                &mut group_sid_ptr,
+                &mut dacl_ptr,
+                &mut sacl_ptr,
+                &mut sd_ptr,
+            ))
+        };
+
+        Ok(Self {
+            sd_ptr,
+            dacl_ptr,
+            sacl_ptr,
+            owner_sid_ptr,
+            group_sid_ptr,
+            origin: None,
+            _priv: PhantomData,
+        })
+    }
+}
+
+/// Reads the security descriptors of multiple file objects in parallel.
+///
+/// The paths are distributed over up to `concurrency` worker threads. Each result is returned
+/// paired with its input, in the same order as the input.
+///
+/// # Arguments
+///
+/// * `paths` - The file objects to read.
+/// * `components` - The components to read for each object (e.g., `SecurityInformation::standard()`).
+/// * `concurrency` - The maximum number of worker threads (`0` is treated as `1`).
+///
+/// # Returns
+///
+/// A vector of `(path, result)` pairs in input order. A failure to read one object does not
+/// affect the others.
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::sd::{SecurityInformation, get_many};
+///
+/// let paths = ["C:\\Windows", "C:\\Users"];
+/// for (path, sd) in get_many(paths, SecurityInformation::DACL, 4) {
+///     println!("{}: {:?}", path, sd.map(|sd| sd.as_sd_string()));
+/// }
+/// ```
+pub fn get_many<I, Q>(
+    paths: I,
+    components: SecurityInformation,
+    concurrency: usize,
+) -> Vec<(Q, Result<SecurityDescriptor, WinError>)>
+where
+    I: IntoIterator<Item = Q>,
+    Q: AsRef<Path> + Sync,
+{
+    let paths: Vec<Q> = paths.into_iter().collect();
+    let workers = concurrency.clamp(1, paths.len().max(1));
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<Option<Result<SecurityDescriptor, WinError>>> = (0..paths.len()).map(|_| None).collect();
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(path) = paths.get(index) else {
+                            break;
+                        };
+                        let sd = SecurityDescriptor::create_sd(path.as_ref().as_os_str(), SE_FILE_OBJECT, components.0);
+                        done.push((index, sd));
+                    }
+                    done
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            let done = handle.join().unwrap_or_else(|e| std::panic::resume_unwind(e));
+            for (index, sd) in done {
+                results[index] = Some(sd);
+            }
+        }
+    });
+
+    paths
+        .into_iter()
+        .zip(results)
+        .map(|(path, sd)| (path, sd.expect("every path is processed by a worker")))
+        .collect()
+}
+
+/// Applies security descriptors to multiple file objects as a unit.
+///
+/// Before changing an object, its current security descriptor is saved. If any change fails,
+/// the objects changed so far are restored to their saved descriptors (in reverse order) and the
+/// error is returned, so a batch is applied either completely or not at all.
+///
+/// # Arguments
+///
+/// * `changes` - `(path, descriptor)` pairs, applied in order.
+/// * `components` - The components to write (and save beforehand).
+///
+/// # Returns
+///
+/// The applied set, which can still be rolled back with [`AppliedSet::rollback`].
+///
+/// # Errors
+///
+/// Returns the error of the first failed change. If restoring some objects failed as well,
+/// the message lists them.
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::sd::{SecurityDescriptor, SecurityInformation, apply_set};
+///
+/// let sd: SecurityDescriptor = "D:P(A;OICI;FA;;;BA)(A;OICI;FR;;;AU)".parse()?;
+/// let applied = apply_set(
+///     [("C:\\data\\a", &sd), ("C:\\data\\b", &sd)],
+///     SecurityInformation::DACL,
+/// )?;
+/// // ... verify the rollout ...
+/// applied.rollback()?;
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+pub fn apply_set<'a, I, Q>(changes: I, components: SecurityInformation) -> Result<AppliedSet, WinError>
+where
+    I: IntoIterator<Item = (Q, &'a SecurityDescriptor)>,
+    Q: AsRef<Path>,
+{
+    let mut applied = AppliedSet {
+        saved: Vec::new(),
+        components,
+    };
+    for (path, sd) in changes {
+        applied.apply(path.as_ref().as_os_str(), SE_FILE_OBJECT, sd)?;
+    }
+    Ok(applied)
+}
+
+/// Applies security descriptors to multiple named objects of any type as a unit.
+///
+/// Like [`apply_set`], but each object has its own type, so files, registry keys, services
+/// and other objects can be configured in one transaction. If any change fails, the objects
+/// changed so far are restored to their saved descriptors (in reverse order) and the error
+/// is returned.
+///
+/// # Arguments
+///
+/// * `changes` - `(name, object type, descriptor)` triples, applied in order.
+/// * `components` - The components to write (and save beforehand).
+///
+/// # Returns
+///
+/// The applied set, which can still be rolled back with [`AppliedSet::rollback`].
+///
+/// # Errors
+///
+/// Returns the error of the first failed change. If restoring some objects failed as well,
+/// the message lists them.
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::{
+///     SE_FILE_OBJECT, SE_REGISTRY_KEY,
+///     sd::{SecurityDescriptor, SecurityInformation, apply_all},
+/// };
+///
+/// let sd: SecurityDescriptor = "D:P(A;OICI;GA;;;BA)(A;OICI;GR;;;AU)".parse()?;
+/// apply_all(
+///     &[
+///         ("C:\\ProgramData\\MyApp", SE_FILE_OBJECT, sd.try_clone()?),
+///         ("MACHINE\\SOFTWARE\\MyApp", SE_REGISTRY_KEY, sd),
+///     ],
+///     SecurityInformation::DACL,
+/// )?;
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+pub fn apply_all<S>(
+    changes: &[(S, SE_OBJECT_TYPE, SecurityDescriptor)],
+    components: SecurityInformation,
+) -> Result<AppliedSet, WinError>
+where
+    S: AsRef<str>,
+{
+    let mut applied = AppliedSet {
+        saved: Vec::new(),
+        components,
+    };
+    for (name, object_type, sd) in changes {
+        applied.apply(OsStr::new(name.as_ref()), *object_type, sd)?;
+    }
+    Ok(applied)
+}
+
+/// A set of changes applied by [`apply_set`] or [`apply_all`], with the saved prior security descriptors.
+///
+/// Dropping the set keeps the changes.
+#[derive(Debug)]
+#[must_use]
+pub struct AppliedSet {
+    saved: Vec<(OsString, SE_OBJECT_TYPE, SecurityDescriptor)>,
+    components: SecurityInformation,
+}
+
+impl AppliedSet {
+    /// Returns the paths (or names) of the changed objects, in the order they were changed.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.saved.iter().map(|(name, _, _)| Path::new(name))
+    }
+
+    /// Returns the number of changed objects.
+    pub fn len(&self) -> usize {
+        self.saved.len()
+    }
+
+    /// Returns `true` if no object was changed.
+    pub fn is_empty(&self) -> bool {
+        self.saved.is_empty()
+    }
+
+    /// Restores all changed objects to their saved security descriptors, in reverse order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error listing the objects that could not be restored. The other objects
+    /// are restored regardless.
+    pub fn rollback(mut self) -> Result<(), WinError> {
+        match self.restore() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Saves the descriptor of an object and applies `sd` to it. On failure, restores the
+    /// objects changed so far and returns the error, combined with the rollback failures.
+    fn apply(&mut self, name: &OsStr, object_type: SE_OBJECT_TYPE, sd: &SecurityDescriptor) -> Result<(), WinError> {
+        let saved = SecurityDescriptor::create_sd(name, object_type, self.components.0)
+            .and_then(|prior| sd.set_named(name, object_type, self.components).map(|_| prior));
+        match saved {
+            Ok(prior) => {
+                self.saved.push((name.to_owned(), object_type, prior));
+                Ok(())
+            }
+            Err(error) => {
+                let Some(rollback_error) = self.restore() else {
+                    return Err(error);
+                };
+                let message = format!("{}; {}", error, rollback_error);
+                Err(WinError::new(error.code, Some(message)))
+            }
+        }
+    }
+
+    /// Restores the saved descriptors. Returns an error listing the failures, carrying the
+    /// code of the first one.
+    fn restore(&mut self) -> Option<WinError> {
+        let mut code = None;
+        let mut failures = Vec::new();
+        while let Some((name, object_type, prior)) = self.saved.pop() {
+            let restored = prior
+                .with_protection(self.components)
+                .and_then(|components| prior.set_named(&name, object_type, components));
+            if let Err(error) = restored {
+                code.get_or_insert(error.code);
+                failures.push(format!("{} ({})", name.display(), error));
+            }
+        }
+        code.map(|code| WinError::new(code, Some(format!("Rollback failed for: {}", failures.join(", ")))))
+    }
+}
+
+/// Restores the security of an object when dropped, unless committed.
+///
+/// The guard saves the selected components of the current security descriptor of an object when
+/// created. Changes made while it is alive (through [`SecurityGuard::apply`] or any other means)
+/// are reverted when the guard is dropped, e.g., when a script returns early with an error or
+/// panics, and kept when [`SecurityGuard::commit`] is called.
+///
+/// Errors while restoring on drop are ignored; use [`SecurityGuard::restore`] to handle them.
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::sd::{SecurityDescriptor, SecurityGuard, SecurityInformation};
+///
+/// let guard = SecurityGuard::new("C:\\data\\reports", SecurityInformation::DACL)?;
+/// guard.apply(&"D:P(A;OICI;FA;;;BA)".parse::<SecurityDescriptor>()?)?;
+/// // ... verify the change; returning early with an error restores the original DACL ...
+/// guard.commit();
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+#[derive(Debug)]
+#[must_use]
+pub struct SecurityGuard {
+    name: OsString,
+    object_type: SE_OBJECT_TYPE,
+    components: SecurityInformation,
+    saved: Option<SecurityDescriptor>,
+}
+
+impl SecurityGuard {
+    /// Saves the security of a file or directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path to the file or directory.
+    /// * `components` - The components to save and restore (e.g., `SecurityInformation::DACL`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the security descriptor cannot be read.
+    pub fn new<Q>(path: Q, components: SecurityInformation) -> Result<Self, WinError>
+    where
+        Q: AsRef<Path>,
+    {
+        Self::save(path.as_ref().as_os_str(), SE_FILE_OBJECT, components)
+    }
+
+    /// Saves the security of a named object.
+    ///
+    /// # Arguments
+    ///
+    /// * `handle` - name of the object. This could be many things (path to the file or directory, to network share, name of the printer, registry key, ...)
+    /// * `object_type` - a type of the object
+    /// * `components` - The components to save and restore (e.g., `SecurityInformation::DACL`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the security descriptor cannot be read.
+    pub fn for_handle_name<S>(
+        handle: S,
+        object_type: SE_OBJECT_TYPE,
+        components: SecurityInformation,
+    ) -> Result<Self, WinError>
+    where
+        S: AsRef<str>,
+    {
+        Self::save(OsStr::new(handle.as_ref()), object_type, components)
+    }
+
+    fn save(name: &OsStr, object_type: SE_OBJECT_TYPE, components: SecurityInformation) -> Result<Self, WinError> {
+        let saved = SecurityDescriptor::create_sd(name, object_type, components.0)?;
+        Ok(Self {
+            name: name.to_owned(),
+            object_type,
+            components,
+            saved: Some(saved),
+        })
+    }
+
+    /// Returns the saved security descriptor.
+    pub fn saved(&self) -> &SecurityDescriptor {
+        self.saved
+            .as_ref()
+            .expect("the descriptor is only taken when the guard is consumed")
+    }
+
+    /// Writes the guarded components of a security descriptor to the object.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object cannot be written.
+    pub fn apply<P>(&self, sd: &SecurityDescriptorImpl<P>) -> Result<(), WinError>
+    where
+        P: PrivilegeLevel,
+    {
+        sd.set_named(&self.name, self.object_type, self.components)
+    }
+
+    /// Keeps the changes: the saved security descriptor is discarded.
+    pub fn commit(mut self) {
+        self.saved = None;
+    }
+
+    /// Restores the saved security descriptor now.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the object cannot be written.
+    pub fn restore(mut self) -> Result<(), WinError> {
+        self.restore_saved()
+    }
+
+    fn restore_saved(&mut self) -> Result<(), WinError> {
+        let Some(saved) = self.saved.take() else {
+            return Ok(());
+        };
+        let components = saved.with_protection(self.components)?;
+        saved.set_named(&self.name, self.object_type, components)
+    }
+}
+
+impl Drop for SecurityGuard {
+    fn drop(&mut self) {
+        let _ = self.restore_saved();
+    }
+}
+
+/// The differences between two security descriptors, computed by [`diff`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SdDiff {
+    /// The owner before and after, if it changed.
+    pub owner: Option<(Option<Sid>, Option<Sid>)>,
+    /// The primary group before and after, if it changed.
+    pub group: Option<(Option<Sid>, Option<Sid>)>,
+    /// Whether the descriptors have a DACL before and after, if exactly one of them has none.
+    ///
+    /// A missing (NULL) DACL grants everyone full access, while an empty DACL grants nobody
+    /// any access, even though neither has entries.
+    pub dacl_present: Option<(bool, bool)>,
+    /// Whether the DACL is protected from inheritance (`SE_DACL_PROTECTED`) before and after,
+    /// if it changed.
+    pub dacl_protected: Option<(bool, bool)>,
+    /// The DACL entries present only in the second descriptor.
+    pub added: Vec<DiffEntry>,
+    /// The DACL entries present only in the first descriptor.
+    pub removed: Vec<DiffEntry>,
+    /// The DACL entries present in both descriptors with different access masks.
+    pub modified: Vec<MaskChange>,
+}
+
+impl SdDiff {
+    /// Returns `true` if the descriptors do not differ.
+    pub fn is_empty(&self) -> bool {
+        self.owner.is_none()
+            && self.group.is_none()
+            && self.dacl_present.is_none()
+            && self.dacl_protected.is_none()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+            && self.modified.is_empty()
+    }
+}
+
+/// A DACL entry of a [`SdDiff`] or an ACL entry of an [`AclDiff`](crate::acl::AclDiff): the combined
+/// access mask of the ACEs that differ in nothing but their mask.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DiffEntry {
+    /// The trustee of the entry.
+    pub trustee: Sid,
+    /// The type of the entry.
+    pub ace_type: AceType,
+    /// The raw flags of the ACE header (inheritance and audit flags).
+    pub flags: u8,
+    /// The schema element an object ACE applies to, see [`Ace::object_type`].
+    pub object_type: Option<Guid>,
+    /// The object class inheriting an object ACE, see [`Ace::inherited_object_type`].
+    pub inherited_object_type: Option<Guid>,
+    /// The application data (e.g., the condition) of a callback ACE, see [`Ace::application_data`].
+    pub application_data: Option<Vec<u8>>,
+    /// The access mask of the entry.
+    pub mask: u32,
+}
+
+/// An entry of a [`SdDiff`] or an [`AclDiff`](crate::acl::AclDiff) whose access mask changed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MaskChange {
+    /// The trustee of the entry.
+    pub trustee: Sid,
+    /// The type of the entry.
+    pub ace_type: AceType,
+    /// The raw flags of the ACE header (inheritance and audit flags).
+    pub flags: u8,
+    /// The schema element an object ACE applies to, see [`Ace::object_type`].
+    pub object_type: Option<Guid>,
+    /// The object class inheriting an object ACE, see [`Ace::inherited_object_type`].
+    pub inherited_object_type: Option<Guid>,
+    /// The application data (e.g., the condition) of a callback ACE, see [`Ace::application_data`].
+    pub application_data: Option<Vec<u8>>,
+    /// The access mask in the first descriptor.
+    pub before: u32,
+    /// The access mask in the second descriptor.
+    pub after: u32,
+}
+
+impl MaskChange {
+    /// Returns the access rights present only after the change.
+    pub fn added_rights(&self) -> u32 {
+        self.after & !self.before
+    }
+
+    /// Returns the access rights present only before the change.
+    pub fn removed_rights(&self) -> u32 {
+        self.before & !self.after
+    }
+}
+
+/// Compares two security descriptors.
+///
+/// The owners, the primary groups, the presence of the DACLs, their protection from inheritance
+/// and the DACL entries are compared. DACL entries are matched by
+/// everything but their access mask: trustee, ACE type, ACE flags, object types and application
+/// data. Multiple ACEs with the same key are combined, so the order of the ACEs does not matter.
+/// Entries are reported in the order of their keys.
+///
+/// # Arguments
+///
+/// * `a` - The first (e.g., baseline) security descriptor.
+/// * `b` - The second (e.g., current) security descriptor.
+///
+/// # Errors
+///
+/// Returns an error if the control flags, an ACL or one of its ACEs cannot be read.
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::sd::{SecurityDescriptor, diff};
+///
+/// let baseline: SecurityDescriptor = "O:BAD:(A;;FA;;;SY)(A;;FR;;;BU)".parse()?;
+/// let current = SecurityDescriptor::from_path("C:\\data\\reports")?;
+/// for change in diff(&baseline, &current)?.modified {
+///     println!("{}: +0x{:X} -0x{:X}", change.trustee, change.added_rights(), change.removed_rights());
+/// }
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+pub fn diff<P, Q>(a: &SecurityDescriptorImpl<P>, b: &SecurityDescriptorImpl<Q>) -> Result<SdDiff, WinError>
+where
+    P: PrivilegeLevel,
+    Q: PrivilegeLevel,
+{
+    let owned = |sid: Option<SidRef<'_>>| sid.map(|sid| Sid::from_bytes(sid.as_bytes())).transpose();
+    let changed = |before: Option<Sid>, after: Option<Sid>| (before != after).then_some((before, after));
+    let flag_changed = |before: bool, after: bool| (before != after).then_some((before, after));
+    let protected = |control: SdControlFlags| control.contains(SdControlFlags::DACL_PROTECTED);
+
+    let mut diff = SdDiff {
+        owner: changed(owned(a.owner_sid())?, owned(b.owner_sid())?),
+        group: changed(owned(a.group_sid())?, owned(b.group_sid())?),
+        dacl_present: flag_changed(a.dacl().is_some(), b.dacl().is_some()),
+        dacl_protected: flag_changed(protected(a.control()?), protected(b.control()?)),
+        ..Default::default()
+    };
+
+    let dacl = acl::diff_optional(a.dacl().as_deref(), b.dacl().as_deref())?;
+    diff.added = dacl.added;
+    diff.removed = dacl.removed;
+    diff.modified = dacl.modified;
+    Ok(diff)
+}
+
+/// Returns the bytes of an ACE that determine its meaning: the type, the flags, the mask and the
+/// SID of the standard ACE types, all bytes (including padding) of the other ones.
+fn canonical_ace(ace: &Ace<'_>) -> Result<Vec<u8>, WinError> {
+    if !matches!(
+        ace.ace_type(),
+        AceType::AccessAllowed | AceType::AccessDenied | AceType::SystemAudit
+    ) {
+        return Ok(ace.as_bytes().to_vec());
+    }
+    let mut bytes = vec![ace.as_bytes()[0], ace.header_flags()];
+    bytes.extend_from_slice(&ace.mask().to_le_bytes());
+    bytes.extend_from_slice(ace.sid()?.as_bytes());
+    Ok(bytes)
+}
+
+/// The 64-bit FNV-1a hash, whose output is fixed by its specification (unlike `DefaultHasher`).
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    const OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01B3;
+
+    fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(Self::PRIME);
+        }
+    }
+
+    /// Writes a length, offset by one so it differs from [`Self::write_absent`].
+    fn write_len(&mut self, len: usize) {
+        self.write(&(len as u32 + 1).to_le_bytes());
+    }
+
+    /// Writes the marker of an absent component.
+    fn write_absent(&mut self) {
+        self.write(&0u32.to_le_bytes());
+    }
+
+    /// Writes a length-prefixed chunk, so adjacent chunks cannot be confused.
+    fn write_chunk(&mut self, bytes: &[u8]) {
+        self.write_len(bytes.len());
+        self.write(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// A modification of a security descriptor, planned with [`SecurityDescriptorImpl::plan`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Modification {
+    /// Grants access rights to a trustee (adds an access allowed ACE to the DACL).
+    Grant {
+        /// The trustee the rights are granted to.
+        trustee: Sid,
+        /// The granted access rights.
+        mask: u32,
+    },
+    /// Denies access rights to a trustee (adds an access denied ACE to the DACL).
+    Deny {
+        /// The trustee the rights are denied to.
+        trustee: Sid,
+        /// The denied access rights.
+        mask: u32,
+    },
+    /// Changes the owner.
+    Owner(Sid),
+    /// Changes the primary group.
+    Group(Sid),
+}
+
+impl Modification {
+    /// Grants access rights to a trustee, see [`Modification::Grant`].
+    ///
+    /// # Arguments
+    ///
+    /// * `trustee` - The trustee the rights are granted to.
+    /// * `mask` - The granted access rights, e.g., a `FileAccess` or `RegistryAccess` mask.
+    pub fn grant<M>(trustee: Sid, mask: M) -> Self
+    where
+        M: IntoAccessMask,
+    {
+        Modification::Grant {
+            trustee,
+            mask: mask.as_u32(),
+        }
+    }
+
+    /// Denies access rights to a trustee, see [`Modification::Deny`].
+    ///
+    /// # Arguments
+    ///
+    /// * `trustee` - The trustee the rights are denied to.
+    /// * `mask` - The denied access rights, e.g., a `FileAccess` or `RegistryAccess` mask.
+    pub fn deny<M>(trustee: Sid, mask: M) -> Self
+    where
+        M: IntoAccessMask,
+    {
+        Modification::Deny {
+            trustee,
+            mask: mask.as_u32(),
+        }
+    }
+}
+
+/// What happens to the inherited ACEs of an ACL when inheritance is disabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InheritedAces {
+    /// The inherited ACEs are converted to explicit ACEs, so the effective access does not change.
+    Copy,
+    /// The inherited ACEs are removed, only the explicit ACEs are kept.
+    Remove,
+}
+
+/// Disables DACL inheritance of a file or directory (sets `SE_DACL_PROTECTED`).
+///
+/// Like the *Disable inheritance* button in Explorer, the inherited ACEs are either converted
+/// to explicit ones or removed.
+///
+/// # Arguments
+///
+/// * `path` - Path to the file or directory.
+/// * `inherited` - What happens to the inherited ACEs.
+///
+/// # Errors
+///
+/// Returns an error if the DACL cannot be read or written.
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::sd::{InheritedAces, disable_inheritance, enable_inheritance};
+///
+/// disable_inheritance("C:\\data\\private", InheritedAces::Copy)?;
+/// // ... and back
+/// enable_inheritance("C:\\data\\private")?;
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+pub fn disable_inheritance<Q>(path: Q, inherited: InheritedAces) -> Result<(), WinError>
+where
+    Q: AsRef<Path>,
+{
+    set_inheritance(path.as_ref(), SecurityInformation::DACL, Some(inherited))
+}
+
+/// Enables DACL inheritance of a file or directory (clears `SE_DACL_PROTECTED`).
+///
+/// The explicit ACEs are kept and the inheritable ACEs of the parent are propagated to the object.
+///
+/// # Arguments
+///
+/// * `path` - Path to the file or directory.
+///
+/// # Errors
+///
+/// Returns an error if the DACL cannot be read or written.
+pub fn enable_inheritance<Q>(path: Q) -> Result<(), WinError>
+where
+    Q: AsRef<Path>,
+{
+    set_inheritance(path.as_ref(), SecurityInformation::DACL, None)
+}
+
+/// Protects (`disable` is `Some`) or unprotects the DACL or the SACL (`acl`) of a file object.
+pub(crate) fn set_inheritance(
+    path: &Path,
+    acl: SecurityInformation,
+    disable: Option<InheritedAces>,
+) -> Result<(), WinError> {
+    let sacl = acl == SecurityInformation::SACL;
+    let sd = SecurityDescriptor::create_sd(path.as_os_str(), SE_FILE_OBJECT, acl.0)?;
+    let current = if sacl { sd.sacl_unchecked() } else { sd.dacl() };
+    let explicit = current
+        .map(|current| current.explicit_copy(disable == Some(InheritedAces::Copy)))
+        .transpose()?;
+
+    let (rebuilt, protection) = if sacl {
+        let rebuilt = SecurityDescriptor::from_components(None, None, None, explicit.as_ref())?;
+        let protection = match disable {
+            Some(_) => SecurityInformation::PROTECTED_SACL,
+            None => SecurityInformation::UNPROTECTED_SACL,
+        };
+        (rebuilt, protection)
+    } else {
+        let rebuilt = SecurityDescriptor::from_components(None, None, explicit.as_ref(), None)?;
+        let protection = match disable {
+            Some(_) => SecurityInformation::PROTECTED_DACL,
+            None => SecurityInformation::UNPROTECTED_DACL,
+        };
+        (rebuilt, protection)
+    };
+    rebuilt.set_named(path.as_os_str(), SE_FILE_OBJECT, acl | protection)
+}
+
+/// A builder composing a security descriptor from scratch.
+///
+/// Components that are not set are left out of the security descriptor and are not written
+/// when the result is applied to an object.
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::{
+///     acl::Acl,
+///     mask::AccessMask,
+///     sd::SecurityDescriptorBuilder,
+///     sid::Sid,
+///     wellknown::{WinBuiltinAdministratorsSid, WinLocalSystemSid},
+/// };
+///
+/// let admins = Sid::from_well_known_sid(WinBuiltinAdministratorsSid)?;
+/// let mut dacl = Acl::new()?;
+/// dacl.allow(AccessMask::full(), &admins)?;
+/// dacl.allow(AccessMask::full(), &Sid::from_well_known_sid(WinLocalSystemSid)?)?;
+///
+/// let builder = SecurityDescriptorBuilder::new()
+///     .owner(admins)
+///     .dacl(dacl)
+///     .protected_dacl(true);
+/// println!("{}", builder.to_sd_string()?);
+/// builder.apply_to_path("C:\\data")?;
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+#[derive(Debug, Default)]
+pub struct SecurityDescriptorBuilder {
+    owner: Option<Sid>,
+    group: Option<Sid>,
+    dacl: Option<Acl>,
+    sacl: Option<Acl>,
+    protected_dacl: Option<bool>,
+    protected_sacl: Option<bool>,
+}
+
+impl SecurityDescriptorBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the owner.
+    pub fn owner(mut self, sid: Sid) -> Self {
+        self.owner = Some(sid);
+        self
+    }
+
+    /// Sets the primary group.
+    pub fn group(mut self, sid: Sid) -> Self {
+        self.group = Some(sid);
+        self
+    }
+
+    /// Sets the DACL.
+    pub fn dacl(mut self, acl: Acl) -> Self {
+        self.dacl = Some(acl);
+        self
+    }
+
+    /// Sets the SACL. Applying a SACL requires the *SeSecurityPrivilege* privilege.
+    pub fn sacl(mut self, acl: Acl) -> Self {
+        self.sacl = Some(acl);
+        self
+    }
+
+    /// Protects the DACL from inheriting ACEs from the parent (`true`) or lets it inherit (`false`).
+    ///
+    /// When applied to an object, the protection is written along with the DACL.
+    pub fn protected_dacl(mut self, protected: bool) -> Self {
+        self.protected_dacl = Some(protected);
+        self
+    }
+
+    /// Protects the SACL from inheriting ACEs from the parent (`true`) or lets it inherit (`false`).
+    ///
+    /// When applied to an object, the protection is written along with the SACL.
+    pub fn protected_sacl(mut self, protected: bool) -> Self {
+        self.protected_sacl = Some(protected);
+        self
+    }
+
+    /// Builds the security descriptor.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the security descriptor cannot be assembled.
+    pub fn build(&self) -> Result<SecurityDescriptor, WinError> {
+        let mut control = SdControlFlags::empty();
+        if self.protected_dacl == Some(true) {
+            control |= SdControlFlags::DACL_PROTECTED;
+        }
+        if self.protected_sacl == Some(true) {
+            control |= SdControlFlags::SACL_PROTECTED;
+        }
+        SecurityDescriptor::from_components_with_control(
+            self.owner.as_ref().map(|sid| sid.as_sid_ref()),
+            self.group.as_ref().map(|sid| sid.as_sid_ref()),
+            self.dacl.as_ref(),
+            self.sacl.as_ref(),
+            control,
+        )
+    }
+
+    /// Builds the security descriptor and converts it to the string format (SDDL).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the security descriptor cannot be assembled or converted.
+    pub fn to_sd_string(&self) -> Result<String, WinError> {
+        self.build()?.as_sd_string()
+    }
+
+    /// Returns the components set in the builder, including the protection flags.
+    pub fn components(&self) -> SecurityInformation {
+        let mut components = SecurityInformation::empty();
+        if self.owner.is_some() {
+            components |= SecurityInformation::OWNER;
+        }
+        if self.group.is_some() {
+            components |= SecurityInformation::GROUP;
+        }
+        if self.dacl.is_some() {
+            components |= SecurityInformation::DACL;
+            match self.protected_dacl {
+                Some(true) => components |= SecurityInformation::PROTECTED_DACL,
+                Some(false) => components |= SecurityInformation::UNPROTECTED_DACL,
+                None => {}
+            }
+        }
+        if self.sacl.is_some() {
+            components |= SecurityInformation::SACL;
+            match self.protected_sacl {
+                Some(true) => components |= SecurityInformation::PROTECTED_SACL,
+                Some(false) => components |= SecurityInformation::UNPROTECTED_SACL,
+                None => {}
+            }
+        }
+        components
+    }
+
+    /// Builds the security descriptor and writes the components set in the builder
+    /// (see [`Self::components`]) to a file or directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the security descriptor cannot be assembled or the object cannot be written.
+    pub fn apply_to_path<Q>(&self, path: Q) -> Result<(), WinError>
+    where
+        Q: AsRef<Path>,
+    {
+        self.build()?.apply_to_path(path, self.components())
+    }
+
+    /// Builds the security descriptor and writes the components set in the builder
+    /// (see [`Self::components`]) to a named object.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the security descriptor cannot be assembled or the object cannot be written.
+    pub fn apply_to_handle_name<S>(&self, handle: S, object_type: SE_OBJECT_TYPE) -> Result<(), WinError>
+    where
+        S: AsRef<str>,
+    {
+        self.build()?
+            .apply_to_handle_name(handle, object_type, self.components())
     }
 }
 
@@ -0,0 +1,279 @@
+//! Network share security.
+//!
+//! A share has its own security descriptor, checked when the share is accessed over the network
+//! in addition to the security of the shared files (the *Share Permissions* tab in Explorer).
+//! [`Share`] reads and writes it through the named security functions (`SE_LMSHARE`), which
+//! also work with [`SecurityGuard`](crate::sd::SecurityGuard) and [`apply_all`](crate::sd::apply_all),
+//! and through `NetShareGetInfo`/`NetShareSetInfo`, which also report the shared path.
+//!
+//! Reading the share security requires membership in the *Administrators*, *Server Operators*
+//! or *Power Users* group of the server.
+//!
+//! see [MSDN](https://learn.microsoft.com/en-us/windows/win32/api/lmshare/nf-lmshare-netsharegetinfo)
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use win_acl_rs::{
+//!     sd::{SecurityDescriptor, SecurityInformation},
+//!     share::Share,
+//! };
+//!
+//! let share = Share::parse(r"\\SERVER01\data")?;
+//! println!("{} shares {}", share, share.info()?.path);
+//!
+//! // read-only for Everyone, full control for Administrators
+//! let sd: SecurityDescriptor = "D:(A;;0x1200a9;;;WD)(A;;FA;;;BA)".parse()?;
+//! share.apply(&sd, SecurityInformation::DACL)?;
+//! # Ok::<(), win_acl_rs::error::WinError>(())
+//! ```
+
+use std::{
+    ffi::OsStr,
+    fmt::{Display, Formatter},
+    ptr::{null, null_mut},
+    slice::from_raw_parts,
+};
+
+use windows_sys::Win32::{
+    Foundation::ERROR_INVALID_PARAMETER,
+    NetworkManagement::NetManagement::NetApiBufferFree,
+    Security::GetSecurityDescriptorLength,
+    Storage::FileSystem::{NetShareGetInfo, NetShareSetInfo, SHARE_INFO_502},
+};
+
+use crate::{
+    SE_LMSHARE,
+    elevated::PrivilegeLevel,
+    error::WinError,
+    remote::RemoteHost,
+    sd::{SecurityDescriptor, SecurityDescriptorImpl, SecurityInformation},
+    utils::WideCString,
+    winapi_call,
+};
+
+/// A network share, local or on a remote server.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Share {
+    server: Option<String>,
+    name: String,
+}
+
+/// The information of a share reported by `NetShareGetInfo`.
+#[derive(Debug)]
+pub struct ShareInfo {
+    /// The name of the share.
+    pub name: String,
+    /// The local path of the shared directory on the server.
+    pub path: String,
+    /// The comment of the share.
+    pub remark: String,
+    /// The maximum number of concurrent connections, `u32::MAX` for unlimited.
+    pub max_uses: u32,
+    /// The number of current connections.
+    pub current_uses: u32,
+    /// The security descriptor of the share, `None` if the share has none (full access to everyone).
+    pub security: Option<SecurityDescriptor>,
+}
+
+impl Share {
+    /// Creates a share of the local machine.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the share.
+    pub fn local<S>(name: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        Self {
+            server: None,
+            name: name.as_ref().to_owned(),
+        }
+    }
+
+    /// Creates a share of a remote machine.
+    ///
+    /// # Arguments
+    ///
+    /// * `host` - The server hosting the share.
+    /// * `name` - The name of the share.
+    pub fn on<S>(host: &RemoteHost, name: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        Self {
+            server: Some(host.name().to_owned()),
+            name: name.as_ref().to_owned(),
+        }
+    }
+
+    /// Parses a share name: either a bare name (`data`) of a local share or a UNC name
+    /// (`\\SERVER\data`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is empty or has more than two components.
+    pub fn parse<S>(s: S) -> Result<Self, WinError>
+    where
+        S: AsRef<str>,
+    {
+        let s = s.as_ref();
+        let invalid = || WinError::new(ERROR_INVALID_PARAMETER, Some(format!("Invalid share name: {}", s)));
+
+        let share = match s.strip_prefix(r"\\") {
+            Some(unc) => {
+                let (server, name) = unc.split_once('\\').ok_or_else(invalid)?;
+                Self::on(&RemoteHost::new(server)?, name.trim_end_matches('\\'))
+            }
+            None => Self::local(s),
+        };
+        if share.name.is_empty() || share.name.contains('\\') {
+            return Err(invalid());
+        }
+        Ok(share)
+    }
+
+    /// Returns the name of the share.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the server hosting the share, `None` for a local share.
+    pub fn server(&self) -> Option<&str> {
+        self.server.as_deref()
+    }
+
+    /// Returns the name expected by the named security functions for `SE_LMSHARE`:
+    /// the bare name of a local share, the UNC name of a remote one.
+    pub fn object_name(&self) -> String {
+        match &self.server {
+            Some(server) => format!(r"\\{}\{}", server, self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Reads the security descriptor of the share through the named security functions.
+    ///
+    /// The descriptor remembers the share as its origin, so it can be written back with
+    /// [`SecurityDescriptorImpl::reapply`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the share does not exist or its security cannot be read.
+    pub fn security(&self) -> Result<SecurityDescriptor, WinError> {
+        SecurityDescriptor::from_handle(self.object_name(), SE_LMSHARE)
+    }
+
+    /// Writes the selected components of a security descriptor to the share through the named
+    /// security functions.
+    ///
+    /// Only the DACL of a share is enforced.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the share does not exist or its security cannot be written.
+    pub fn apply<P>(&self, sd: &SecurityDescriptorImpl<P>, components: SecurityInformation) -> Result<(), WinError>
+    where
+        P: PrivilegeLevel,
+    {
+        sd.apply_to_handle_name(self.object_name(), SE_LMSHARE, components)
+    }
+
+    /// Reads the information of the share, including its security descriptor, with
+    /// `NetShareGetInfo` (level 502).
+    ///
+    /// # Errors
+    ///
+    /// Returns `NERR_NetNameNotFound` (2310) if the share does not exist, or an error if the
+    /// information cannot be read.
+    pub fn info(&self) -> Result<ShareInfo, WinError> {
+        let buffer = self.get_info_502()?;
+        let info = unsafe { &*(buffer as *const SHARE_INFO_502) };
+
+        let security = if info.shi502_security_descriptor.is_null() {
+            Ok(None)
+        } else {
+            let len = unsafe { GetSecurityDescriptorLength(info.shi502_security_descriptor) } as usize;
+            let bytes = unsafe { from_raw_parts(info.shi502_security_descriptor as *const u8, len) };
+            SecurityDescriptor::from_bytes(bytes).map(Some)
+        };
+        let result = security.map(|security| ShareInfo {
+            name: WideCString::from_wide_null_ptr(info.shi502_netname).as_string(),
+            path: WideCString::from_wide_null_ptr(info.shi502_path).as_string(),
+            remark: WideCString::from_wide_null_ptr(info.shi502_remark).as_string(),
+            max_uses: info.shi502_max_uses,
+            current_uses: info.shi502_current_uses,
+            security,
+        });
+
+        unsafe { NetApiBufferFree(buffer as _) };
+        result
+    }
+
+    /// Replaces the security descriptor of the share with `NetShareSetInfo` (level 502).
+    ///
+    /// The other settings of the share are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NERR_NetNameNotFound` (2310) if the share does not exist, or an error if the
+    /// share cannot be updated.
+    pub fn set_info_security<P>(&self, sd: &SecurityDescriptorImpl<P>) -> Result<(), WinError>
+    where
+        P: PrivilegeLevel,
+    {
+        let buffer = self.get_info_502()?;
+        let mut info = unsafe { *(buffer as *const SHARE_INFO_502) };
+        info.shi502_security_descriptor = sd.as_ptr();
+
+        let server = self
+            .server
+            .as_ref()
+            .map(|server| WideCString::new(&format!(r"\\{}", server)));
+        let name = WideCString::new(OsStr::new(&self.name));
+        let mut parm_err = 0u32;
+        unsafe {
+            winapi_call!(
+                NetShareSetInfo(
+                    server.as_ref().map_or(null(), WideCString::as_ptr),
+                    name.as_ptr(),
+                    502,
+                    &info as *const SHARE_INFO_502 as _,
+                    &mut parm_err,
+                ),
+                {
+                    NetApiBufferFree(buffer as _);
+                }
+            );
+            NetApiBufferFree(buffer as _);
+        }
+        Ok(())
+    }
+
+    /// Returns the `SHARE_INFO_502` buffer of the share, to be freed with `NetApiBufferFree`.
+    fn get_info_502(&self) -> Result<*mut u8, WinError> {
+        let server = self
+            .server
+            .as_ref()
+            .map(|server| WideCString::new(&format!(r"\\{}", server)));
+        let name = WideCString::new(OsStr::new(&self.name));
+
+        let mut buffer: *mut u8 = null_mut();
+        unsafe {
+            winapi_call!(NetShareGetInfo(
+                server.as_ref().map_or(null(), WideCString::as_ptr),
+                name.as_ptr(),
+                502,
+                &mut buffer,
+            ))
+        };
+        Ok(buffer)
+    }
+}
+
+impl Display for Share {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.object_name())
+    }
+}
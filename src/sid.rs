@@ -27,26 +27,44 @@
 //! ```
 
 use std::{
+    cmp::Ordering,
     fmt::{Debug, Display, Formatter},
     hash::Hash,
     marker::PhantomData,
-    ptr::null_mut,
+    mem::{size_of, size_of_val},
+    os::windows::io::AsRawHandle,
+    ptr::{null, null_mut},
     str::FromStr,
 };
 
 use windows_sys::Win32::{
-    Foundation::{ERROR_OUTOFMEMORY, FALSE, GetLastError},
+    Foundation::{
+        CloseHandle, ERROR_INSUFFICIENT_BUFFER, ERROR_INVALID_PARAMETER, ERROR_INVALID_SID, FALSE, GetLastError,
+        HANDLE, STATUS_SUCCESS,
+    },
     Security::{
+        Authentication::Identity::LsaNtStatusToWinError,
         Authorization::{ConvertSidToStringSidW, ConvertStringSidToSidW},
-        CreateWellKnownSid, GetLengthSid, IsValidSid, PSID, SECURITY_MAX_SID_SIZE, SID, WELL_KNOWN_SID_TYPE,
+        CreateWellKnownSid,
+        Cryptography::{BCRYPT_SHA1_ALG_HANDLE, BCryptHash},
+        EqualDomainSid, EqualPrefixSid, FreeSid, GetLengthSid, GetTokenInformation, GetWindowsAccountDomainSid,
+        IsValidSid,
+        Isolation::DeriveAppContainerSidFromAppContainerName,
+        PSID, SECURITY_MAX_SID_SIZE, SID, SID_AND_ATTRIBUTES, TOKEN_GROUPS, TOKEN_INFORMATION_CLASS, TOKEN_QUERY,
+        TokenGroups, TokenOwner, TokenPrimaryGroup, TokenUser, WELL_KNOWN_SID_TYPE,
+    },
+    System::{
+        SystemServices::{SE_GROUP_LOGON_ID, SECURITY_SERVICE_ID_BASE_RID, SID_MAX_SUB_AUTHORITIES, SID_REVISION},
+        Threading::{GetCurrentProcess, OpenProcessToken},
     },
-    System::Memory::{LMEM_FIXED, LocalAlloc},
 };
 
 use crate::{
     assert_free,
     error::WinError,
-    sid::account::{AccountLookup, lookup_account_name, lookup_account_sid},
+    lsa::LsaPolicy,
+    remote::RemoteHost,
+    sid::account::{AccountLookup, LookupPolicy, cached_name, lookup_account_name, lookup_policy, lookup_sid},
     trustee::Trustee,
     utils::WideCString,
     winapi_bool_call,
@@ -84,9 +102,75 @@ pub trait AsSidRef<'a> {
 /// let sid_str = Sid::from_string("S-1-5-32-544")?; // Administrators
 /// # Ok::<(), win_acl_rs::error::WinError>(())
 /// ```
+///
+/// The SID is stored inline (SIDs are at most `SECURITY_MAX_SID_SIZE` bytes long), so creating
+/// and cloning SIDs does not allocate.
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub struct Sid {
-    data: Vec<u8>,
+    // u32 elements keep the sub-authorities properly aligned
+    data: [u32; SID_MAX_WORDS],
+    len: usize,
+}
+
+/// The size of the inline storage of a SID in `u32` elements.
+const SID_MAX_WORDS: usize = SECURITY_MAX_SID_SIZE as usize / size_of::<u32>();
+
+/// The identifier authority of a SID, the `X` in `S-1-X-...`.
+///
+/// The authority is a 48-bit value naming the authority that issued the SID; the constants
+/// cover the authorities in common use.
+///
+/// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/secauthz/sid-components)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct IdentifierAuthority(pub u64);
+
+impl IdentifierAuthority {
+    /// The null authority (`S-1-0`), e.g., the Nobody SID `S-1-0-0`.
+    pub const NULL: Self = Self(0);
+    /// The world authority (`S-1-1`), e.g., Everyone `S-1-1-0`.
+    pub const WORLD: Self = Self(1);
+    /// The local authority (`S-1-2`).
+    pub const LOCAL: Self = Self(2);
+    /// The creator authority (`S-1-3`), e.g., Creator Owner `S-1-3-0`.
+    pub const CREATOR: Self = Self(3);
+    /// The Windows NT authority (`S-1-5`): accounts, groups, services and built-in aliases.
+    pub const NT: Self = Self(5);
+    /// The resource manager authority (`S-1-9`).
+    pub const RESOURCE_MANAGER: Self = Self(9);
+    /// The app package authority (`S-1-15`): app containers and capabilities.
+    pub const APP_PACKAGE: Self = Self(15);
+    /// The mandatory label authority (`S-1-16`): integrity levels.
+    pub const MANDATORY_LABEL: Self = Self(16);
+    /// The scoped policy ID authority (`S-1-17`): central access policies.
+    pub const SCOPED_POLICY_ID: Self = Self(17);
+    /// The authentication authority (`S-1-18`), e.g., the authentication assertion SIDs.
+    pub const AUTHENTICATION: Self = Self(18);
+
+    /// Returns the authority as the 6 big-endian bytes stored in a SID.
+    pub fn to_bytes(self) -> [u8; 6] {
+        let bytes = self.0.to_be_bytes();
+        [bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]]
+    }
+
+    /// Creates the authority from the 6 big-endian bytes stored in a SID.
+    pub fn from_bytes(bytes: [u8; 6]) -> Self {
+        let mut value = [0u8; 8];
+        value[2..].copy_from_slice(&bytes);
+        Self(u64::from_be_bytes(value))
+    }
+}
+
+impl From<IdentifierAuthority> for u64 {
+    fn from(authority: IdentifierAuthority) -> Self {
+        authority.0
+    }
+}
+
+impl From<u64> for IdentifierAuthority {
+    fn from(value: u64) -> Self {
+        IdentifierAuthority(value)
+    }
 }
 
 /// A borrowed reference to a Security Identifier (SID).
@@ -134,6 +218,41 @@ impl TryFrom<&[u8]> for Sid {
     }
 }
 
+/// SIDs are ordered by identifier authority, then by their sub-authorities compared as
+/// numbers from left to right, so a SID sorts right after its prefixes:
+/// `S-1-1-0` < `S-1-5-18` < `S-1-5-21-1-2-3` < `S-1-5-21-1-2-3-500` < `S-1-5-32-544`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::collections::BTreeSet;
+///
+/// use win_acl_rs::sid::Sid;
+///
+/// let sids: BTreeSet<Sid> = ["S-1-5-32-544", "S-1-1-0", "S-1-5-18"]
+///     .iter()
+///     .map(Sid::from_string)
+///     .collect::<Result<_, _>>()?;
+/// let first = sids.first().map(Sid::to_string).transpose()?;
+/// assert_eq!(first.as_deref(), Some("S-1-1-0"));
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+impl Ord for Sid {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.identifier_authority()
+            .cmp(&other.identifier_authority())
+            .then_with(|| self.sub_authorities().cmp(other.sub_authorities()))
+            // only differs for malformed SIDs, keeps the order consistent with `Eq`
+            .then_with(|| self.as_bytes().cmp(other.as_bytes()))
+    }
+}
+
+impl PartialOrd for Sid {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl From<Sid> for Vec<u8> {
     fn from(sid: Sid) -> Self {
         sid.to_vec()
@@ -141,6 +260,53 @@ impl From<Sid> for Vec<u8> {
 }
 
 impl Sid {
+    /// Creates a SID from its identifier authority and sub-authorities.
+    ///
+    /// This builds arbitrary SIDs (service SIDs, capability SIDs, test fixtures) without
+    /// formatting and parsing their string form.
+    ///
+    /// # Arguments
+    ///
+    /// * `authority` - The identifier authority (the `5` in `S-1-5-32-544`).
+    /// * `sub_authorities` - The sub-authorities (`32` and `544` in `S-1-5-32-544`), at most 15.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ERROR_INVALID_PARAMETER` if there are more than 15 sub-authorities or the
+    /// authority does not fit in 48 bits.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sid::{IdentifierAuthority, Sid};
+    ///
+    /// let admins = Sid::new(IdentifierAuthority::NT, &[32, 544])?;
+    /// assert_eq!(admins.to_string()?, "S-1-5-32-544");
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn new(authority: IdentifierAuthority, sub_authorities: &[u32]) -> Result<Self, WinError> {
+        if sub_authorities.len() > SID_MAX_SUB_AUTHORITIES as usize {
+            return Err(WinError::new(
+                ERROR_INVALID_PARAMETER,
+                Some(format!("Too many sub-authorities: {}", sub_authorities.len())),
+            ));
+        }
+        if authority.0 >> 48 != 0 {
+            return Err(WinError::new(
+                ERROR_INVALID_PARAMETER,
+                Some(format!("Identifier authority out of range: {:#x}", authority.0)),
+            ));
+        }
+
+        let mut bytes = Vec::with_capacity(8 + size_of_val(sub_authorities));
+        bytes.extend_from_slice(&[SID_REVISION as u8, sub_authorities.len() as u8]);
+        bytes.extend_from_slice(&authority.to_bytes());
+        for sub_authority in sub_authorities {
+            bytes.extend_from_slice(&sub_authority.to_le_bytes());
+        }
+        Self::from_bytes(&bytes)
+    }
+
     /// Creates a SID from raw byte data.
     ///
     /// # Arguments
@@ -149,9 +315,17 @@ impl Sid {
     ///
     /// # Errors
     ///
-    /// Returns an error if the byte data does not represent a valid SID structure.
+    /// Returns `ERROR_INVALID_SID` if the data is longer than `SECURITY_MAX_SID_SIZE` bytes.
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, WinError> {
-        Ok(Self { data: bytes.to_vec() })
+        if bytes.len() > SECURITY_MAX_SID_SIZE as usize {
+            return Err(WinError::new(
+                ERROR_INVALID_SID,
+                Some(format!("SID data too long: {} bytes", bytes.len())),
+            ));
+        }
+        let mut data = [0u32; SID_MAX_WORDS];
+        unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), data.as_mut_ptr() as *mut u8, bytes.len()) };
+        Ok(Self { data, len: bytes.len() })
     }
 
     /// Creates a SID from its string representation.
@@ -192,11 +366,11 @@ impl Sid {
             return Err(unsafe { GetLastError().into() });
         }
         let len = unsafe { GetLengthSid(sid_ptr) as usize };
-        let data = unsafe { std::slice::from_raw_parts(sid_ptr as *const u8, len) }.to_vec();
+        let sid = Self::from_bytes(unsafe { std::slice::from_raw_parts(sid_ptr as *const u8, len) });
 
         unsafe { assert_free!(sid_ptr, "Sid::from_string") };
 
-        Ok(Sid { data })
+        sid
     }
 
     /// Creates a SID for a well-known security principal.
@@ -240,7 +414,7 @@ impl Sid {
         kind: WELL_KNOWN_SID_TYPE,
         domain_sid_ptr: Option<SidRef>,
     ) -> Result<Self, WinError> {
-        let mut buf = vec![0u8; SECURITY_MAX_SID_SIZE as usize];
+        let mut buf = [0u8; SECURITY_MAX_SID_SIZE as usize];
         let mut size = buf.len() as u32;
         let domain = match domain_sid_ptr {
             None => null_mut() as PSID,
@@ -250,6 +424,269 @@ impl Sid {
         Self::from_bytes(&buf[..size as usize])
     }
 
+    /// Returns the SID of the user the current process runs as.
+    ///
+    /// This is the usual principal to grant access to when a program creates objects for itself.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the process token cannot be queried.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{acl::Acl, mask::FileAccess, sid::Sid};
+    ///
+    /// let mut acl = Acl::new()?;
+    /// acl.allow(FileAccess::FULL, &Sid::current_user()?)?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn current_user() -> Result<Self, WinError> {
+        let mut token: HANDLE = null_mut();
+        unsafe { winapi_bool_call!(OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token)) };
+        let sid = unsafe { token_sid(token, TokenUser) };
+        unsafe { CloseHandle(token) };
+        sid
+    }
+
+    /// Returns the logon SID of the current process, identifying its logon session.
+    ///
+    /// The logon SID (`S-1-5-5-X-Y`) is needed to grant access to window stations, desktops and
+    /// other objects that should be shared by all processes of the session.
+    ///
+    /// # Returns
+    ///
+    /// `Some(sid)`, or `None` if the process token has no logon SID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the process token cannot be queried.
+    ///
+    /// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/secauthz/getting-the-logon-sid-in-c--)
+    pub fn logon_sid() -> Result<Option<Self>, WinError> {
+        let mut token: HANDLE = null_mut();
+        unsafe { winapi_bool_call!(OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token)) };
+        let buffer = unsafe { token_information(token, TokenGroups) };
+        unsafe { CloseHandle(token) };
+        let buffer = buffer?;
+
+        let groups = unsafe {
+            // the group array extends past the declared length of `Groups`
+            let groups = buffer.as_ptr() as *const TOKEN_GROUPS;
+            std::slice::from_raw_parts(
+                (&raw const (*groups).Groups) as *const SID_AND_ATTRIBUTES,
+                (*groups).GroupCount as usize,
+            )
+        };
+        groups
+            .iter()
+            .find(|group| group.Attributes & SE_GROUP_LOGON_ID as u32 == SE_GROUP_LOGON_ID as u32)
+            .map(|group| Self::from_bytes(unsafe { SidRef::from_ptr(group.Sid as _) }.as_bytes()))
+            .transpose()
+    }
+
+    /// Returns the user, primary group and owner SIDs of an access token.
+    ///
+    /// The token can belong to a process, a thread or an impersonated client, which lets server
+    /// code build ACLs granting access to the connected user.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - An access token opened with `TOKEN_QUERY` access.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the token cannot be queried.
+    ///
+    /// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-gettokeninformation)
+    pub fn from_token<H>(token: &H) -> Result<TokenSids, WinError>
+    where
+        H: AsRawHandle,
+    {
+        let token = token.as_raw_handle() as HANDLE;
+        unsafe {
+            Ok(TokenSids {
+                user: token_sid(token, TokenUser)?,
+                primary_group: token_sid(token, TokenPrimaryGroup)?,
+                owner: token_sid(token, TokenOwner)?,
+            })
+        }
+    }
+
+    /// Derives the SID of an AppContainer from its name (`S-1-15-2-...`).
+    ///
+    /// Granting access to this SID lets files, pipes and other objects be used by a specific
+    /// packaged or AppContainer application. The SID is computed from the name, so the app does
+    /// not need to be installed.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The AppContainer name (for packaged apps, the package family name).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error (an `HRESULT` code) if the name is invalid, e.g., empty or longer than
+    /// 64 characters.
+    ///
+    /// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/api/userenv/nf-userenv-deriveappcontainersidfromappcontainername)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{acl::Acl, mask::FileAccess, sid::Sid};
+    ///
+    /// let app = Sid::app_container("ContosoApp")?;
+    /// let mut acl = Acl::new()?;
+    /// acl.allow(FileAccess::READ, &app)?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn app_container<S>(name: S) -> Result<Self, WinError>
+    where
+        S: AsRef<str>,
+    {
+        let wide = WideCString::new(name.as_ref());
+        let mut sid_ptr: PSID = null_mut();
+        let hr = unsafe { DeriveAppContainerSidFromAppContainerName(wide.as_ptr(), &mut sid_ptr) };
+        if hr < 0 || sid_ptr.is_null() {
+            return Err(WinError::new(hr as u32, None));
+        }
+        let len = unsafe { GetLengthSid(sid_ptr) as usize };
+        let sid = Self::from_bytes(unsafe { std::slice::from_raw_parts(sid_ptr as *const u8, len) });
+
+        // the SID is allocated by the system with AllocateAndInitializeSid
+        unsafe { FreeSid(sid_ptr) };
+
+        sid
+    }
+
+    /// Computes the SID of a Windows service (`NT SERVICE\<name>`, `S-1-5-80-...`).
+    ///
+    /// The SID is derived locally from the SHA-1 hash of the upper-cased service name, the same
+    /// way the service control manager does it. Per-service ACLs can therefore be created before
+    /// the service is installed.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The service name (not its display name).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is empty or cannot be hashed.
+    ///
+    /// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/services/service-security-and-access-rights)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sid::Sid;
+    ///
+    /// let sid = Sid::service("TrustedInstaller")?;
+    /// assert_eq!(
+    ///     sid.to_string()?,
+    ///     "S-1-5-80-956008885-3418522649-1831038044-1853292631-2271478464"
+    /// );
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn service<S>(name: S) -> Result<Self, WinError>
+    where
+        S: AsRef<str>,
+    {
+        let name = name.as_ref();
+        if name.is_empty() {
+            return Err(WinError::new(
+                ERROR_INVALID_PARAMETER,
+                Some("The service name cannot be empty".to_owned()),
+            ));
+        }
+        let input: Vec<u8> = name.to_uppercase().encode_utf16().flat_map(u16::to_le_bytes).collect();
+
+        let mut hash = [0u8; 20];
+        let status = unsafe {
+            BCryptHash(
+                BCRYPT_SHA1_ALG_HANDLE,
+                null_mut(),
+                0,
+                input.as_ptr(),
+                input.len() as u32,
+                hash.as_mut_ptr(),
+                hash.len() as u32,
+            )
+        };
+        if status != STATUS_SUCCESS {
+            return Err(unsafe { LsaNtStatusToWinError(status) }.into());
+        }
+
+        let mut sub_authorities = [SECURITY_SERVICE_ID_BASE_RID as u32; 6];
+        for (sub_authority, chunk) in sub_authorities[1..].iter_mut().zip(hash.chunks_exact(4)) {
+            *sub_authority = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        Self::new(IdentifierAuthority::NT, &sub_authorities)
+    }
+
+    /// Returns the SID of the local machine, the domain of its local accounts.
+    ///
+    /// The SIDs of local users and groups are this SID followed by a RID (see [`Sid::with_rid`]),
+    /// e.g., the local *Administrator* account has RID `500`. On a domain controller this is
+    /// the domain SID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the local LSA policy cannot be queried.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sid::Sid;
+    ///
+    /// let machine = Sid::machine()?;
+    /// let administrator = machine.with_rid(500)?;
+    /// println!("{}", administrator.lookup_name()?.name);
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn machine() -> Result<Self, WinError> {
+        LsaPolicy::open()?.account_domain_sid()
+    }
+
+    /// Returns the SID of the domain an account SID belongs to.
+    ///
+    /// # Arguments
+    ///
+    /// * `sid` - The SID of a domain or local account (`S-1-5-21-...`).
+    ///
+    /// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-getwindowsaccountdomainsid)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `sid` is not the SID of an account in a domain (e.g., a built-in
+    /// or well-known SID).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sid::Sid;
+    ///
+    /// let user = Sid::from_string("S-1-5-21-1402048822-409899687-2319524958-1001")?;
+    /// let domain = Sid::account_domain_of(&user)?;
+    /// assert_eq!(domain.to_string()?, "S-1-5-21-1402048822-409899687-2319524958");
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn account_domain_of<'a, S>(sid: &'a S) -> Result<Self, WinError>
+    where
+        S: AsSidRef<'a>,
+    {
+        let mut buf = [0u8; SECURITY_MAX_SID_SIZE as usize];
+        let mut size = buf.len() as u32;
+        unsafe {
+            winapi_bool_call!(GetWindowsAccountDomainSid(
+                sid.as_sid_ref().as_ptr() as PSID,
+                buf.as_mut_ptr() as PSID,
+                &mut size
+            ))
+        };
+        let len = unsafe { GetLengthSid(buf.as_ptr() as PSID) } as usize;
+        Self::from_bytes(&buf[..len])
+    }
+
     /// Creates a SID by looking up an account name.
     ///
     /// The account name can be in formats like:
@@ -278,7 +715,7 @@ impl Sid {
     where
         S: AsRef<str>,
     {
-        unsafe { lookup_account_name(name).map(|a| Self::from_string(&a.name))? }
+        unsafe { lookup_account_name(None, name).map(|a| a.sid) }
     }
 
     /// Looks up the account name and domain for this SID.
@@ -305,7 +742,115 @@ impl Sid {
     /// # Ok::<(), win_acl_rs::error::WinError>(())
     /// ```
     pub fn lookup_name(&self) -> Result<AccountLookup, WinError> {
-        unsafe { lookup_account_sid(self.data.as_ptr() as PSID) }
+        lookup_sid(None, self, lookup_policy())
+    }
+
+    /// Looks up the account name and domain for this SID with a specific lookup policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - How to resolve the SID, overriding the global policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the SID cannot be resolved within the constraints of the policy.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sid::{Sid, account::LookupPolicy};
+    ///
+    /// let sid = Sid::from_string("S-1-5-32-544")?;
+    /// let lookup = sid.lookup_name_with(LookupPolicy::Offline)?;
+    /// assert_eq!(lookup.domain, "BUILTIN");
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn lookup_name_with(&self, policy: LookupPolicy) -> Result<AccountLookup, WinError> {
+        lookup_sid(None, self, policy)
+    }
+
+    /// Creates a SID by looking up an account name on a remote machine.
+    ///
+    /// Local accounts and groups of the remote machine (e.g., `SERVER01\Backup`) can only be
+    /// resolved there.
+    ///
+    /// # Arguments
+    ///
+    /// * `host` - The machine to resolve the name on.
+    /// * `name` - The account name to look up.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the machine cannot be reached or the account name cannot be resolved.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::{remote::RemoteHost, sid::Sid};
+    ///
+    /// let host = RemoteHost::new("SERVER01")?;
+    /// let sid = Sid::from_account_name_on(&host, "SERVER01\\Backup")?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn from_account_name_on<S>(host: &RemoteHost, name: S) -> Result<Self, WinError>
+    where
+        S: AsRef<str>,
+    {
+        unsafe { lookup_account_name(Some(host.name()), name).map(|a| a.sid) }
+    }
+
+    /// Looks up the account name and domain for this SID on a remote machine.
+    ///
+    /// SIDs of local accounts of the remote machine, such as the trustees of its share ACLs, only
+    /// resolve there.
+    ///
+    /// # Arguments
+    ///
+    /// * `host` - The machine to resolve the SID on.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the machine cannot be reached or the SID is unknown to it.
+    pub fn lookup_name_on(&self, host: &RemoteHost) -> Result<AccountLookup, WinError> {
+        lookup_sid(Some(host.name()), self, lookup_policy())
+    }
+
+    /// Returns a formatter rendering the SID with its account name, as `DOMAIN\name (S-1-5-...)`.
+    ///
+    /// SIDs that cannot be resolved are rendered as the SID string alone. Lookups follow the
+    /// global [`LookupPolicy`], and their results are cached for the lifetime of the process
+    /// (see [`account::clear_name_cache`]), so dumping a large ACL resolves each trustee once.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sid::Sid;
+    ///
+    /// let sid = Sid::from_string("S-1-5-32-544")?;
+    /// // BUILTIN\Administrators (S-1-5-32-544)
+    /// println!("{}", sid.display_with_names());
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn display_with_names(&self) -> SidDisplay<'_> {
+        SidDisplay { sid: self }
+    }
+
+    /// Returns a wrapper whose `Debug` output also includes the account name of the SID.
+    ///
+    /// The plain `Debug` output stays offline; resolving the name follows the global
+    /// [`LookupPolicy`] and can be slow on a domain-joined machine.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sid::Sid;
+    ///
+    /// let sid = Sid::from_string("S-1-5-32-544")?;
+    /// println!("{:?}", sid.debug_resolved());
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn debug_resolved(&self) -> impl Debug + '_ {
+        DebugResolved(self)
     }
 
     /// Checks if this SID is valid.
@@ -382,8 +927,202 @@ impl Sid {
     ///
     /// A `Vec<u8>` containing the raw SID byte data.
     pub fn to_vec(&self) -> Vec<u8> {
-        self.data.clone()
+        self.as_bytes().to_vec()
+    }
+
+    /// Returns the revision of the SID structure, always `1` (the `1` in `S-1-...`).
+    pub fn revision(&self) -> u8 {
+        self.as_bytes().first().copied().unwrap_or_default()
+    }
+
+    /// Returns the identifier authority of the SID (the `5` in `S-1-5-32-544`).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sid::{IdentifierAuthority, Sid};
+    ///
+    /// let sid = Sid::from_string("S-1-5-21-1402048822-409899687-2319524958-1001")?;
+    /// // accounts of a domain or of a machine
+    /// assert_eq!(sid.identifier_authority(), IdentifierAuthority::NT);
+    /// assert_eq!(sid.sub_authorities().first(), Some(&21));
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn identifier_authority(&self) -> IdentifierAuthority {
+        let mut bytes = [0u8; 6];
+        if let Some(authority) = self.as_bytes().get(2..8) {
+            bytes.copy_from_slice(authority);
+        }
+        IdentifierAuthority::from_bytes(bytes)
+    }
+
+    /// Returns the sub-authorities of the SID (`32` and `544` in `S-1-5-32-544`).
+    pub fn sub_authorities(&self) -> &[u32] {
+        // the sub-authorities follow the 8 byte header: revision, count and authority
+        let count = self.as_bytes().get(1).copied().unwrap_or_default() as usize;
+        let available = self.len.saturating_sub(8) / size_of::<u32>();
+        self.data.get(2..2 + count.min(available)).unwrap_or_default()
+    }
+
+    /// Returns the relative identifier (RID) of the SID, its last sub-authority (`544` in
+    /// `S-1-5-32-544`).
+    ///
+    /// # Returns
+    ///
+    /// `Some(rid)`, or `None` for SIDs without sub-authorities.
+    pub fn rid(&self) -> Option<u32> {
+        self.sub_authorities().last().copied()
+    }
+
+    /// Creates a SID by appending a relative identifier (RID) to this SID.
+    ///
+    /// This is how the accounts of a domain are derived from the domain SID, e.g., the domain
+    /// SID with RID `513` is the *Domain Users* group.
+    ///
+    /// # Arguments
+    ///
+    /// * `rid` - The relative identifier to append.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ERROR_INVALID_PARAMETER` if the SID already has 15 sub-authorities.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sid::Sid;
+    ///
+    /// let domain = Sid::from_string("S-1-5-21-1402048822-409899687-2319524958")?;
+    /// let domain_users = domain.with_rid(513)?;
+    /// assert_eq!(domain_users.to_string()?, "S-1-5-21-1402048822-409899687-2319524958-513");
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn with_rid(&self, rid: u32) -> Result<Self, WinError> {
+        let mut sub_authorities = self.sub_authorities().to_vec();
+        sub_authorities.push(rid);
+        Self::new(self.identifier_authority(), &sub_authorities)
     }
+
+    /// Checks whether two account SIDs belong to the same domain.
+    ///
+    /// Either SID can also be a domain SID itself. Comparing an account against [`Sid::machine`]
+    /// tells whether it is a local account rather than one from a trusted domain.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The SID to compare with.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either SID is not the SID of a domain or of an account in a domain.
+    ///
+    /// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-equaldomainsid)
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use win_acl_rs::sid::Sid;
+    ///
+    /// let user = Sid::from_string("S-1-5-21-1402048822-409899687-2319524958-1001")?;
+    /// let is_local = user.same_domain(&Sid::machine()?)?;
+    /// # Ok::<(), win_acl_rs::error::WinError>(())
+    /// ```
+    pub fn same_domain<'a, S>(&self, other: &'a S) -> Result<bool, WinError>
+    where
+        S: AsSidRef<'a>,
+    {
+        let mut equal = FALSE;
+        unsafe {
+            winapi_bool_call!(EqualDomainSid(
+                self.data.as_ptr() as PSID,
+                other.as_sid_ref().as_ptr() as PSID,
+                &mut equal
+            ))
+        };
+        Ok(equal != FALSE)
+    }
+
+    /// Checks whether two SIDs share the same prefix, i.e., are equal except for the RID.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The SID to compare with.
+    ///
+    /// # Returns
+    ///
+    /// `true` if both SIDs have the same identifier authority and sub-authorities up to the last one.
+    ///
+    /// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/api/securitybaseapi/nf-securitybaseapi-equalprefixsid)
+    pub fn equal_prefix<'a, S>(&self, other: &'a S) -> bool
+    where
+        S: AsSidRef<'a>,
+    {
+        unsafe { EqualPrefixSid(self.data.as_ptr() as PSID, other.as_sid_ref().as_ptr() as PSID) != FALSE }
+    }
+
+    /// Returns the raw SID bytes without copying them.
+    ///
+    /// # Returns
+    ///
+    /// A slice of the raw SID byte data, see [`Sid::to_vec`] for an owned copy.
+    pub fn as_bytes(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.data.as_ptr() as *const u8, self.len) }
+    }
+}
+
+/// The SIDs identifying an access token, see [`Sid::from_token`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenSids {
+    /// The user the token represents.
+    pub user: Sid,
+    /// The default primary group of objects created with the token.
+    pub primary_group: Sid,
+    /// The default owner of objects created with the token.
+    pub owner: Sid,
+}
+
+/// Reads a class of token information into a buffer aligned for the returned structure.
+///
+/// # Safety
+///
+/// `token` must be a valid token handle with `TOKEN_QUERY` access.
+unsafe fn token_information(token: HANDLE, class: TOKEN_INFORMATION_CLASS) -> Result<Vec<u64>, WinError> {
+    let mut size = 0u32;
+    let result = unsafe { GetTokenInformation(token, class, null_mut(), 0, &mut size) };
+    if result == 0 {
+        let err = unsafe { GetLastError() };
+        if err != ERROR_INSUFFICIENT_BUFFER {
+            return Err(err.into());
+        }
+    }
+
+    // u64 elements keep the structure properly aligned
+    let mut buffer = vec![0u64; (size as usize).div_ceil(size_of::<u64>())];
+    unsafe {
+        winapi_bool_call!(GetTokenInformation(
+            token,
+            class,
+            buffer.as_mut_ptr() as _,
+            size,
+            &mut size
+        ))
+    };
+
+    Ok(buffer)
+}
+
+/// Reads a SID from a token: the user (`TokenUser`), owner (`TokenOwner`) or primary group
+/// (`TokenPrimaryGroup`).
+///
+/// # Safety
+///
+/// `token` must be a valid token handle with `TOKEN_QUERY` access.
+unsafe fn token_sid(token: HANDLE, class: TOKEN_INFORMATION_CLASS) -> Result<Sid, WinError> {
+    let buffer = unsafe { token_information(token, class)? };
+
+    // TOKEN_USER, TOKEN_OWNER and TOKEN_PRIMARY_GROUP all start with the SID pointer
+    let sid = unsafe { *(buffer.as_ptr() as *const PSID) };
+    Sid::from_bytes(unsafe { SidRef::from_ptr(sid as _) }.as_bytes())
 }
 
 impl<'a> AsSidRef<'a> for Sid {
@@ -392,22 +1131,61 @@ impl<'a> AsSidRef<'a> for Sid {
     }
 }
 
+/// Renders a SID with its account name, see [`Sid::display_with_names`].
+#[derive(Debug, Clone, Copy)]
+pub struct SidDisplay<'a> {
+    sid: &'a Sid,
+}
+
+impl Display for SidDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match cached_name(self.sid) {
+            Some(name) => write!(f, "{} ({})", name, self.sid),
+            None => write!(f, "{}", self.sid),
+        }
+    }
+}
+
+/// Wraps a value to include resolved account names in its `Debug` output, see
+/// [`Sid::debug_resolved`].
+pub(crate) struct DebugResolved<'a, T: ?Sized>(pub(crate) &'a T);
+
+/// Formats the account of a SID lookup for `Debug` output.
+pub(crate) fn debug_account(lookup: Result<AccountLookup, WinError>) -> String {
+    match lookup {
+        Ok(acc) => format!("{}/{}", acc.domain, acc.name),
+        Err(_) => "<ACCOUNT LOOKUP ERROR>".to_string(),
+    }
+}
+
+impl Sid {
+    fn fmt_debug(&self, f: &mut Formatter<'_>, resolve: bool) -> std::fmt::Result {
+        let mut fmt = f.debug_struct("Sid");
+        fmt.field(
+            "as_string",
+            &self.to_string().unwrap_or_else(|_| "<INVALID SID>".to_string()),
+        )
+        .field("is_valid", &self.is_valid())
+        .field("data", &self.as_bytes())
+        .field("len", &self.len());
+        if resolve {
+            fmt.field("account", &debug_account(self.lookup_name()));
+        }
+        fmt.finish()
+    }
+}
+
+/// The `Debug` output does not look up the account name, so formatting is fast and never
+/// contacts a domain controller. Use [`Sid::debug_resolved`] to include it.
 impl Debug for Sid {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let account = match &self.lookup_name() {
-            Ok(acc) => format!("{}/{}", acc.domain, acc.name),
-            Err(_) => "<ACCOUNT LOOKUP ERROR>".to_string(),
-        };
-        f.debug_struct("Sid")
-            .field(
-                "as_string",
-                &self.to_string().unwrap_or_else(|_| "<INVALID SID>".to_string()),
-            )
-            .field("is_valid", &self.is_valid())
-            .field("data", &self.data)
-            .field("len", &self.len())
-            .field("account", &account)
-            .finish()
+        self.fmt_debug(f, false)
+    }
+}
+
+impl Debug for DebugResolved<'_, Sid> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt_debug(f, true)
     }
 }
 
@@ -438,6 +1216,12 @@ impl<'a> SidRef<'a> {
         Self { ptr, _p: PhantomData }
     }
 
+    /// Returns a wrapper whose `Debug` output also includes the account name of the SID,
+    /// see [`Sid::debug_resolved`].
+    pub fn debug_resolved(&self) -> impl Debug + '_ {
+        DebugResolved(self)
+    }
+
     /// Looks up the account name and domain for this SID.
     ///
     /// # Safety
@@ -449,7 +1233,7 @@ impl<'a> SidRef<'a> {
     /// An `AccountLookup` containing the account name, domain, and SID type, or an error
     /// if the lookup fails.
     pub unsafe fn lookup_name(&self) -> Result<AccountLookup, WinError> {
-        unsafe { lookup_account_sid(self.ptr as PSID) }
+        lookup_sid(None, &Sid::from_bytes(self.as_bytes())?, lookup_policy())
     }
 
     /// Checks if this SID is valid.
@@ -521,7 +1305,16 @@ impl<'a> SidRef<'a> {
     ///
     /// A `Vec<u8>` containing the raw SID byte data.
     pub fn to_vec(&self) -> Vec<u8> {
-        unsafe { std::slice::from_raw_parts(self.ptr as *const u8, self.len()) }.to_vec()
+        self.as_bytes().to_vec()
+    }
+
+    /// Returns the raw SID bytes without copying them.
+    ///
+    /// # Returns
+    ///
+    /// A slice of the raw SID byte data, valid for the lifetime `'a`.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr as *const u8, self.len()) }
     }
 
     /// Returns the raw pointer to the underlying SID structure.
@@ -547,33 +1340,301 @@ impl<'a> Display for SidRef<'a> {
     }
 }
 
-impl<'a> Debug for SidRef<'a> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let lookup = unsafe { &self.lookup_name() };
+impl<'a> SidRef<'a> {
+    fn fmt_debug(&self, f: &mut Formatter<'_>, resolve: bool) -> std::fmt::Result {
         let is_valid = unsafe { &self.is_valid() };
         let len = unsafe { &self.len() };
-        let account = match lookup {
-            Ok(acc) => format!("{}/{}", acc.domain, acc.name),
-            Err(_) => "<ACCOUNT LOOKUP ERROR>".to_string(),
-        };
-        f.debug_struct("SidRef<'a>")
-            .field(
-                "as_string",
-                &self.to_string().unwrap_or_else(|_| "<INVALID SID>".to_string()),
-            )
-            .field("is_valid", is_valid)
-            .field("ptr", &self.ptr)
-            .field("len", len)
-            .field("account", &account)
-            .finish()
+        let mut fmt = f.debug_struct("SidRef<'a>");
+        fmt.field(
+            "as_string",
+            &self.to_string().unwrap_or_else(|_| "<INVALID SID>".to_string()),
+        )
+        .field("is_valid", is_valid)
+        .field("ptr", &self.ptr)
+        .field("len", len);
+        if resolve {
+            fmt.field("account", &debug_account(unsafe { self.lookup_name() }));
+        }
+        fmt.finish()
+    }
+}
+
+/// The `Debug` output does not look up the account name, see [`SidRef::debug_resolved`].
+impl<'a> Debug for SidRef<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.fmt_debug(f, false)
+    }
+}
+
+impl Debug for DebugResolved<'_, SidRef<'_>> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt_debug(f, true)
     }
 }
 
 pub mod account {
-    use windows_sys::Win32::Security::{LookupAccountNameW, LookupAccountSidW, SID_NAME_USE};
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        sync::{
+            Arc, Mutex, RwLock,
+            atomic::{AtomicBool, Ordering},
+            mpsc,
+        },
+        thread,
+        time::{Duration, Instant},
+    };
+
+    use windows_sys::Win32::{
+        Foundation::{ERROR_NONE_MAPPED, ERROR_TIMEOUT},
+        Security::{
+            LookupAccountNameW, LookupAccountSidW, SID_NAME_USE, SidTypeAlias, SidTypeComputer, SidTypeDeletedAccount,
+            SidTypeDomain, SidTypeGroup, SidTypeInvalid, SidTypeLabel, SidTypeLogonSession, SidTypeUser,
+            SidTypeWellKnownGroup,
+        },
+    };
 
     use super::*;
 
+    /// How SIDs are resolved to account names.
+    ///
+    /// On a domain-joined machine whose domain controller is unreachable, resolving a domain SID
+    /// can block for many seconds. The policy bounds that wait, or avoids the network entirely.
+    /// It applies to [`Sid::lookup_name`], [`Sid::lookup_name_on`], [`Sid::debug_resolved`] and
+    /// [`Sid::display_with_names`], which resolve with the global policy (see
+    /// [`set_lookup_policy`]), and to [`Sid::lookup_name_with`] for a single call.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+    pub enum LookupPolicy {
+        /// Resolves every SID, waiting as long as Windows does.
+        #[default]
+        Online,
+        /// Resolves every SID, but fails with `ERROR_TIMEOUT` if it takes longer than the timeout.
+        ///
+        /// Well-known SIDs and accounts of the local machine are resolved on the calling thread.
+        /// Other lookups run one at a time on a single background thread, where a lookup
+        /// continues after its timeout. A SID whose lookup was started and timed out fails
+        /// immediately on later lookups, until [`clear_name_cache`] is called; a SID that timed
+        /// out while still queued behind another lookup is tried again.
+        Timeout(Duration),
+        /// Resolves well-known SIDs and accounts of the local machine only. Other SIDs fail
+        /// with `ERROR_NONE_MAPPED` without contacting any other machine.
+        Offline,
+    }
+
+    static LOOKUP_POLICY: RwLock<LookupPolicy> = RwLock::new(LookupPolicy::Online);
+
+    /// The names resolved by [`Sid::display_with_names`], `None` for SIDs that no account maps to.
+    static NAME_CACHE: Mutex<BTreeMap<Sid, Option<String>>> = Mutex::new(BTreeMap::new());
+
+    /// The SIDs whose lookup with [`LookupPolicy::Timeout`] timed out, with the machine they were
+    /// resolved on.
+    static TIMED_OUT: Mutex<BTreeSet<(Option<String>, Sid)>> = Mutex::new(BTreeSet::new());
+
+    /// The thread running lookups with [`LookupPolicy::Timeout`], started on first use.
+    static LOOKUP_WORKER: Mutex<Option<mpsc::Sender<LookupJob>>> = Mutex::new(None);
+
+    /// A lookup queued for the lookup worker.
+    struct LookupJob {
+        system: Option<String>,
+        sid: Sid,
+        /// When the caller stops waiting for the result.
+        deadline: Instant,
+        /// Set by the worker when it starts the lookup.
+        started: Arc<AtomicBool>,
+        reply: mpsc::Sender<Result<AccountLookup, WinError>>,
+    }
+
+    /// Returns a sender queueing lookups for the lookup worker, starting the worker if needed.
+    fn lookup_worker() -> mpsc::Sender<LookupJob> {
+        LOOKUP_WORKER
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_or_insert_with(|| {
+                let (sender, receiver) = mpsc::channel::<LookupJob>();
+                thread::spawn(move || {
+                    for job in receiver {
+                        // nobody waits for the result anymore
+                        if Instant::now() >= job.deadline {
+                            continue;
+                        }
+                        job.started.store(true, Ordering::SeqCst);
+                        let lookup =
+                            unsafe { lookup_account_sid(job.system.as_deref(), job.sid.as_bytes().as_ptr() as PSID) };
+                        let _ = job.reply.send(lookup);
+                    }
+                });
+                sender
+            })
+            .clone()
+    }
+
+    /// Returns the `DOMAIN\name` of a SID, resolving it only on first use.
+    ///
+    /// A failed lookup is cached only if an online lookup found no account for the SID. Other
+    /// failures (e.g., a timeout, an unreachable domain controller or a SID not resolved by
+    /// [`LookupPolicy::Offline`]) are retried on next use.
+    pub(crate) fn cached_name(sid: &Sid) -> Option<String> {
+        if let Some(name) = NAME_CACHE.lock().unwrap_or_else(|e| e.into_inner()).get(sid) {
+            return name.clone();
+        }
+        // resolved without holding the lock, a slow lookup must not block other threads
+        let policy = lookup_policy();
+        let name = match lookup_sid(None, sid, policy) {
+            Ok(lookup) if lookup.domain.is_empty() => Some(lookup.name),
+            Ok(lookup) => Some(format!(r"{}\{}", lookup.domain, lookup.name)),
+            Err(err) if err.code == ERROR_NONE_MAPPED && policy != LookupPolicy::Offline => None,
+            Err(_) => return None,
+        };
+        NAME_CACHE
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(sid.clone(), name.clone());
+        name
+    }
+
+    /// Clears the names cached by [`Sid::display_with_names`] and forgets the SIDs that timed out
+    /// with [`LookupPolicy::Timeout`], e.g., after accounts were renamed or a domain controller
+    /// became reachable again.
+    pub fn clear_name_cache() {
+        NAME_CACHE.lock().unwrap_or_else(|e| e.into_inner()).clear();
+        TIMED_OUT.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    /// Sets the policy used by lookups that do not specify one.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// use win_acl_rs::sid::account::{LookupPolicy, set_lookup_policy};
+    ///
+    /// // never let logging wait for a domain controller for more than a second
+    /// set_lookup_policy(LookupPolicy::Timeout(Duration::from_secs(1)));
+    /// ```
+    pub fn set_lookup_policy(policy: LookupPolicy) {
+        *LOOKUP_POLICY.write().unwrap_or_else(|e| e.into_inner()) = policy;
+    }
+
+    /// Returns the policy used by lookups that do not specify one ([`LookupPolicy::Online`]
+    /// unless changed with [`set_lookup_policy`]).
+    pub fn lookup_policy() -> LookupPolicy {
+        *LOOKUP_POLICY.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Looks up an account name from a SID according to a lookup policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `system` - The machine to resolve the SID on, or `None` for the local machine.
+    /// * `sid` - The SID to resolve.
+    /// * `policy` - How to resolve the SID.
+    pub(crate) fn lookup_sid(system: Option<&str>, sid: &Sid, policy: LookupPolicy) -> Result<AccountLookup, WinError> {
+        match policy {
+            LookupPolicy::Online => unsafe { lookup_account_sid(system, sid.as_bytes().as_ptr() as PSID) },
+            // local lookups do not wait for a domain controller, nor queue behind one that does
+            LookupPolicy::Timeout(_) if system.is_none() && is_local(sid) => unsafe {
+                lookup_account_sid(None, sid.as_bytes().as_ptr() as PSID)
+            },
+            LookupPolicy::Timeout(timeout) => {
+                let timed_out = || WinError::new(ERROR_TIMEOUT, Some(format!("Lookup of {} timed out", sid)));
+                let key = (system.map(str::to_owned), sid.clone());
+                if TIMED_OUT.lock().unwrap_or_else(|e| e.into_inner()).contains(&key) {
+                    return Err(timed_out());
+                }
+                let (reply, receiver) = mpsc::channel();
+                let started = Arc::new(AtomicBool::new(false));
+                let job = LookupJob {
+                    system: key.0.clone(),
+                    sid: key.1.clone(),
+                    deadline: Instant::now() + timeout,
+                    started: Arc::clone(&started),
+                    reply,
+                };
+                if lookup_worker().send(job).is_err() {
+                    return Err(timed_out());
+                }
+                receiver.recv_timeout(timeout).unwrap_or_else(|_| {
+                    // a lookup still queued behind a slow one says nothing about this SID
+                    if started.load(Ordering::SeqCst) {
+                        TIMED_OUT.lock().unwrap_or_else(|e| e.into_inner()).insert(key);
+                    }
+                    Err(timed_out())
+                })
+            }
+            LookupPolicy::Offline => {
+                if system.is_some() || !is_local(sid) {
+                    return Err(WinError::new(
+                        ERROR_NONE_MAPPED,
+                        Some(format!("{} is not resolved offline", sid)),
+                    ));
+                }
+                unsafe { lookup_account_sid(None, sid.as_bytes().as_ptr() as PSID) }
+            }
+        }
+    }
+
+    /// Checks whether the local security authority resolves a SID by itself: well-known SIDs
+    /// and accounts of the local machine.
+    fn is_local(sid: &Sid) -> bool {
+        const SECURITY_NT_NON_UNIQUE: u32 = 21;
+
+        if sid.identifier_authority() != IdentifierAuthority::NT
+            || sid.sub_authorities().first() != Some(&SECURITY_NT_NON_UNIQUE)
+        {
+            return true;
+        }
+        Sid::machine().is_ok_and(|machine| sid.same_domain(&machine).unwrap_or(false))
+    }
+
+    /// The kind of account a SID identifies.
+    ///
+    /// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/api/winnt/ne-winnt-sid_name_use)
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    pub enum SidNameUse {
+        /// A user account.
+        User,
+        /// A domain group.
+        Group,
+        /// A domain.
+        Domain,
+        /// A local group (alias), such as `BUILTIN\Administrators`.
+        Alias,
+        /// A well-known group, such as `Everyone`.
+        WellKnownGroup,
+        /// An account that has been deleted.
+        DeletedAccount,
+        /// An invalid SID.
+        Invalid,
+        /// A SID of an unknown kind.
+        Unknown,
+        /// A computer account.
+        Computer,
+        /// A mandatory integrity label.
+        Label,
+        /// A logon session.
+        LogonSession,
+    }
+
+    impl From<SID_NAME_USE> for SidNameUse {
+        // the SID_NAME_USE constants are named like enum variants
+        #[allow(non_upper_case_globals)]
+        fn from(value: SID_NAME_USE) -> Self {
+            match value {
+                SidTypeUser => SidNameUse::User,
+                SidTypeGroup => SidNameUse::Group,
+                SidTypeDomain => SidNameUse::Domain,
+                SidTypeAlias => SidNameUse::Alias,
+                SidTypeWellKnownGroup => SidNameUse::WellKnownGroup,
+                SidTypeDeletedAccount => SidNameUse::DeletedAccount,
+                SidTypeInvalid => SidNameUse::Invalid,
+                SidTypeComputer => SidNameUse::Computer,
+                SidTypeLabel => SidNameUse::Label,
+                SidTypeLogonSession => SidNameUse::LogonSession,
+                _ => SidNameUse::Unknown,
+            }
+        }
+    }
+
     /// The result of looking up an account name from a SID (or vice versa).
     #[derive(Debug, Clone)]
     pub struct AccountLookup {
@@ -581,8 +1642,10 @@ pub mod account {
         pub name: String,
         /// The domain name (e.g., "BUILTIN", "NT AUTHORITY", or the actual domain).
         pub domain: String,
-        /// The SID type indicating what kind of account this is (user, group, alias, etc.).
-        pub sid_type: SID_NAME_USE,
+        /// The kind of account (user, group, alias, etc.).
+        pub sid_type: SidNameUse,
+        /// The SID of the account.
+        pub sid: Sid,
     }
 
     /// Looks up a SID from an account name.
@@ -594,15 +1657,18 @@ pub mod account {
     ///
     /// # Arguments
     ///
+    /// * `system` - The machine to resolve the name on, or `None` for the local machine.
     /// * `account` - The account name to look up (e.g., "BUILTIN\\Administrators").
     ///
     /// # Returns
     ///
-    /// An `AccountLookup` containing the SID string representation, domain, and SID type.
-    pub(crate) unsafe fn lookup_account_name<S>(account: S) -> Result<AccountLookup, WinError>
+    /// An `AccountLookup` containing the account name (without the domain), domain, SID type and SID.
+    pub(crate) unsafe fn lookup_account_name<S>(system: Option<&str>, account: S) -> Result<AccountLookup, WinError>
     where
         S: AsRef<str>,
     {
+        let wide_system = system.map(WideCString::new);
+        let system_ptr = wide_system.as_ref().map_or(null(), |s| s.as_ptr());
         let wide_account = WideCString::new(account.as_ref());
 
         let mut sid_size = 0u32;
@@ -611,7 +1677,7 @@ pub mod account {
 
         unsafe {
             LookupAccountNameW(
-                null_mut(),
+                system_ptr,
                 wide_account.as_ptr(),
                 null_mut(),
                 &mut sid_size,
@@ -626,40 +1692,33 @@ pub mod account {
             return Err(err.into());
         }
 
-        let sid = unsafe { LocalAlloc(LMEM_FIXED, sid_size as usize) as PSID };
-        if sid.is_null() {
-            return Err(ERROR_OUTOFMEMORY.into());
+        let mut sid_buf = [0u8; SECURITY_MAX_SID_SIZE as usize];
+        if sid_size as usize > sid_buf.len() {
+            return Err(ERROR_INVALID_SID.into());
         }
-
         let mut domain_buf = vec![0u16; domain_size as usize];
 
         unsafe {
-            winapi_bool_call!(
-                LookupAccountNameW(
-                    null_mut(),
-                    wide_account.as_ptr(),
-                    sid,
-                    &mut sid_size,
-                    domain_buf.as_mut_ptr(),
-                    &mut domain_size,
-                    &mut sid_type,
-                ),
-                {
-                    assert_free!(sid, "account::lookup_account_name()");
-                }
-            )
+            winapi_bool_call!(LookupAccountNameW(
+                system_ptr,
+                wide_account.as_ptr(),
+                sid_buf.as_mut_ptr() as PSID,
+                &mut sid_size,
+                domain_buf.as_mut_ptr(),
+                &mut domain_size,
+                &mut sid_type,
+            ))
         };
 
-        let domain = String::from_utf16_lossy(&domain_buf[..domain_size as usize]);
-
-        let sid_ref = unsafe { SidRef::from_ptr(sid as *const SID) };
-        let name = sid_ref.to_string()?;
-
-        unsafe {
-            assert_free!(sid, "account::lookup_account_name()");
-        };
+        let account = account.as_ref();
+        let name = account.rsplit_once('\\').map_or(account, |(_, name)| name);
 
-        Ok(AccountLookup { name, domain, sid_type })
+        Ok(AccountLookup {
+            name: name.to_owned(),
+            domain: String::from_utf16_lossy(&domain_buf[..domain_size as usize]),
+            sid_type: sid_type.into(),
+            sid: Sid::from_bytes(&sid_buf[..sid_size as usize])?,
+        })
     }
 
     /// Looks up an account name from a SID.
@@ -670,19 +1729,22 @@ pub mod account {
     ///
     /// # Arguments
     ///
+    /// * `system` - The machine to resolve the SID on, or `None` for the local machine.
     /// * `sid` - A pointer to a valid SID structure.
     ///
     /// # Returns
     ///
     /// An `AccountLookup` containing the account name, domain, and SID type.
-    pub(crate) unsafe fn lookup_account_sid(sid: PSID) -> Result<AccountLookup, WinError> {
+    pub(crate) unsafe fn lookup_account_sid(system: Option<&str>, sid: PSID) -> Result<AccountLookup, WinError> {
+        let wide_system = system.map(WideCString::new);
+        let system_ptr = wide_system.as_ref().map_or(null(), |s| s.as_ptr());
         let mut name_size = 0u32;
         let mut domain_size = 0u32;
         let mut sid_type: SID_NAME_USE = 0;
 
         unsafe {
             LookupAccountSidW(
-                null_mut(),
+                system_ptr,
                 sid,
                 null_mut(),
                 &mut name_size,
@@ -702,7 +1764,7 @@ pub mod account {
 
         unsafe {
             winapi_bool_call!(LookupAccountSidW(
-                null_mut(),
+                system_ptr,
                 sid,
                 name_buf.as_mut_ptr(),
                 &mut name_size,
@@ -715,7 +1777,8 @@ pub mod account {
         Ok(AccountLookup {
             name: String::from_utf16_lossy(&name_buf[..name_size as usize]),
             domain: String::from_utf16_lossy(&domain_buf[..domain_size as usize]),
-            sid_type,
+            sid_type: sid_type.into(),
+            sid: Sid::from_bytes(unsafe { SidRef::from_ptr(sid as *const SID) }.as_bytes())?,
         })
     }
 }
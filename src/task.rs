@@ -0,0 +1,127 @@
+//! Scheduled task security.
+//!
+//! The Task Scheduler keeps two copies of a task's security: the task definition file under
+//! `%SystemRoot%\System32\Tasks` and the `SD` value of the task's entry in the task cache
+//! (`HKLM\SOFTWARE\Microsoft\Windows NT\CurrentVersion\Schedule\TaskCache\Tree`). The latter
+//! controls who can read, run and modify the task through the Task Scheduler service.
+//!
+//! Modifying either location generally requires running as `SYSTEM` or an Administrator.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use win_acl_rs::task::ScheduledTask;
+//!
+//! let task = ScheduledTask::new(r"\Microsoft\Windows\Defrag\ScheduledDefrag");
+//! println!("definition file: {}", task.file_path().display());
+//!
+//! let sd = task.security()?;
+//! println!("{}", sd.as_sd_string()?);
+//! # Ok::<(), win_acl_rs::error::WinError>(())
+//! ```
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    SE_FILE_OBJECT,
+    error::WinError,
+    registry::{self, Hive, RegistryPath},
+    sd::{SecurityDescriptor, SecurityInformation},
+};
+
+const TASK_CACHE_TREE: &str = r"SOFTWARE\Microsoft\Windows NT\CurrentVersion\Schedule\TaskCache\Tree";
+const SD_VALUE: &str = "SD";
+
+/// A scheduled task identified by its path in the Task Scheduler library.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ScheduledTask {
+    name: String,
+}
+
+impl ScheduledTask {
+    /// Creates a scheduled task reference.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The path of the task in the library (e.g., `\Microsoft\Windows\Defrag\ScheduledDefrag`).
+    ///   The leading backslash is optional.
+    pub fn new<S>(name: S) -> Self
+    where
+        S: AsRef<str>,
+    {
+        Self {
+            name: format!(r"\{}", name.as_ref().trim_matches('\\')),
+        }
+    }
+
+    /// Returns the path of the task in the library, always starting with a backslash.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the path of the task definition file under `%SystemRoot%\System32\Tasks`.
+    pub fn file_path(&self) -> PathBuf {
+        let system_root = env::var_os("SystemRoot").unwrap_or_else(|| r"C:\Windows".into());
+        Path::new(&system_root)
+            .join("System32")
+            .join("Tasks")
+            .join(self.relative_name())
+    }
+
+    /// Returns the task cache registry key of the task.
+    pub fn tree_key(&self) -> RegistryPath {
+        RegistryPath::new(
+            Hive::LocalMachine,
+            format!(r"{}\{}", TASK_CACHE_TREE, self.relative_name()),
+        )
+    }
+
+    /// Reads the security descriptor controlling access to the task from the task cache.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the task does not exist, the registry value cannot be read
+    /// or does not contain a valid security descriptor.
+    pub fn security(&self) -> Result<SecurityDescriptor, WinError> {
+        registry::read_sd_value(&self.tree_key(), SD_VALUE)
+    }
+
+    /// Writes the security descriptor controlling access to the task to the task cache.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the task does not exist or the registry value cannot be written
+    /// (typically because the process is not running as `SYSTEM` or an Administrator).
+    pub fn set_security(&self, sd: &SecurityDescriptor) -> Result<(), WinError> {
+        registry::write_sd_value(&self.tree_key(), SD_VALUE, sd)
+    }
+
+    /// Reads the security descriptor of the task definition file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file does not exist or its security cannot be read.
+    pub fn file_security(&self) -> Result<SecurityDescriptor, WinError> {
+        SecurityDescriptor::from_path(self.file_path())
+    }
+
+    /// Writes the selected components of a security descriptor to the task definition file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file does not exist or its security cannot be written.
+    pub fn apply_file_security(
+        &self,
+        sd: &SecurityDescriptor,
+        components: SecurityInformation,
+    ) -> Result<(), WinError> {
+        sd.set_named(self.file_path().as_os_str(), SE_FILE_OBJECT, components)
+    }
+
+    fn relative_name(&self) -> &str {
+        self.name.trim_start_matches('\\')
+    }
+}
@@ -1,6 +1,7 @@
 use std::{
     ffi::{OsStr, OsString},
     os::windows::ffi::{OsStrExt, OsStringExt},
+    sync::atomic::{Ordering, compiler_fence},
 };
 
 pub struct WideCString {
@@ -92,3 +93,32 @@ impl AsRef<WideCString> for WideCString {
         self
     }
 }
+
+/// A null-terminated wide string holding a secret (e.g., a password), zeroed when dropped.
+pub struct SecretWideCString {
+    inner: Vec<u16>,
+}
+
+impl SecretWideCString {
+    pub fn new(s: &str) -> Self {
+        // allocated at its final size, growing would leave copies of the secret in freed memory
+        let mut inner = Vec::with_capacity(s.encode_utf16().count() + 1);
+        inner.extend(s.encode_utf16());
+        inner.push(0);
+        Self { inner }
+    }
+
+    pub fn as_ptr(&self) -> *const u16 {
+        self.inner.as_ptr()
+    }
+}
+
+impl Drop for SecretWideCString {
+    fn drop(&mut self) {
+        for c in self.inner.iter_mut() {
+            // volatile, so the writes to memory about to be freed are not optimized out
+            unsafe { std::ptr::write_volatile(c, 0) };
+        }
+        compiler_fence(Ordering::SeqCst);
+    }
+}
@@ -18,6 +18,11 @@
 //! # Ok::<(), win_acl_rs::error::WinError>(())
 //! ```
 
+use std::{
+    ptr::{null, null_mut},
+    slice::from_raw_parts,
+};
+
 pub use windows_sys::Win32::Security::{
     SID_NAME_USE, WinAccountAdministratorSid, WinAccountCertAdminsSid, WinAccountCloneableControllersSid,
     WinAccountComputersSid, WinAccountControllersSid, WinAccountDefaultSystemManagedSid, WinAccountDomainAdminsSid,
@@ -54,3 +59,453 @@ pub use windows_sys::Win32::Security::{
     WinSystemLabelSid, WinTerminalServerSid, WinThisOrganizationCertificateSid, WinThisOrganizationSid,
     WinUntrustedLabelSid, WinUserModeDriversSid, WinWorldSid, WinWriteRestrictedCodeSid,
 };
+use windows_sys::Win32::{
+    Foundation::{ERROR_INVALID_PARAMETER, ERROR_MORE_DATA, ERROR_NO_SUCH_DOMAIN, ERROR_SUCCESS},
+    NetworkManagement::NetManagement::{
+        LOCALGROUP_MEMBERS_INFO_2, MAX_PREFERRED_LENGTH, NetApiBufferFree, NetLocalGroupGetMembers,
+    },
+    Security::WELL_KNOWN_SID_TYPE,
+};
+
+use crate::{
+    error::WinError,
+    lsa::LsaPolicy,
+    sid::{AsSidRef, Sid, SidRef, account::AccountLookup},
+    utils::WideCString,
+};
+
+/// SDDL SID aliases that map to a fixed SID.
+///
+/// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/secauthz/sid-strings)
+const SDDL_ALIASES: &[(&str, &str)] = &[
+    ("AA", "S-1-5-32-579"),
+    ("AC", "S-1-15-2-1"),
+    ("AN", "S-1-5-7"),
+    ("AO", "S-1-5-32-548"),
+    ("AS", "S-1-18-1"),
+    ("AU", "S-1-5-11"),
+    ("BA", "S-1-5-32-544"),
+    ("BG", "S-1-5-32-546"),
+    ("BO", "S-1-5-32-551"),
+    ("BU", "S-1-5-32-545"),
+    ("CD", "S-1-5-32-574"),
+    ("CG", "S-1-3-1"),
+    ("CO", "S-1-3-0"),
+    ("CY", "S-1-5-32-569"),
+    ("ED", "S-1-5-9"),
+    ("ER", "S-1-5-32-573"),
+    ("ES", "S-1-5-32-576"),
+    ("HA", "S-1-5-32-578"),
+    ("HI", "S-1-16-12288"),
+    ("IS", "S-1-5-32-568"),
+    ("IU", "S-1-5-4"),
+    ("LS", "S-1-5-19"),
+    ("LU", "S-1-5-32-559"),
+    ("LW", "S-1-16-4096"),
+    ("ME", "S-1-16-8192"),
+    ("MP", "S-1-16-8448"),
+    ("MS", "S-1-5-32-577"),
+    ("MU", "S-1-5-32-558"),
+    ("NO", "S-1-5-32-556"),
+    ("NS", "S-1-5-20"),
+    ("NU", "S-1-5-2"),
+    ("OW", "S-1-3-4"),
+    ("PO", "S-1-5-32-550"),
+    ("PS", "S-1-5-10"),
+    ("PU", "S-1-5-32-547"),
+    ("RA", "S-1-5-32-575"),
+    ("RC", "S-1-5-12"),
+    ("RD", "S-1-5-32-555"),
+    ("RE", "S-1-5-32-552"),
+    ("RM", "S-1-5-32-580"),
+    ("RU", "S-1-5-32-554"),
+    ("SI", "S-1-16-16384"),
+    ("SO", "S-1-5-32-549"),
+    ("SS", "S-1-18-2"),
+    ("SU", "S-1-5-6"),
+    ("SY", "S-1-5-18"),
+    ("UD", "S-1-5-84-0-0-0-0-0"),
+    ("WD", "S-1-1-0"),
+    ("WR", "S-1-5-33"),
+];
+
+/// English names of SIDs with a fixed value, as `LookupAccountSidW` reports them on an English
+/// system.
+const FRIENDLY_NAMES: &[(&str, &str)] = &[
+    ("S-1-0-0", "NULL SID"),
+    ("S-1-1-0", "Everyone"),
+    ("S-1-2-0", "LOCAL"),
+    ("S-1-2-1", "CONSOLE LOGON"),
+    ("S-1-3-0", "CREATOR OWNER"),
+    ("S-1-3-1", "CREATOR GROUP"),
+    ("S-1-3-2", "CREATOR OWNER SERVER"),
+    ("S-1-3-3", "CREATOR GROUP SERVER"),
+    ("S-1-3-4", "OWNER RIGHTS"),
+    ("S-1-5-1", r"NT AUTHORITY\DIALUP"),
+    ("S-1-5-2", r"NT AUTHORITY\NETWORK"),
+    ("S-1-5-3", r"NT AUTHORITY\BATCH"),
+    ("S-1-5-4", r"NT AUTHORITY\INTERACTIVE"),
+    ("S-1-5-6", r"NT AUTHORITY\SERVICE"),
+    ("S-1-5-7", r"NT AUTHORITY\ANONYMOUS LOGON"),
+    ("S-1-5-8", r"NT AUTHORITY\PROXY"),
+    ("S-1-5-9", r"NT AUTHORITY\ENTERPRISE DOMAIN CONTROLLERS"),
+    ("S-1-5-10", r"NT AUTHORITY\SELF"),
+    ("S-1-5-11", r"NT AUTHORITY\Authenticated Users"),
+    ("S-1-5-12", r"NT AUTHORITY\RESTRICTED"),
+    ("S-1-5-13", r"NT AUTHORITY\TERMINAL SERVER USER"),
+    ("S-1-5-14", r"NT AUTHORITY\REMOTE INTERACTIVE LOGON"),
+    ("S-1-5-15", r"NT AUTHORITY\This Organization"),
+    ("S-1-5-17", r"NT AUTHORITY\IUSR"),
+    ("S-1-5-18", r"NT AUTHORITY\SYSTEM"),
+    ("S-1-5-19", r"NT AUTHORITY\LOCAL SERVICE"),
+    ("S-1-5-20", r"NT AUTHORITY\NETWORK SERVICE"),
+    ("S-1-5-32-544", r"BUILTIN\Administrators"),
+    ("S-1-5-32-545", r"BUILTIN\Users"),
+    ("S-1-5-32-546", r"BUILTIN\Guests"),
+    ("S-1-5-32-547", r"BUILTIN\Power Users"),
+    ("S-1-5-32-548", r"BUILTIN\Account Operators"),
+    ("S-1-5-32-549", r"BUILTIN\Server Operators"),
+    ("S-1-5-32-550", r"BUILTIN\Print Operators"),
+    ("S-1-5-32-551", r"BUILTIN\Backup Operators"),
+    ("S-1-5-32-552", r"BUILTIN\Replicator"),
+    ("S-1-5-32-554", r"BUILTIN\Pre-Windows 2000 Compatible Access"),
+    ("S-1-5-32-555", r"BUILTIN\Remote Desktop Users"),
+    ("S-1-5-32-556", r"BUILTIN\Network Configuration Operators"),
+    ("S-1-5-32-558", r"BUILTIN\Performance Monitor Users"),
+    ("S-1-5-32-559", r"BUILTIN\Performance Log Users"),
+    ("S-1-5-32-560", r"BUILTIN\Windows Authorization Access Group"),
+    ("S-1-5-32-561", r"BUILTIN\Terminal Server License Servers"),
+    ("S-1-5-32-562", r"BUILTIN\Distributed COM Users"),
+    ("S-1-5-32-568", r"BUILTIN\IIS_IUSRS"),
+    ("S-1-5-32-569", r"BUILTIN\Cryptographic Operators"),
+    ("S-1-5-32-573", r"BUILTIN\Event Log Readers"),
+    ("S-1-5-32-574", r"BUILTIN\Certificate Service DCOM Access"),
+    ("S-1-5-32-575", r"BUILTIN\RDS Remote Access Servers"),
+    ("S-1-5-32-576", r"BUILTIN\RDS Endpoint Servers"),
+    ("S-1-5-32-577", r"BUILTIN\RDS Management Servers"),
+    ("S-1-5-32-578", r"BUILTIN\Hyper-V Administrators"),
+    ("S-1-5-32-579", r"BUILTIN\Access Control Assistance Operators"),
+    ("S-1-5-32-580", r"BUILTIN\Remote Management Users"),
+    ("S-1-5-32-583", r"BUILTIN\Device Owners"),
+    ("S-1-5-33", r"NT AUTHORITY\WRITE RESTRICTED"),
+    ("S-1-5-64-10", r"NT AUTHORITY\NTLM Authentication"),
+    ("S-1-5-64-14", r"NT AUTHORITY\SChannel Authentication"),
+    ("S-1-5-64-21", r"NT AUTHORITY\Digest Authentication"),
+    ("S-1-5-80-0", r"NT SERVICE\ALL SERVICES"),
+    ("S-1-5-84-0-0-0-0-0", r"NT AUTHORITY\USER MODE DRIVERS"),
+    ("S-1-5-113", r"NT AUTHORITY\Local account"),
+    (
+        "S-1-5-114",
+        r"NT AUTHORITY\Local account and member of Administrators group",
+    ),
+    ("S-1-5-1000", r"NT AUTHORITY\Other Organization"),
+    ("S-1-15-2-1", r"APPLICATION PACKAGE AUTHORITY\ALL APPLICATION PACKAGES"),
+    (
+        "S-1-15-2-2",
+        r"APPLICATION PACKAGE AUTHORITY\ALL RESTRICTED APPLICATION PACKAGES",
+    ),
+    ("S-1-16-0", r"Mandatory Label\Untrusted Mandatory Level"),
+    ("S-1-16-4096", r"Mandatory Label\Low Mandatory Level"),
+    ("S-1-16-8192", r"Mandatory Label\Medium Mandatory Level"),
+    ("S-1-16-8448", r"Mandatory Label\Medium Plus Mandatory Level"),
+    ("S-1-16-12288", r"Mandatory Label\High Mandatory Level"),
+    ("S-1-16-16384", r"Mandatory Label\System Mandatory Level"),
+    ("S-1-16-20480", r"Mandatory Label\Protected Process Mandatory Level"),
+    ("S-1-18-1", "Authentication authority asserted identity"),
+    ("S-1-18-2", "Service asserted identity"),
+];
+
+/// SDDL SID aliases that are relative to the domain of the computer (or its forest root domain).
+const SDDL_DOMAIN_ALIASES: &[&str] = &[
+    "AP", "CA", "CN", "DA", "DC", "DD", "DG", "DU", "EA", "EK", "KA", "LA", "LG", "PA", "RO", "RS", "SA",
+];
+
+/// Resolves a two-letter SDDL SID alias (e.g., `BA`, `SY`, `WD`) to a SID.
+///
+/// Domain-relative aliases (e.g., `DA` for Domain Admins) are resolved against the domain of
+/// the computer.
+///
+/// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/secauthz/sid-strings)
+///
+/// # Errors
+///
+/// Returns an error if the alias is unknown or a domain-relative alias cannot be resolved.
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::wellknown::from_sddl_alias;
+///
+/// let admins = from_sddl_alias("BA")?;
+/// assert_eq!(admins.to_string()?, "S-1-5-32-544");
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+pub fn from_sddl_alias<S>(alias: S) -> Result<Sid, WinError>
+where
+    S: AsRef<str>,
+{
+    let alias = alias.as_ref().to_ascii_uppercase();
+    if let Some((_, sid)) = SDDL_ALIASES.iter().find(|(a, _)| *a == alias) {
+        return Sid::from_string(sid);
+    }
+    if SDDL_DOMAIN_ALIASES.contains(&alias.as_str()) {
+        // ConvertStringSidToSidW resolves domain-relative aliases itself
+        return Sid::from_string(&alias);
+    }
+    Err(WinError::new(
+        ERROR_INVALID_PARAMETER,
+        Some(format!("Unknown SDDL SID alias: {}", alias)),
+    ))
+}
+
+/// Returns the two-letter SDDL alias of a SID, if it has one.
+///
+/// Only aliases that map to a fixed SID are considered; domain-relative aliases are not
+/// reported, as they depend on the domain of the computer.
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::{sid::Sid, wellknown::sddl_alias_for};
+///
+/// let sid = Sid::from_string("S-1-5-18")?;
+/// assert_eq!(sddl_alias_for(&sid), Some("SY"));
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+pub fn sddl_alias_for<'a, S>(sid: &'a S) -> Option<&'static str>
+where
+    S: AsSidRef<'a>,
+{
+    let sid = sid.as_sid_ref().to_string().ok()?;
+    SDDL_ALIASES.iter().find(|(_, s)| *s == sid).map(|(alias, _)| *alias)
+}
+
+/// Returns the English name of a well-known SID without calling `LookupAccountSidW`.
+///
+/// The name is in the `DOMAIN\name` form (or just `name` for principals without a domain, such
+/// as `Everyone`). The bundled table covers SIDs with a fixed value only; domain and local
+/// accounts still need [`Sid::lookup_name`]. Unlike a lookup, it never touches the network and
+/// does not depend on the display language of the system.
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::{sid::Sid, wellknown::friendly_name};
+///
+/// let sid = Sid::from_string("S-1-5-32-544")?;
+/// assert_eq!(friendly_name(&sid), Some(r"BUILTIN\Administrators"));
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+pub fn friendly_name<'a, S>(sid: &'a S) -> Option<&'static str>
+where
+    S: AsSidRef<'a>,
+{
+    let sid = sid.as_sid_ref().to_string().ok()?;
+    FRIENDLY_NAMES.iter().find(|(s, _)| *s == sid).map(|(_, name)| *name)
+}
+
+/// A well-known group of an Active Directory domain.
+///
+/// See [`domain_group`] to obtain the SID of the group in the domain the computer is joined to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DomainGroup {
+    /// Domain Admins (RID 512).
+    Admins,
+    /// Domain Users (RID 513).
+    Users,
+    /// Domain Guests (RID 514).
+    Guests,
+    /// Domain Computers (RID 515).
+    Computers,
+    /// Domain Controllers (RID 516).
+    Controllers,
+    /// Cert Publishers (RID 517).
+    CertPublishers,
+    /// Schema Admins (RID 518).
+    SchemaAdmins,
+    /// Enterprise Admins (RID 519).
+    EnterpriseAdmins,
+    /// Group Policy Creator Owners (RID 520).
+    PolicyAdmins,
+    /// Read-only Domain Controllers (RID 521).
+    ReadonlyControllers,
+    /// Cloneable Domain Controllers (RID 522).
+    CloneableControllers,
+    /// Protected Users (RID 525).
+    ProtectedUsers,
+    /// Key Admins (RID 526).
+    KeyAdmins,
+    /// Enterprise Key Admins (RID 527).
+    EnterpriseKeyAdmins,
+    /// RAS and IAS Servers (RID 553).
+    RasAndIasServers,
+}
+
+impl DomainGroup {
+    /// Returns the relative identifier (RID) of the group within its domain.
+    pub fn rid(&self) -> u32 {
+        match self {
+            DomainGroup::Admins => 512,
+            DomainGroup::Users => 513,
+            DomainGroup::Guests => 514,
+            DomainGroup::Computers => 515,
+            DomainGroup::Controllers => 516,
+            DomainGroup::CertPublishers => 517,
+            DomainGroup::SchemaAdmins => 518,
+            DomainGroup::EnterpriseAdmins => 519,
+            DomainGroup::PolicyAdmins => 520,
+            DomainGroup::ReadonlyControllers => 521,
+            DomainGroup::CloneableControllers => 522,
+            DomainGroup::ProtectedUsers => 525,
+            DomainGroup::KeyAdmins => 526,
+            DomainGroup::EnterpriseKeyAdmins => 527,
+            DomainGroup::RasAndIasServers => 553,
+        }
+    }
+
+    /// Returns the matching well-known SID type.
+    pub fn well_known_sid_type(&self) -> WELL_KNOWN_SID_TYPE {
+        match self {
+            DomainGroup::Admins => WinAccountDomainAdminsSid,
+            DomainGroup::Users => WinAccountDomainUsersSid,
+            DomainGroup::Guests => WinAccountDomainGuestsSid,
+            DomainGroup::Computers => WinAccountComputersSid,
+            DomainGroup::Controllers => WinAccountControllersSid,
+            DomainGroup::CertPublishers => WinAccountCertAdminsSid,
+            DomainGroup::SchemaAdmins => WinAccountSchemaAdminsSid,
+            DomainGroup::EnterpriseAdmins => WinAccountEnterpriseAdminsSid,
+            DomainGroup::PolicyAdmins => WinAccountPolicyAdminsSid,
+            DomainGroup::ReadonlyControllers => WinAccountReadonlyControllersSid,
+            DomainGroup::CloneableControllers => WinAccountCloneableControllersSid,
+            DomainGroup::ProtectedUsers => WinAccountProtectedUsersSid,
+            DomainGroup::KeyAdmins => WinAccountKeyAdminsSid,
+            DomainGroup::EnterpriseKeyAdmins => WinAccountEnterpriseKeyAdminsSid,
+            DomainGroup::RasAndIasServers => WinAccountRasAndIasServersSid,
+        }
+    }
+}
+
+/// Returns the SID of a well-known group in the domain the computer is joined to.
+///
+/// The domain SID is detected automatically using the local LSA policy.
+///
+/// # Errors
+///
+/// Returns an error if the computer is not joined to a domain or the domain SID cannot be queried.
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::wellknown::{DomainGroup, domain_group};
+///
+/// let domain_admins = domain_group(DomainGroup::Admins)?;
+/// println!("Domain Admins: {}", domain_admins);
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+pub fn domain_group(group: DomainGroup) -> Result<Sid, WinError> {
+    let domain_sid = LsaPolicy::open()?.primary_domain_sid()?.ok_or_else(|| {
+        WinError::new(
+            ERROR_NO_SUCH_DOMAIN,
+            Some("The computer is not joined to a domain".to_owned()),
+        )
+    })?;
+    domain_group_of(group, &domain_sid)
+}
+
+/// Returns the SID of a well-known group in a specific domain.
+///
+/// # Arguments
+///
+/// * `group` - The well-known domain group.
+/// * `domain_sid` - The SID of the domain (`S-1-5-21-...`).
+///
+/// # Errors
+///
+/// Returns an error if `domain_sid` is not a domain SID.
+pub fn domain_group_of<'a, S>(group: DomainGroup, domain_sid: &'a S) -> Result<Sid, WinError>
+where
+    S: AsSidRef<'a>,
+{
+    Sid::from_well_known_sid_and_domain(group.well_known_sid_type(), Some(domain_sid.as_sid_ref()))
+}
+
+/// Lists the members of a local group with `NetLocalGroupGetMembers`.
+///
+/// Expanding the group ACEs of a descriptor into the users they cover answers who effectively
+/// has access to an object. Only direct members are listed; members that are domain groups are
+/// not expanded further.
+///
+/// # Arguments
+///
+/// * `group` - The name of the local group. Group names are localized, resolve a well-known
+///   SID with [`Sid::lookup_name`] to get the name on any system.
+///
+/// # Errors
+///
+/// Returns `ERROR_NO_SUCH_ALIAS` (1376) if the group does not exist, or an error if the members
+/// cannot be listed.
+///
+/// see [MSDN](https://learn.microsoft.com/en-us/windows/win32/api/lmaccess/nf-lmaccess-netlocalgroupgetmembers)
+///
+/// # Examples
+///
+/// ```no_run
+/// use win_acl_rs::wellknown::local_group_members;
+///
+/// for member in local_group_members("Administrators")? {
+///     println!(r"{}\{} ({})", member.domain, member.name, member.sid);
+/// }
+/// # Ok::<(), win_acl_rs::error::WinError>(())
+/// ```
+pub fn local_group_members<S>(group: S) -> Result<Vec<AccountLookup>, WinError>
+where
+    S: AsRef<str>,
+{
+    let wide_group = WideCString::new(group.as_ref());
+    let mut members = Vec::new();
+    let mut resume_handle = 0usize;
+    loop {
+        let mut buffer: *mut u8 = null_mut();
+        let mut entries_read = 0u32;
+        let mut total_entries = 0u32;
+        let status = unsafe {
+            NetLocalGroupGetMembers(
+                null(),
+                wide_group.as_ptr(),
+                2,
+                &mut buffer,
+                MAX_PREFERRED_LENGTH,
+                &mut entries_read,
+                &mut total_entries,
+                &mut resume_handle,
+            )
+        };
+        if status != ERROR_SUCCESS && status != ERROR_MORE_DATA {
+            return Err(status.into());
+        }
+        if buffer.is_null() {
+            break;
+        }
+
+        let entries = unsafe { from_raw_parts(buffer as *const LOCALGROUP_MEMBERS_INFO_2, entries_read as usize) };
+        let result = entries.iter().map(group_member).collect::<Result<Vec<_>, _>>();
+        unsafe { NetApiBufferFree(buffer as _) };
+        members.extend(result?);
+
+        if status != ERROR_MORE_DATA {
+            break;
+        }
+    }
+    Ok(members)
+}
+
+fn group_member(entry: &LOCALGROUP_MEMBERS_INFO_2) -> Result<AccountLookup, WinError> {
+    let domain_and_name = WideCString::from_wide_null_ptr(entry.lgrmi2_domainandname).as_string();
+    let (domain, name) = domain_and_name.split_once('\\').unwrap_or(("", &domain_and_name));
+    Ok(AccountLookup {
+        name: name.to_owned(),
+        domain: domain.to_owned(),
+        sid_type: entry.lgrmi2_sidusage.into(),
+        sid: Sid::from_bytes(unsafe { SidRef::from_ptr(entry.lgrmi2_sid as _) }.as_bytes())?,
+    })
+}
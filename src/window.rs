@@ -0,0 +1,88 @@
+//! Window station and desktop handles for the `SE_WINDOW_OBJECT` security functions.
+
+use std::{
+    ffi::OsStr,
+    os::windows::io::{AsRawHandle, RawHandle},
+};
+
+use windows_sys::Win32::{
+    Foundation::{FALSE, GetLastError, HANDLE},
+    System::{
+        StationsAndDesktops::{CloseDesktop, CloseWindowStation, OpenDesktopW, OpenWindowStationW},
+        SystemServices::ACCESS_SYSTEM_SECURITY,
+    },
+};
+
+use crate::{error::WinError, mask::AccessMask, sd::SecurityInformation, utils::WideCString};
+
+/// An open window station or desktop, closed when dropped.
+#[derive(Debug)]
+pub(crate) struct WindowObject {
+    handle: HANDLE,
+    desktop: bool,
+}
+
+impl WindowObject {
+    /// Opens a window station (e.g., `WinSta0`).
+    pub(crate) fn open_window_station(name: &str, access: u32) -> Result<Self, WinError> {
+        let name = WideCString::new(OsStr::new(name));
+        let handle = unsafe { OpenWindowStationW(name.as_ptr(), FALSE, access) };
+        Self::from_opened(handle, false)
+    }
+
+    /// Opens a desktop (e.g., `Default`) of the window station of the calling process.
+    pub(crate) fn open_desktop(name: &str, access: u32) -> Result<Self, WinError> {
+        let name = WideCString::new(OsStr::new(name));
+        let handle = unsafe { OpenDesktopW(name.as_ptr(), 0, FALSE, access) };
+        Self::from_opened(handle, true)
+    }
+
+    fn from_opened(handle: HANDLE, desktop: bool) -> Result<Self, WinError> {
+        if handle.is_null() {
+            return Err(unsafe { GetLastError() }.into());
+        }
+        Ok(Self { handle, desktop })
+    }
+}
+
+impl AsRawHandle for WindowObject {
+    fn as_raw_handle(&self) -> RawHandle {
+        self.handle as RawHandle
+    }
+}
+
+impl Drop for WindowObject {
+    fn drop(&mut self) {
+        unsafe {
+            if self.desktop {
+                CloseDesktop(self.handle);
+            } else {
+                CloseWindowStation(self.handle);
+            }
+        }
+    }
+}
+
+/// Returns the access needed to read the selected components.
+pub(crate) fn read_access(components: SecurityInformation) -> u32 {
+    let mut access = AccessMask::READ_CONTROL.0;
+    if components.contains(SecurityInformation::SACL) {
+        access |= ACCESS_SYSTEM_SECURITY;
+    }
+    access
+}
+
+/// Returns the access needed to write the selected components.
+pub(crate) fn write_access(components: SecurityInformation) -> u32 {
+    let mut access = 0;
+    if components.contains(SecurityInformation::DACL) {
+        access |= AccessMask::WRITE_DAC.0;
+    }
+    if components.contains(SecurityInformation::OWNER) || components.contains(SecurityInformation::GROUP) {
+        access |= AccessMask::WRITE_OWNER.0;
+    }
+    if components.contains(SecurityInformation::SACL) {
+        access |= ACCESS_SYSTEM_SECURITY;
+    }
+    access
+}
@@ -3,12 +3,22 @@
 use std::str::FromStr;
 
 use win_acl_rs::{
-    acl::{AceType::AccessAllowed, Acl},
-    mask::{FileAccess, Mask},
-    sd::SecurityDescriptor,
+    acl::{
+        self, AceEntry, AceFlags, AcePayload,
+        AceType::{self, AccessAllowed},
+        Acl, AclIndex, AclIssue,
+    },
+    audit::AuditOn,
+    guid::Guid,
+    integrity::{IntegrityLevel, MandatoryLabel, MandatoryPolicy},
+    mask::{AccessMask, FileAccess, Mask},
+    sd::{SecurityDescriptor, SecurityDescriptorBuilder},
     sid::{AsSidRef, Sid},
 };
-use windows_sys::Win32::Foundation::GENERIC_ALL;
+use windows_sys::Win32::{
+    Foundation::{ERROR_INVALID_ACL, ERROR_INVALID_PARAMETER, GENERIC_ALL},
+    Security::{ACL_REVISION, ACL_REVISION_DS},
+};
 
 fn create_sd() -> SecurityDescriptor {
     const TEST_SD_STRING: &str = "O:S-1-5-21-1402048822-409899687-2319524958-1001G:S-1-5-21-1402048822-409899687-2319524958-1001D:(A;ID;FA;;;SY)(A;ID;FA;;;BA)(A;ID;FA;;;S-1-5-21-1402048822-409899687-2319524958-1001)";
@@ -67,7 +77,7 @@ fn test_mask_and_type() {
     assert_eq!(ace.mask(), mask.as_u32());
 
     acl.remove_ace(0).unwrap();
-    acl.allow(0x10000000, &sid).unwrap();
+    acl.allow(AccessMask::from(0x10000000), &sid).unwrap();
 
     let ace = acl.into_iter().next().unwrap();
     assert_eq!(ace.ace_type(), AccessAllowed);
@@ -104,3 +114,825 @@ fn test_add_remove_ace() {
     assert!(acl.is_valid());
     assert_eq!(acl.ace_count(), 1);
 }
+
+#[test]
+fn test_try_iter() {
+    let sd = create_sd();
+    let acl = sd.dacl().unwrap();
+
+    let aces = acl.try_iter().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(aces.len() as u32, acl.ace_count());
+
+    let empty = Acl::empty().unwrap();
+    assert_eq!(empty.try_iter().count(), 0);
+}
+
+#[test]
+fn test_acl_index() {
+    let sd = create_sd();
+    let dacl = sd.dacl().unwrap();
+    let index = AclIndex::new(&dacl).unwrap();
+    assert_eq!(index.sid_count(), 3);
+    assert_eq!(index.by_type(AccessAllowed).count(), 3);
+    assert_eq!(index.by_type(AceType::AccessDenied).count(), 0);
+
+    let system = Sid::from_string("S-1-5-18").unwrap();
+    let aces: Vec<_> = index.by_sid(&system).collect();
+    assert_eq!(aces.len(), 1);
+    assert_eq!(aces[0].sid().unwrap(), system);
+    assert_eq!(index.mask_for(&system, AccessAllowed), FileAccess::FULL.as_u32());
+    assert_eq!(index.mask_for(&system, AceType::AccessDenied), 0);
+}
+
+#[test]
+fn test_allow_deny_with_flags() {
+    let mut acl = Acl::empty().unwrap();
+    let users = Sid::from_string("S-1-5-32-545").unwrap();
+    let guests = Sid::from_string("S-1-5-32-546").unwrap();
+    let inherit = AceFlags::OBJECT_INHERIT | AceFlags::CONTAINER_INHERIT;
+
+    acl.deny_with_flags(FileAccess::WRITE, inherit | AceFlags::INHERIT_ONLY, &guests)
+        .unwrap();
+    acl.allow_with_flags(FileAccess::READ, inherit, &users).unwrap();
+    assert!(acl.is_valid());
+    assert_eq!(acl.ace_count(), 2);
+
+    let sddl = SecurityDescriptorBuilder::new().dacl(acl).to_sd_string().unwrap();
+    assert!(sddl.starts_with("D:(D;OICIIO;"), "{}", sddl);
+    assert!(sddl.contains(";;;BG)(A;OICI;"), "{}", sddl);
+    assert!(sddl.ends_with(";;;BU)"), "{}", sddl);
+}
+
+#[test]
+fn test_ace_flags() {
+    let sd = SecurityDescriptor::from_str("D:(A;ID;FA;;;SY)(A;OICIIO;FA;;;CO)(D;;FW;;;BG)").unwrap();
+    let dacl = sd.dacl().unwrap();
+    let aces: Vec<_> = dacl.into_iter().collect();
+
+    assert!(aces[0].is_inherited());
+    assert!(!aces[0].is_inherit_only());
+    assert_eq!(aces[0].flags(), AceFlags::INHERITED);
+    assert!(!aces[1].is_inherited());
+    assert!(aces[1].is_inherit_only());
+    assert_eq!(
+        aces[1].flags(),
+        AceFlags::OBJECT_INHERIT | AceFlags::CONTAINER_INHERIT | AceFlags::INHERIT_ONLY
+    );
+    assert_eq!(aces[2].flags(), AceFlags::empty());
+
+    assert_eq!(dacl.into_iter().explicit().count(), 2);
+    let inherited: Vec<_> = dacl.into_iter().inherited().collect();
+    assert_eq!(inherited.len(), 1);
+    assert_eq!(inherited[0].sid().unwrap(), Sid::from_string("S-1-5-18").unwrap());
+}
+
+#[test]
+fn test_object_aces() {
+    let reset_password = Guid::parse("00299570-246d-11d0-a768-00aa006e0529").unwrap();
+    let user_class = Guid::parse("bf967aba-0de6-11d0-a285-00aa003049e2").unwrap();
+    let users = Sid::from_string("S-1-5-32-545").unwrap();
+    let guests = Sid::from_string("S-1-5-32-546").unwrap();
+
+    let mut acl = Acl::empty().unwrap();
+    acl.deny_object(
+        AccessMask::from(0x100),
+        AceFlags::empty(),
+        Some(reset_password),
+        None,
+        &guests,
+    )
+    .unwrap();
+    acl.allow_object(
+        AccessMask::from(0x100),
+        AceFlags::CONTAINER_INHERIT | AceFlags::INHERIT_ONLY,
+        Some(reset_password),
+        Some(user_class),
+        &users,
+    )
+    .unwrap();
+    acl.allow_object(
+        AccessMask::from(0x10),
+        AceFlags::CONTAINER_INHERIT,
+        None,
+        Some(user_class),
+        &users,
+    )
+    .unwrap();
+    assert!(acl.is_valid());
+
+    let aces: Vec<_> = acl.iter().unwrap().collect();
+    assert_eq!(aces[0].ace_type(), AceType::AccessDeniedObject);
+    assert_eq!(aces[0].object_type(), Some(reset_password));
+    assert_eq!(aces[0].inherited_object_type(), None);
+    assert_eq!(aces[0].sid().unwrap(), guests);
+    assert_eq!(aces[1].ace_type(), AceType::AccessAllowedObject);
+    assert_eq!(aces[1].mask(), 0x100);
+    assert_eq!(aces[1].object_type(), Some(reset_password));
+    assert_eq!(aces[1].inherited_object_type(), Some(user_class));
+    assert_eq!(aces[1].sid().unwrap(), users);
+    assert_eq!(aces[2].object_type(), None);
+    assert_eq!(aces[2].inherited_object_type(), Some(user_class));
+    assert_eq!(aces[2].sid().unwrap(), users);
+
+    let sddl = SecurityDescriptorBuilder::new().dacl(acl).to_sd_string().unwrap();
+    assert_eq!(
+        sddl,
+        "D:(OD;;CR;00299570-246d-11d0-a768-00aa006e0529;;BG)\
+         (OA;CIIO;CR;00299570-246d-11d0-a768-00aa006e0529;bf967aba-0de6-11d0-a285-00aa003049e2;BU)\
+         (OA;CI;RP;;bf967aba-0de6-11d0-a285-00aa003049e2;BU)"
+    );
+
+    let sd = SecurityDescriptor::from_str(&sddl).unwrap();
+    let dacl = sd.dacl().unwrap();
+    let parsed: Vec<_> = dacl
+        .into_iter()
+        .map(|ace| (ace.object_type(), ace.sid().unwrap()))
+        .collect();
+    assert_eq!(
+        parsed,
+        vec![
+            (Some(reset_password), guests.clone()),
+            (Some(reset_password), users.clone()),
+            (None, users)
+        ]
+    );
+}
+
+#[test]
+fn test_callback_aces() {
+    let sd = SecurityDescriptor::from_str(
+        "D:(XA;;FA;;;WD;(Member_of {SID(BA)}))(XD;OICI;FW;;;BU;(@User.Department == \"Finance\"))(A;;FR;;;SY)",
+    )
+    .unwrap();
+    let dacl = sd.dacl().unwrap();
+    let aces: Vec<_> = dacl.into_iter().collect();
+
+    assert_eq!(aces[0].ace_type(), AceType::AccessAllowedCallback);
+    assert_eq!(aces[0].mask(), FileAccess::FULL.as_u32());
+    assert_eq!(aces[0].sid().unwrap(), Sid::from_string("S-1-1-0").unwrap());
+    assert!(aces[0].application_data().unwrap().starts_with(b"artx"));
+    assert_eq!(aces[0].condition().unwrap().as_deref(), Some("Member_of {SID(BA)}"));
+
+    assert_eq!(aces[1].ace_type(), AceType::AccessDeniedCallback);
+    assert_eq!(aces[1].flags(), AceFlags::OBJECT_INHERIT | AceFlags::CONTAINER_INHERIT);
+    assert_eq!(aces[1].sid().unwrap(), Sid::from_string("S-1-5-32-545").unwrap());
+    let condition = aces[1].condition().unwrap().unwrap();
+    assert!(condition.contains(".Department == \"Finance\""), "{}", condition);
+
+    assert_eq!(aces[2].application_data(), None);
+    assert_eq!(aces[2].condition().unwrap(), None);
+}
+
+#[test]
+fn test_ace_type_conversions() {
+    for raw in 0..=u8::MAX {
+        assert_eq!(u8::from(AceType::from(raw)), raw);
+    }
+    assert_eq!(AceType::from(2), AceType::SystemAudit);
+    assert_eq!(AceType::from(17), AceType::SystemMandatoryLabel);
+    assert_eq!(AceType::from(22), AceType::Unknown(22));
+    assert!(AceType::AccessDeniedCallbackObject.is_deny());
+    assert!(AceType::AccessDeniedCallbackObject.is_callback());
+    assert!(AceType::AccessDeniedCallbackObject.is_object());
+    assert!(!AceType::AccessDeniedCallbackObject.is_system());
+    assert!(AceType::SystemAuditObject.is_audit());
+    assert!(AceType::SystemScopedPolicyId.is_system());
+}
+
+#[test]
+fn test_ace_payload() {
+    let sd = SecurityDescriptor::from_str(
+        "D:(A;;FA;;;SY)(OA;;CR;00299570-246d-11d0-a768-00aa006e0529;;BU)(XA;;FA;;;WD;(Member_of {SID(BA)}))",
+    )
+    .unwrap();
+    let dacl = sd.dacl().unwrap();
+    let payloads = dacl
+        .try_iter()
+        .map(|ace| ace.unwrap().payload().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        payloads[0],
+        AcePayload::Access {
+            mask: FileAccess::FULL.as_u32(),
+            sid: Sid::from_string("S-1-5-18").unwrap()
+        }
+    );
+    assert_eq!(
+        payloads[1],
+        AcePayload::Object {
+            mask: 0x100,
+            object_type: Some(Guid::from_str("00299570-246d-11d0-a768-00aa006e0529").unwrap()),
+            inherited_object_type: None,
+            sid: Sid::from_string("S-1-5-32-545").unwrap()
+        }
+    );
+    match &payloads[2] {
+        AcePayload::Callback {
+            object_type: None,
+            sid,
+            application_data,
+            ..
+        } => {
+            assert_eq!(*sid, Sid::from_string("S-1-1-0").unwrap());
+            assert!(application_data.starts_with(b"artx"));
+        }
+        other => panic!("unexpected payload: {:?}", other),
+    }
+
+    let label = MandatoryLabel::new(IntegrityLevel::LOW, MandatoryPolicy::NO_WRITE_UP);
+    let capid = Sid::from_string("S-1-17-1").unwrap();
+    let everyone = Sid::from_string("S-1-1-0").unwrap();
+    let mut sacl = Acl::empty().unwrap();
+    sacl.add_mandatory_label(label, AceFlags::empty()).unwrap();
+    sacl.add_scoped_policy_id(&capid, AceFlags::empty()).unwrap();
+    sacl.audit(AccessMask::from(0x10), &everyone, AuditOn::Failure, AceFlags::empty())
+        .unwrap();
+
+    let aces = sacl.try_iter().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(aces[0].ace_type(), AceType::SystemMandatoryLabel);
+    assert_eq!(aces[0].payload().unwrap(), AcePayload::MandatoryLabel(label));
+    assert_eq!(aces[1].ace_type(), AceType::SystemScopedPolicyId);
+    assert_eq!(aces[1].payload().unwrap(), AcePayload::ScopedPolicyId(capid));
+    assert_eq!(
+        aces[2].payload().unwrap(),
+        AcePayload::Access {
+            mask: 0x10,
+            sid: everyone
+        }
+    );
+}
+
+#[test]
+fn test_insert_allow_deny() {
+    let users = Sid::from_string("S-1-5-32-545").unwrap();
+    let guests = Sid::from_string("S-1-5-32-546").unwrap();
+    let system = Sid::from_string("S-1-5-18").unwrap();
+
+    let mut acl = Acl::new().unwrap();
+    acl.allow(FileAccess::READ, &users).unwrap();
+    acl.insert_deny(0, FileAccess::WRITE, &guests).unwrap();
+    acl.insert_allow(1, FileAccess::FULL, &system).unwrap();
+    acl.insert_deny(acl.ace_count(), FileAccess::FULL, &guests).unwrap();
+    assert!(acl.insert_allow(acl.ace_count() + 1, FileAccess::READ, &users).is_err());
+    assert!(acl.is_valid());
+
+    let aces: Vec<_> = acl
+        .iter()
+        .unwrap()
+        .map(|ace| (ace.ace_type(), ace.mask(), ace.sid().unwrap()))
+        .collect();
+    assert_eq!(
+        aces,
+        vec![
+            (AceType::AccessDenied, FileAccess::WRITE.as_u32(), guests.clone()),
+            (AccessAllowed, FileAccess::FULL.as_u32(), system),
+            (AccessAllowed, FileAccess::READ.as_u32(), users),
+            (AceType::AccessDenied, FileAccess::FULL.as_u32(), guests),
+        ]
+    );
+}
+
+#[test]
+fn test_acl_get() {
+    let sd = create_sd();
+    let dacl = sd.dacl().unwrap();
+    let aces: Vec<_> = dacl.into_iter().collect();
+
+    assert_eq!(dacl.get(0).as_ref(), aces.first());
+    assert_eq!(dacl.get(2).unwrap().sid().unwrap(), aces[2].sid().unwrap());
+    assert_ne!(dacl.get(1), dacl.get(2));
+    assert_eq!(dacl.get(3), None);
+    assert_eq!(Acl::new().unwrap().get(0), None);
+}
+
+#[test]
+fn test_retain_and_remove_entries_for() {
+    let users = Sid::from_string("S-1-5-32-545").unwrap();
+    let guests = Sid::from_string("S-1-5-32-546").unwrap();
+    let system = Sid::from_string("S-1-5-18").unwrap();
+
+    let mut acl = Acl::new().unwrap();
+    acl.deny(FileAccess::WRITE, &guests).unwrap();
+    acl.allow(FileAccess::FULL, &system).unwrap();
+    acl.allow(FileAccess::READ, &guests).unwrap();
+    acl.allow_with_flags(FileAccess::READ, AceFlags::CONTAINER_INHERIT, &users)
+        .unwrap();
+    acl.allow(FileAccess::EXECUTE, &guests).unwrap();
+
+    assert_eq!(acl.remove_entries_for(&guests).unwrap(), 3);
+    assert_eq!(acl.remove_entries_for(&guests).unwrap(), 0);
+    let sids: Vec<_> = acl.iter().unwrap().map(|ace| ace.sid().unwrap()).collect();
+    assert_eq!(sids, vec![system.clone(), users]);
+
+    let mut visited = 0;
+    let removed = acl
+        .retain(|ace| {
+            visited += 1;
+            ace.flags() == AceFlags::empty()
+        })
+        .unwrap();
+    assert_eq!((visited, removed), (2, 1));
+    assert_eq!(acl.ace_count(), 1);
+    assert_eq!(acl.get(0).unwrap().sid().unwrap(), system);
+}
+
+#[test]
+fn test_canonical_order() {
+    let users = Sid::from_string("S-1-5-32-545").unwrap();
+    let guests = Sid::from_string("S-1-5-32-546").unwrap();
+    let system = Sid::from_string("S-1-5-18").unwrap();
+    let admins = Sid::from_string("S-1-5-32-544").unwrap();
+
+    let mut acl = Acl::new().unwrap();
+    acl.allow_with_flags(FileAccess::FULL, AceFlags::INHERITED, &system)
+        .unwrap();
+    acl.allow(FileAccess::READ, &users).unwrap();
+    acl.deny_with_flags(FileAccess::WRITE, AceFlags::INHERITED, &guests)
+        .unwrap();
+    acl.deny(FileAccess::WRITE, &guests).unwrap();
+    acl.allow(FileAccess::FULL, &admins).unwrap();
+    assert!(!acl.is_canonical().unwrap());
+
+    acl.reorder_canonical().unwrap();
+    assert!(acl.is_canonical().unwrap());
+    assert!(acl.is_valid());
+    let sddl = SecurityDescriptorBuilder::new().dacl(acl).to_sd_string().unwrap();
+    assert_eq!(
+        sddl,
+        "D:(D;;FW;;;BG)(A;;FR;;;BU)(A;;FA;;;BA)(A;ID;FA;;;SY)(D;ID;FW;;;BG)"
+    );
+
+    let sd = SecurityDescriptor::from_str("D:(D;;FW;;;AN)(A;;FA;;;BA)(A;ID;FA;;;SY)(D;ID;FW;;;BG)").unwrap();
+    assert!(sd.dacl().unwrap().is_canonical().unwrap());
+    assert!(Acl::new().unwrap().is_canonical().unwrap());
+}
+
+#[test]
+fn test_add_canonical() {
+    let users = Sid::from_string("S-1-5-32-545").unwrap();
+    let guests = Sid::from_string("S-1-5-32-546").unwrap();
+    let system = Sid::from_string("S-1-5-18").unwrap();
+
+    let mut acl = Acl::new().unwrap();
+    acl.add_canonical(AccessAllowed, FileAccess::FULL, AceFlags::INHERITED, &system)
+        .unwrap();
+    acl.add_canonical(AccessAllowed, FileAccess::READ, AceFlags::CONTAINER_INHERIT, &users)
+        .unwrap();
+    acl.add_canonical(AceType::AccessDenied, FileAccess::WRITE, AceFlags::empty(), &guests)
+        .unwrap();
+    acl.add_canonical(AceType::AccessDenied, FileAccess::EXECUTE, AceFlags::empty(), &users)
+        .unwrap();
+    acl.add_canonical(AccessAllowed, FileAccess::READ, AceFlags::empty(), &guests)
+        .unwrap();
+    assert!(
+        acl.add_canonical(AceType::SystemAudit, FileAccess::READ, AceFlags::empty(), &guests)
+            .is_err()
+    );
+    assert!(acl.is_canonical().unwrap());
+    assert!(acl.is_valid());
+
+    let sddl = SecurityDescriptorBuilder::new().dacl(acl).to_sd_string().unwrap();
+    assert_eq!(
+        sddl,
+        "D:(D;;FW;;;BG)(D;;FX;;;BU)(A;CI;FR;;;BU)(A;;FR;;;BG)(A;ID;FA;;;SY)"
+    );
+}
+
+#[test]
+fn test_acl_size_information() {
+    let system = Sid::from_string("S-1-5-18").unwrap();
+
+    let mut acl = Acl::with_capacity(2, 16).unwrap();
+    assert_eq!(acl.capacity(), 64);
+    assert_eq!(acl.bytes_in_use(), 8);
+    assert_eq!(acl.bytes_free(), 56);
+    assert!(acl.would_fit(&system, AceType::AccessAllowedObject));
+
+    for _ in 0..2 {
+        assert!(acl.would_fit(&system, AccessAllowed));
+        acl.allow(FileAccess::READ, &system).unwrap();
+    }
+    assert_eq!(acl.bytes_in_use(), 48);
+    assert_eq!(acl.bytes_free(), 16);
+    assert_eq!(acl.capacity(), 64);
+    assert!(!acl.would_fit(&system, AccessAllowed));
+    assert!(acl.allow(FileAccess::READ, &system).is_err());
+}
+
+#[test]
+fn test_ace_entries() {
+    let sd = SecurityDescriptor::from_str(
+        "D:(D;;FW;;;BG)(A;OICI;FA;;;SY)(A;ID;FR;;;BU)(OA;;CR;00299570-246d-11d0-a768-00aa006e0529;;BU)",
+    )
+    .unwrap();
+    let dacl = sd.dacl().unwrap();
+    let aces: Vec<_> = dacl.into_iter().collect();
+
+    let entry = aces[1].to_entry().unwrap();
+    assert_eq!(
+        entry,
+        AceEntry::new(
+            AccessAllowed,
+            AceFlags::OBJECT_INHERIT | AceFlags::CONTAINER_INHERIT,
+            FileAccess::FULL,
+            Sid::from_string("S-1-5-18").unwrap()
+        )
+    );
+    assert!(aces[3].to_entry().is_err());
+
+    let entries = aces[..3]
+        .iter()
+        .map(AceEntry::try_from)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    drop(aces);
+    drop(sd);
+
+    let acl = Acl::from_entries(&entries).unwrap();
+    assert!(acl.is_valid());
+    let sddl = SecurityDescriptorBuilder::new().dacl(acl).to_sd_string().unwrap();
+    assert_eq!(sddl, "D:(D;;FW;;;BG)(A;OICI;FA;;;SY)(A;ID;FR;;;BU)");
+
+    let mut acl = Acl::new().unwrap();
+    acl.push_entry(&entries[0]).unwrap();
+    assert_eq!(acl.get(0).unwrap().to_entry().unwrap(), entries[0]);
+    assert_eq!(Acl::from_entries(&[]).unwrap().ace_count(), 0);
+}
+
+#[test]
+fn test_normalize() {
+    let sd = SecurityDescriptor::from_str(
+        "D:(A;;FR;;;BU)(A;;FX;;;BU)(A;CI;FW;;;BU)(A;;FR;;;BU)(D;;FW;;;BG)(A;;0x4;;;BU)(D;;FW;;;BG)(A;ID;FA;;;SY)(A;ID;FA;;;SY)",
+    )
+    .unwrap();
+    let entries = sd
+        .dacl()
+        .unwrap()
+        .try_iter()
+        .map(|ace| ace.unwrap().to_entry().unwrap())
+        .collect::<Vec<_>>();
+    let mut acl = Acl::from_entries(&entries).unwrap();
+
+    assert_eq!(acl.normalize().unwrap(), 4);
+    assert!(acl.is_valid());
+    assert_eq!(acl.normalize().unwrap(), 0);
+    let sddl = SecurityDescriptorBuilder::new().dacl(acl).to_sd_string().unwrap();
+    assert_eq!(
+        sddl,
+        "D:(A;;0x1200a9;;;BU)(A;CI;FW;;;BU)(D;;FW;;;BG)(A;;0x4;;;BU)(A;ID;FA;;;SY)"
+    );
+}
+
+#[test]
+fn test_acl_diff() {
+    let before = SecurityDescriptor::from_str("D:(A;;FR;;;BU)(A;;FA;;;SY)(D;;FW;;;BG)(A;OICI;FR;;;BA)").unwrap();
+    let after = SecurityDescriptor::from_str("D:(A;;FA;;;SY)(A;;0x1200a9;;;BU)(A;OICI;FR;;;BA)(A;;FR;;;WD)").unwrap();
+    let (before, after) = (before.dacl().unwrap(), after.dacl().unwrap());
+
+    let diff = acl::diff(&before, &after).unwrap();
+    assert_eq!(diff.added.len(), 1);
+    assert_eq!(diff.added[0].trustee, Sid::from_string("S-1-1-0").unwrap());
+    assert_eq!(diff.removed.len(), 1);
+    assert_eq!(diff.removed[0].ace_type, AceType::AccessDenied);
+    assert_eq!(diff.removed[0].trustee, Sid::from_string("S-1-5-32-546").unwrap());
+    assert_eq!(diff.modified.len(), 1);
+    assert_eq!(diff.modified[0].trustee, Sid::from_string("S-1-5-32-545").unwrap());
+    assert_eq!(diff.modified[0].added_rights(), 0x20);
+    assert_eq!(diff.modified[0].removed_rights(), 0);
+
+    assert!(acl::diff(&before, &before).unwrap().is_empty());
+}
+
+#[test]
+fn test_acl_diff_object_aces() {
+    // "Reset Password" replaced with "Change Password" for the same trustee and mask
+    let before = SecurityDescriptor::from_str("D:(OA;;CR;00299570-246d-11d0-a768-00aa006e0529;;WD)").unwrap();
+    let after = SecurityDescriptor::from_str("D:(OA;;CR;ab721a53-1e2f-11d0-9819-00aa0040529b;;WD)").unwrap();
+    let (before, after) = (before.dacl().unwrap(), after.dacl().unwrap());
+
+    let diff = acl::diff(&before, &after).unwrap();
+    assert!(diff.modified.is_empty());
+    assert_eq!(diff.removed.len(), 1);
+    assert_eq!(
+        diff.removed[0].object_type,
+        Some(Guid(0x00299570_246D_11D0_A768_00AA006E0529))
+    );
+    assert_eq!(diff.added.len(), 1);
+    assert_eq!(
+        diff.added[0].object_type,
+        Some(Guid(0xAB721A53_1E2F_11D0_9819_00AA0040529B))
+    );
+    assert!(acl::diff(&before, &before).unwrap().is_empty());
+}
+
+#[test]
+fn test_acl_bytes() {
+    let sd = create_sd();
+    let dacl = sd.dacl().unwrap();
+    let bytes = dacl.to_bytes();
+    assert_eq!(bytes.len(), dacl.capacity() as usize);
+
+    let copy = Acl::from_bytes(&bytes).unwrap();
+    assert!(acl::diff(&dacl, &copy).unwrap().is_empty());
+    assert_eq!(copy.to_bytes(), bytes);
+    drop(sd);
+    assert_eq!(copy.ace_count(), 3);
+
+    let mut padded = bytes.clone();
+    padded.extend_from_slice(&[0; 16]);
+    assert_eq!(Acl::try_from(padded.as_slice()).unwrap().to_bytes(), bytes);
+
+    assert!(Acl::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    assert!(Acl::from_bytes(&[2, 0]).is_err());
+    let mut corrupted = bytes;
+    corrupted[4] = 10;
+    assert!(Acl::from_bytes(&corrupted).is_err());
+}
+
+#[test]
+fn test_ace_sddl() {
+    let sd =
+        SecurityDescriptor::from_str("D:(D;;FW;;;BG)(A;OICI;FA;;;WD)(OA;;CR;00299570-246d-11d0-a768-00aa006e0529;;BU)")
+            .unwrap();
+    let dacl = sd.dacl().unwrap();
+    let fragments = dacl
+        .try_iter()
+        .map(|ace| ace.unwrap().to_sddl().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fragments,
+        vec![
+            "(D;;FW;;;BG)",
+            "(A;OICI;FA;;;WD)",
+            "(OA;;CR;00299570-246d-11d0-a768-00aa006e0529;;BU)"
+        ]
+    );
+
+    let entry = AceEntry::from_sddl("(A;OICI;FA;;;WD)").unwrap();
+    assert_eq!(entry, dacl.get(1).unwrap().to_entry().unwrap());
+    assert_eq!(entry.to_sddl().unwrap(), "(A;OICI;FA;;;WD)");
+
+    let audit = AceEntry::from_sddl(" (AU;SA;FW;;;WD) ").unwrap();
+    assert_eq!(audit.kind, AceType::SystemAudit);
+    assert_eq!(audit.flags, AceFlags::SUCCESSFUL_ACCESS);
+    assert_eq!(audit.to_sddl().unwrap(), "(AU;SA;FW;;;WD)");
+    let label = AceEntry::from_sddl("(ML;;NW;;;LW)").unwrap();
+    assert_eq!(label.kind, AceType::SystemMandatoryLabel);
+
+    assert!(AceEntry::from_sddl("A;;FA;;;WD").is_err());
+    assert!(AceEntry::from_sddl("(A;;FA;;;WD)(A;;FR;;;BU)").is_err());
+    assert!(AceEntry::from_sddl("(OA;;CR;00299570-246d-11d0-a768-00aa006e0529;;BU)").is_err());
+    assert!(AceEntry::from_sddl("(A;;FA;;;nobody)").is_err());
+}
+
+#[test]
+fn test_acl_try_clone() {
+    let mut copy = {
+        let sd = create_sd();
+        let dacl = sd.dacl().unwrap();
+        dacl.try_clone().unwrap()
+    };
+    assert!(copy.is_valid());
+    assert_eq!(copy.ace_count(), 3);
+    assert_eq!(
+        copy.get(0).unwrap().sid().unwrap(),
+        Sid::from_string("S-1-5-18").unwrap()
+    );
+
+    let sd = create_sd();
+    copy.remove_ace(0).unwrap();
+    assert_eq!(copy.ace_count(), 2);
+    assert_eq!(sd.dacl().unwrap().ace_count(), 3);
+
+    let original = copy.try_clone().unwrap();
+    copy.remove_ace(0).unwrap();
+    assert_eq!(original.ace_count(), 2);
+    assert_eq!(original.capacity(), copy.capacity());
+}
+
+#[test]
+fn test_acl_iter_exact_size_and_reverse() {
+    let sd = create_sd();
+    let dacl = sd.dacl().unwrap();
+
+    let mut aces = dacl.iter().unwrap();
+    assert_eq!(aces.len(), 3);
+    let first = aces.next().unwrap();
+    let last = aces.next_back().unwrap();
+    assert_eq!(aces.size_hint(), (1, Some(1)));
+    assert_eq!(first.sid().unwrap(), Sid::from_string("S-1-5-18").unwrap());
+    assert_eq!(last, dacl.get(2).unwrap());
+    assert_eq!(aces.next(), dacl.get(1));
+    assert_eq!(aces.next(), None);
+    assert_eq!(aces.next_back(), None);
+
+    let forward: Vec<_> = dacl.into_iter().collect();
+    let mut backward: Vec<_> = dacl.into_iter().rev().collect();
+    backward.reverse();
+    assert_eq!(forward, backward);
+    assert_eq!(Acl::new().unwrap().iter().unwrap().len(), 0);
+}
+
+#[test]
+fn test_ace_kind_iterators() {
+    let users = Sid::from_string("S-1-5-32-545").unwrap();
+    let guests = Sid::from_string("S-1-5-32-546").unwrap();
+
+    let mut acl = Acl::new().unwrap();
+    acl.deny(FileAccess::WRITE, &guests).unwrap();
+    acl.allow(FileAccess::READ, &users).unwrap();
+    acl.allow(FileAccess::READ, &guests).unwrap();
+    acl.audit(FileAccess::WRITE, &users, AuditOn::Failure, AceFlags::empty())
+        .unwrap();
+
+    assert_eq!(acl.allowed().count(), 2);
+    assert!(acl.allowed().all(|ace| ace.ace_type() == AccessAllowed));
+    assert_eq!(acl.denied().next().unwrap().sid().unwrap(), guests);
+    assert_eq!(acl.denied().count(), 1);
+    assert_eq!(acl.audits().count(), 1);
+    assert_eq!(acl.explicit().count(), 4);
+    assert_eq!(acl.inherited().count(), 0);
+
+    let sd = create_sd();
+    let dacl = sd.dacl().unwrap();
+    assert_eq!(dacl.inherited().count(), 3);
+    assert_eq!(dacl.explicit().count(), 0);
+}
+
+#[test]
+fn test_acl_into_entries() {
+    let users = Sid::from_string("S-1-5-32-545").unwrap();
+    let guests = Sid::from_string("S-1-5-32-546").unwrap();
+
+    let mut acl = Acl::new().unwrap();
+    acl.deny(FileAccess::WRITE, &guests).unwrap();
+    acl.allow(FileAccess::READ, &users).unwrap();
+    acl.allow(FileAccess::READ, &guests).unwrap();
+
+    let entries: Vec<AceEntry> = acl
+        .try_clone()
+        .unwrap()
+        .into_entries()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(
+        entries[1],
+        AceEntry::new(AccessAllowed, AceFlags::empty(), FileAccess::READ, users.clone())
+    );
+
+    let filtered: Acl = entries
+        .into_iter()
+        .filter(|entry| entry.sid != guests)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert!(filtered.is_valid());
+    assert_eq!(filtered.ace_count(), 1);
+    assert_eq!(filtered.get(0).unwrap().sid().unwrap(), users);
+
+    // unsupported ACEs are reported, not dropped
+    acl.allow_object(AccessMask::from(0x10), AceFlags::empty(), None, None, &users)
+        .unwrap();
+    let entries: Vec<_> = acl.into_entries().collect();
+    assert_eq!(entries.len(), 4);
+    assert!(entries[3].is_err());
+
+    let unsupported = AceEntry::new(AceType::AccessAllowedObject, AceFlags::empty(), FileAccess::READ, users);
+    let collected: Result<Acl, _> = [unsupported].into_iter().collect();
+    assert_eq!(collected.unwrap_err().code, ERROR_INVALID_PARAMETER);
+}
+
+#[test]
+fn test_acl_validate() {
+    let users = Sid::from_string("S-1-5-32-545").unwrap();
+    let guests = Sid::from_string("S-1-5-32-546").unwrap();
+
+    let mut acl = Acl::new().unwrap();
+    assert!(acl.validate().is_empty());
+
+    acl.allow(FileAccess::READ, &users).unwrap();
+    acl.allow_with_flags(FileAccess::READ, AceFlags::INHERIT_ONLY, &guests)
+        .unwrap();
+    acl.deny(FileAccess::WRITE, &guests).unwrap();
+    assert_eq!(
+        acl.validate(),
+        vec![
+            AclIssue::InconsistentInheritance {
+                index: 1,
+                flags: AceFlags::INHERIT_ONLY
+            },
+            AclIssue::NotCanonical { index: 2 },
+        ]
+    );
+
+    acl.reorder_canonical().unwrap();
+    assert_eq!(acl.validate().len(), 1);
+    assert!(create_sd().dacl().unwrap().validate().is_empty());
+}
+
+#[test]
+fn test_acl_revision() {
+    let users = Sid::from_string("S-1-5-32-545").unwrap();
+
+    let mut acl = Acl::new().unwrap();
+    assert_eq!(acl.revision() as u32, ACL_REVISION);
+    acl.allow(FileAccess::READ, &users).unwrap();
+    assert_eq!(acl.revision() as u32, ACL_REVISION);
+
+    acl.allow_object(AccessMask::from(0x10), AceFlags::empty(), None, None, &users)
+        .unwrap();
+    assert_eq!(acl.revision() as u32, ACL_REVISION_DS);
+    acl.insert_deny(0, FileAccess::WRITE, &users).unwrap();
+    assert_eq!(acl.revision() as u32, ACL_REVISION_DS);
+    assert!(acl.is_valid());
+    assert!(acl.validate().is_empty());
+
+    let copy = acl.try_clone().unwrap();
+    assert_eq!(copy.revision() as u32, ACL_REVISION_DS);
+
+    acl.audit(FileAccess::WRITE, &users, AuditOn::Failure, AceFlags::empty())
+        .unwrap();
+    assert_eq!(acl.revision() as u32, ACL_REVISION_DS);
+}
+
+#[test]
+fn test_compound_ace() {
+    let network_service = Sid::from_string("S-1-5-20").unwrap();
+    let users = Sid::from_string("S-1-5-32-545").unwrap();
+
+    let mut acl = Acl::new().unwrap();
+    acl.allow_compound(FileAccess::READ, AceFlags::CONTAINER_INHERIT, &network_service, &users)
+        .unwrap();
+    assert!(acl.is_valid());
+    assert!(acl.validate().is_empty());
+    assert_eq!(acl.revision(), 3);
+
+    let ace = acl.get(0).unwrap();
+    assert_eq!(ace.ace_type(), AceType::AccessAllowedCompound);
+    assert_eq!(ace.flags(), AceFlags::CONTAINER_INHERIT);
+    assert_eq!(ace.sid().unwrap(), network_service);
+    assert_eq!(ace.client_sid().unwrap(), Some(users.clone()));
+    assert_eq!(
+        ace.payload().unwrap(),
+        AcePayload::Compound {
+            mask: FileAccess::READ.as_u32(),
+            server: network_service.clone(),
+            client: users.clone(),
+        }
+    );
+
+    acl.allow(FileAccess::READ, &users).unwrap();
+    assert_eq!(acl.get(1).unwrap().client_sid().unwrap(), None);
+}
+
+#[test]
+fn test_truncated_ace_sid() {
+    // an ACL with one 16-byte allow ACE whose SID claims 5 sub-authorities
+    let bytes: [u8; 24] = [
+        2, 0, 24, 0, 1, 0, 0, 0, // ACL header
+        0, 0, 16, 0, 0xFF, 0x01, 0x1F, 0x00, // ACE header, mask
+        1, 5, 0, 0, 0, 0, 0, 5, // SID header without its sub-authorities
+    ];
+    let mut buffer = [0u32; 6];
+    unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer.as_mut_ptr() as *mut u8, bytes.len()) };
+    let acl = unsafe { Acl::from_ptr(buffer.as_mut_ptr() as _) };
+
+    let ace = acl.get(0).unwrap();
+    assert_eq!(ace.sid().unwrap_err().code, ERROR_INVALID_ACL);
+    assert!(ace.payload().is_err());
+}
+
+/// Returns an ACL buffer whose header claims two ACEs while it holds only one.
+fn corrupted_acl_buffer() -> [u32; 7] {
+    let bytes: [u8; 28] = [
+        2, 0, 28, 0, 2, 0, 0, 0, // ACL header
+        0, 0, 20, 0, 0xFF, 0x01, 0x1F, 0x00, // ACE header, mask
+        1, 1, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, // S-1-1-0
+    ];
+    let mut buffer = [0u32; 7];
+    unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer.as_mut_ptr() as *mut u8, bytes.len()) };
+    buffer
+}
+
+#[test]
+fn test_acl_iter_len_of_corrupted_acl() {
+    let mut buffer = corrupted_acl_buffer();
+    let acl = unsafe { Acl::from_ptr(buffer.as_mut_ptr() as _) };
+
+    // the length is the count claimed by the header, iteration ends at the unreadable ACE
+    assert_eq!(acl.iter().unwrap().len(), 2);
+    assert_eq!(acl.iter().unwrap().count(), 1);
+    assert_eq!(acl.iter().unwrap().rev().count(), 0);
+    assert_eq!((&acl).into_iter().count(), 1);
+}
+
+#[test]
+fn test_try_iter_corrupted_acl() {
+    let mut buffer = corrupted_acl_buffer();
+    let acl = unsafe { Acl::from_ptr(buffer.as_mut_ptr() as _) };
+
+    let results: Vec<_> = acl.try_iter().collect();
+    assert!(!results.is_empty());
+    assert!(results.last().unwrap().is_err());
+    assert!(acl.try_iter().collect::<Result<Vec<_>, _>>().is_err());
+}
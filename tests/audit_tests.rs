@@ -0,0 +1,72 @@
+#![cfg(windows)]
+
+use win_acl_rs::{
+    acl::{AceFlags, AceType, Acl},
+    audit::{AuditOn, SecurityEventSource, audit_access},
+    elevated::{PrivilegeToken, SecurityDescriptorElevated},
+    mask::FileAccess,
+    sid::Sid,
+    wellknown::WinWorldSid,
+};
+
+#[test]
+fn test_register_missing_source() {
+    assert!(SecurityEventSource::register("win-acl-rs-missing-test-source").is_err());
+}
+
+#[test]
+fn test_acl_audit() {
+    let everyone = Sid::from_well_known_sid(WinWorldSid).unwrap();
+    let mut sacl = Acl::empty().unwrap();
+    sacl.audit(
+        FileAccess::WRITE,
+        &everyone,
+        AuditOn::Failure,
+        AceFlags::CONTAINER_INHERIT,
+    )
+    .unwrap();
+    sacl.audit(
+        FileAccess::READ,
+        &everyone,
+        AuditOn::FailureAndSuccess,
+        AceFlags::empty(),
+    )
+    .unwrap();
+    assert!(sacl.is_valid());
+
+    let aces: Vec<_> = sacl.iter().unwrap().collect();
+    assert_eq!(aces.len(), 2);
+    assert_eq!(aces[0].ace_type(), AceType::SystemAudit);
+    assert_eq!(aces[0].sid().unwrap(), everyone);
+    assert_eq!(aces[0].mask(), FileAccess::WRITE.0);
+    assert_eq!(aces[0].audit_on(), Some(AuditOn::Failure));
+    assert_eq!(aces[0].flags(), AceFlags::CONTAINER_INHERIT | AceFlags::FAILED_ACCESS);
+    assert_eq!(aces[1].mask(), FileAccess::READ.0);
+    assert_eq!(aces[1].audit_on(), Some(AuditOn::FailureAndSuccess));
+}
+
+#[test]
+#[ignore] // would fail on CI
+fn test_audit_access() {
+    let dir = std::env::temp_dir().join("win_acl_rs_audit_access");
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let everyone = Sid::from_well_known_sid(WinWorldSid).unwrap();
+    audit_access(&dir, &everyone, FileAccess::WRITE, AuditOn::Failure).unwrap();
+    // merged into the existing entry
+    audit_access(&dir, &everyone, FileAccess::READ, AuditOn::Failure).unwrap();
+
+    let token = PrivilegeToken::new().try_elevate().unwrap();
+    let sd = SecurityDescriptorElevated::from_path(&token, &dir).unwrap();
+    assert!(sd.sacl_present().unwrap());
+
+    let sacl = sd.sacl().unwrap();
+    let entry = (&sacl)
+        .into_iter()
+        .find(|ace| ace.sid().unwrap() == everyone && ace.audit_on().is_some())
+        .unwrap();
+    assert_eq!(entry.audit_on(), Some(AuditOn::Failure));
+    assert_eq!(entry.mask() & FileAccess::READ.0, FileAccess::READ.0);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
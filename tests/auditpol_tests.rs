@@ -0,0 +1,28 @@
+#![cfg(windows)]
+
+use win_acl_rs::{
+    auditpol::{self, AuditSetting, AuditSubcategory},
+    elevated::PrivilegeToken,
+};
+
+#[test]
+fn test_enumerate_categories() {
+    let categories = auditpol::categories().unwrap();
+    assert!(!categories.is_empty());
+    for category in &categories {
+        assert!(!category.name().unwrap().is_empty());
+        assert!(!category.subcategories().unwrap().is_empty());
+    }
+
+    let subcategories = auditpol::subcategories().unwrap();
+    assert!(subcategories.contains(&AuditSubcategory::FILE_SYSTEM));
+    assert!(subcategories.contains(&AuditSubcategory::REGISTRY));
+}
+
+#[test]
+#[ignore] // would fail on CI
+fn test_query_policy() {
+    let token = PrivilegeToken::new().try_elevate().unwrap();
+    let setting = AuditSubcategory::FILE_SYSTEM.policy(&token).unwrap();
+    assert!(!setting.contains(AuditSetting::NONE));
+}
@@ -0,0 +1,102 @@
+#![cfg(windows)]
+
+use tempfile::NamedTempFile;
+use win_acl_rs::{
+    acl::{AceFlags, Acl},
+    claims::{self, ClaimFlags, ClaimValues, ResourceAttribute},
+    sd::{SecurityDescriptor, SecurityDescriptorBuilder, SecurityInformation},
+    sid::Sid,
+};
+
+fn attributes() -> Vec<ResourceAttribute> {
+    vec![
+        ResourceAttribute::new("Project", ClaimValues::String(vec!["X".to_owned(), "Y".to_owned()]))
+            .with_flags(ClaimFlags::CASE_SENSITIVE),
+        ResourceAttribute::new("Sensitivity", ClaimValues::from(-100i64)),
+        ResourceAttribute::new("Reviewed", ClaimValues::from(true)),
+        ResourceAttribute::new(
+            "Owners",
+            ClaimValues::Sid(vec![Sid::from_string("S-1-5-32-544").unwrap()]),
+        ),
+    ]
+}
+
+#[test]
+fn test_resource_attribute_sddl() {
+    let attributes = attributes();
+    assert_eq!(attributes[0].to_sddl().unwrap(), "(\"Project\",TS,0x2,\"X\",\"Y\")");
+    assert_eq!(attributes[1].to_sddl().unwrap(), "(\"Sensitivity\",TI,0x0,-100)");
+    assert_eq!(attributes[2].to_sddl().unwrap(), "(\"Reviewed\",TB,0x0,1)");
+    assert_eq!(
+        attributes[3].to_sddl().unwrap(),
+        "(\"Owners\",TD,0x0,SID(S-1-5-32-544))"
+    );
+    assert!(
+        ResourceAttribute::new("Empty", ClaimValues::String(Vec::new()))
+            .to_sddl()
+            .is_err()
+    );
+}
+
+#[test]
+fn test_resource_attribute_ace() {
+    let mut sacl = Acl::empty().unwrap();
+    for attribute in attributes() {
+        sacl.add_resource_attribute(&attribute, AceFlags::empty()).unwrap();
+    }
+    assert!(sacl.is_valid());
+
+    let decoded = sacl
+        .try_iter()
+        .map(|ace| ace.unwrap().resource_attribute().unwrap().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(decoded, attributes());
+
+    let sd = SecurityDescriptorBuilder::new().sacl(sacl).build().unwrap();
+    assert_eq!(sd.resource_attributes().unwrap(), attributes());
+
+    let sd = SecurityDescriptor::from_sd_string("O:BAG:BAD:(A;;FA;;;WD)").unwrap();
+    assert!(sd.resource_attributes().unwrap().is_empty());
+}
+
+#[test]
+fn test_resource_attributes_of_file() {
+    let path = NamedTempFile::new().unwrap().into_temp_path();
+    let mut sacl = Acl::empty().unwrap();
+    sacl.add_resource_attribute(&attributes()[0], AceFlags::empty())
+        .unwrap();
+    let sd = SecurityDescriptorBuilder::new().sacl(sacl).build().unwrap();
+    sd.apply_to_path(&path, SecurityInformation::ATTRIBUTE).unwrap();
+
+    let sd = SecurityDescriptor::from_path(&path).unwrap();
+    assert_eq!(sd.resource_attributes().unwrap(), vec![attributes()[0].clone()]);
+}
+
+#[test]
+fn test_scoped_policy_id_ace() {
+    let capid = Sid::from_string("S-1-17-1234567890-1").unwrap();
+    let mut sacl = Acl::empty().unwrap();
+    sacl.add_scoped_policy_id(&capid, AceFlags::OBJECT_INHERIT | AceFlags::CONTAINER_INHERIT)
+        .unwrap();
+    sacl.add_resource_attribute(&attributes()[1], AceFlags::empty())
+        .unwrap();
+    assert!(sacl.is_valid());
+
+    let aces = sacl.try_iter().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(aces[0].scoped_policy_id().unwrap(), Some(capid.clone()));
+    assert_eq!(aces[0].flags(), AceFlags::OBJECT_INHERIT | AceFlags::CONTAINER_INHERIT);
+    assert_eq!(aces[1].scoped_policy_id().unwrap(), None);
+    assert_eq!(aces[0].resource_attribute().unwrap(), None);
+
+    let sd = SecurityDescriptorBuilder::new().sacl(sacl).build().unwrap();
+    assert_eq!(sd.central_access_policy().unwrap(), Some(capid));
+    let sd = SecurityDescriptor::from_sd_string("O:BAG:BAD:(A;;FA;;;WD)").unwrap();
+    assert_eq!(sd.central_access_policy().unwrap(), None);
+}
+
+#[test]
+fn test_applied_capids() {
+    for capid in claims::applied_capids().unwrap() {
+        assert!(capid.to_string().unwrap().starts_with("S-1-17-"));
+    }
+}
@@ -0,0 +1,58 @@
+#![cfg(windows)]
+
+use win_acl_rs::{
+    acl::AceType,
+    com::{AppId, ComSecurityBuilder},
+    mask::ComAccess,
+    sid::Sid,
+    wellknown::{WinAuthenticatedUserSid, WinBuiltinAdministratorsSid, WinBuiltinGuestsSid},
+};
+
+#[test]
+fn test_com_security_builder() {
+    let users = Sid::from_well_known_sid(WinAuthenticatedUserSid).unwrap();
+    let guests = Sid::from_well_known_sid(WinBuiltinGuestsSid).unwrap();
+
+    let sd = ComSecurityBuilder::new()
+        .allow(users, ComAccess::EXECUTE_LOCAL)
+        .deny(guests, ComAccess::local_and_remote())
+        .build()
+        .unwrap();
+    assert!(sd.is_valid());
+
+    let admins = Sid::from_well_known_sid(WinBuiltinAdministratorsSid).unwrap();
+    assert_eq!(
+        sd.owner_sid().unwrap().to_string().unwrap(),
+        admins.to_string().unwrap()
+    );
+    assert_eq!(
+        sd.group_sid().unwrap().to_string().unwrap(),
+        admins.to_string().unwrap()
+    );
+
+    let dacl = sd.dacl().unwrap();
+    let aces: Vec<_> = dacl.into_iter().map(|ace| (ace.ace_type(), ace.mask())).collect();
+    assert_eq!(
+        aces,
+        vec![
+            (AceType::AccessDenied, ComAccess::local_and_remote().0),
+            (
+                AceType::AccessAllowed,
+                (ComAccess::EXECUTE | ComAccess::EXECUTE_LOCAL).0
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_app_id() {
+    let app_id = AppId::new("0000031a-0000-0000-c000-000000000046").unwrap();
+    assert_eq!(app_id.guid(), "{0000031A-0000-0000-C000-000000000046}");
+    assert_eq!(
+        app_id.key().object_name(),
+        r"CLASSES_ROOT\AppID\{0000031A-0000-0000-C000-000000000046}"
+    );
+
+    assert!(AppId::new("{0000031a-0000-0000-c000}").is_err());
+    assert!(AppId::new("not-a-guid").is_err());
+}
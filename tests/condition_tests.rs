@@ -0,0 +1,75 @@
+#![cfg(windows)]
+
+use win_acl_rs::{
+    acl::{AceFlags, AceType, Acl},
+    condition::{Attribute, Condition},
+    mask::{AccessMask, FileAccess, Mask},
+    sid::Sid,
+};
+
+#[test]
+fn test_condition_sddl() {
+    let admins = Sid::from_string("S-1-5-32-544").unwrap();
+    let condition = Condition::member_of([admins])
+        .and(Attribute::resource("Project").equals("X"))
+        .or(!Attribute::user("Clearance").less_than(3));
+    assert_eq!(
+        condition.to_sddl().unwrap(),
+        "(((Member_of {SID(S-1-5-32-544)}) && (@Resource.Project == \"X\")) || (!(@User.Clearance < 3)))"
+    );
+
+    let condition = Attribute::user("Department").any_of(["Finance", "Legal"]);
+    assert_eq!(
+        condition.to_sddl().unwrap(),
+        "(@User.Department Any_of {\"Finance\", \"Legal\"})"
+    );
+    assert_eq!(
+        Attribute::device("Managed Device").exists().to_sddl().unwrap(),
+        "(Exists @Device.Managed%0020Device)"
+    );
+    assert!(Attribute::user("Name").equals("a\"b").to_sddl().is_err());
+}
+
+#[test]
+fn test_conditional_ace() {
+    let everyone = Sid::from_string("S-1-1-0").unwrap();
+    let condition = Attribute::user("Department").equals("Finance");
+
+    assert_eq!(
+        condition
+            .ace_sddl(
+                AceType::AccessAllowedCallback,
+                AceFlags::CONTAINER_INHERIT,
+                AccessMask::from(0x1f01ff),
+                &everyone
+            )
+            .unwrap(),
+        "(XA;CI;0x1f01ff;;;S-1-1-0;((@User.Department == \"Finance\")))"
+    );
+    assert!(
+        condition
+            .ace_sddl(
+                AceType::AccessAllowed,
+                AceFlags::empty(),
+                AccessMask::from(0x1f01ff),
+                &everyone
+            )
+            .is_err()
+    );
+    assert!(condition.to_bytes().unwrap().starts_with(b"artx"));
+
+    let mut dacl = Acl::empty().unwrap();
+    dacl.allow_if(FileAccess::READ, AceFlags::empty(), &everyone, &condition)
+        .unwrap();
+    dacl.deny_if(FileAccess::WRITE, AceFlags::empty(), &everyone, &!condition.clone())
+        .unwrap();
+    assert!(dacl.is_valid());
+
+    let aces: Vec<_> = dacl.iter().unwrap().collect();
+    assert_eq!(aces[0].ace_type(), AceType::AccessAllowedCallback);
+    assert_eq!(aces[0].mask(), FileAccess::READ.as_u32());
+    assert_eq!(aces[0].sid().unwrap(), everyone);
+    assert_eq!(aces[0].application_data().unwrap(), condition.to_bytes().unwrap());
+    assert!(aces[0].condition().unwrap().unwrap().contains("Department"));
+    assert_eq!(aces[1].ace_type(), AceType::AccessDeniedCallback);
+}
@@ -0,0 +1,38 @@
+#![cfg(windows)]
+
+use std::collections::HashMap;
+
+use win_acl_rs::{
+    ds::{GuidResolver, SchemaObjectKind, WellKnownSchema, well_known_schema_entry},
+    guid::Guid,
+};
+
+#[test]
+fn test_well_known_schema() {
+    let reset_password = Guid::parse("{00299570-246D-11D0-A768-00AA006E0529}").unwrap();
+    assert_eq!(
+        well_known_schema_entry(reset_password),
+        Some(("Reset Password", SchemaObjectKind::ExtendedRight))
+    );
+    assert_eq!(
+        well_known_schema_entry(Guid(0x91E647DE_D96F_4B70_9557_D63FF4F3CCD8)),
+        Some(("Private Information", SchemaObjectKind::PropertySet))
+    );
+    assert_eq!(WellKnownSchema.resolve(Guid(1)), None);
+}
+
+#[test]
+fn test_chained_resolver() {
+    let custom_guid = Guid(0x12345678_0000_0000_0000_000000000000);
+    let member = Guid(0xBF9679C0_0DE6_11D0_A285_00AA003049E2);
+
+    let mut custom = HashMap::new();
+    custom.insert(custom_guid, "myAttribute".to_owned());
+    let resolver = (custom, WellKnownSchema);
+
+    assert_eq!(resolver.resolve(custom_guid).as_deref(), Some("myAttribute"));
+    assert_eq!(resolver.resolve(member).as_deref(), Some("member"));
+
+    let closure = |guid: Guid| (guid == Guid(1)).then(|| "one".to_owned());
+    assert_eq!(closure.resolve(Guid(1)).as_deref(), Some("one"));
+}
@@ -0,0 +1,19 @@
+#![cfg(windows)]
+
+use win_acl_rs::error::WinError;
+
+#[test]
+fn test_error_equality() {
+    let error = WinError::new(5, None);
+    assert_eq!(error, WinError::from(5u32));
+    assert_eq!(error.clone(), error);
+    assert_ne!(error, WinError::new(5, Some("access denied".to_owned())));
+}
+
+#[cfg(feature = "backtrace")]
+#[test]
+fn test_error_backtrace() {
+    let error = WinError::new(5, None);
+    assert!(error.backtrace().is_some());
+    assert!(WinError::default().backtrace().is_none());
+}
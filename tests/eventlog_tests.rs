@@ -0,0 +1,22 @@
+#![cfg(windows)]
+
+use win_acl_rs::eventlog;
+
+#[test]
+fn test_channel_access() {
+    let sddl = eventlog::channel_access_sddl("Application").unwrap();
+    let sd = eventlog::channel_access("Application").unwrap();
+    assert!(sd.is_valid());
+    assert!(sd.dacl_present().unwrap());
+    assert!(!sddl.is_empty());
+}
+
+#[test]
+fn test_missing_channel() {
+    assert!(eventlog::channel_access("win-acl-rs/DoesNotExist").is_err());
+}
+
+#[test]
+fn test_set_invalid_sddl() {
+    assert!(eventlog::set_channel_access_sddl("Application", "not an sddl").is_err());
+}
@@ -0,0 +1,76 @@
+#![cfg(windows)]
+
+use std::path::{Path, PathBuf};
+
+use tempfile::TempDir;
+use win_acl_rs::{
+    fs::to_extended_path,
+    sd::{SecurityDescriptor, SecurityInformation},
+};
+
+/// Creates a file nested deep enough for its path to exceed `MAX_PATH`.
+fn create_deep_file(root: &Path) -> PathBuf {
+    let mut dir = root.to_path_buf();
+    while dir.as_os_str().len() < 300 {
+        dir.push("a_rather_long_directory_name_for_testing");
+    }
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("file.txt");
+    std::fs::write(&file, b"test").unwrap();
+    file
+}
+
+#[test]
+fn test_to_extended_path_local() {
+    assert_eq!(
+        to_extended_path(r"C:\data\file.txt").unwrap(),
+        Path::new(r"\\?\C:\data\file.txt")
+    );
+    assert_eq!(
+        to_extended_path(r"C:/data/./x/../file.txt").unwrap(),
+        Path::new(r"\\?\C:\data\file.txt")
+    );
+}
+
+#[test]
+fn test_to_extended_path_unc() {
+    assert_eq!(
+        to_extended_path(r"\\server\share\dir").unwrap(),
+        Path::new(r"\\?\UNC\server\share\dir")
+    );
+}
+
+#[test]
+fn test_to_extended_path_unchanged() {
+    for path in [r"\\?\C:\data", r"\\?\UNC\server\share", r"\\.\PhysicalDrive0"] {
+        assert_eq!(to_extended_path(path).unwrap(), Path::new(path));
+    }
+}
+
+#[test]
+fn test_to_extended_path_relative() {
+    let extended = to_extended_path("file.txt").unwrap();
+    let expected = std::env::current_dir().unwrap().join("file.txt");
+    assert_eq!(extended, to_extended_path(expected).unwrap());
+    assert!(extended.as_os_str().to_string_lossy().starts_with(r"\\?\"));
+}
+
+#[test]
+fn test_to_extended_path_empty() {
+    assert!(to_extended_path("").is_err());
+}
+
+#[test]
+fn test_sd_from_long_path() {
+    let root = TempDir::new().unwrap();
+    let file = create_deep_file(root.path());
+    assert!(file.as_os_str().len() > 260);
+
+    let sd = SecurityDescriptor::from_path(&file).unwrap();
+    assert!(sd.is_valid());
+    assert!(sd.owner_sid().is_some());
+
+    // the origin keeps the path as given, so the descriptor can be written back
+    assert_eq!(sd.origin().unwrap().name(), file.as_os_str());
+    sd.reapply(SecurityInformation::DACL).unwrap();
+}
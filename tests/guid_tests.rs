@@ -0,0 +1,18 @@
+#![cfg(windows)]
+
+use win_acl_rs::guid::Guid;
+
+#[test]
+fn test_guid_roundtrip() {
+    let guid = Guid::parse("0cce921d-69ae-11d9-bed3-505054503030").unwrap();
+    assert_eq!(guid, Guid(0x0CCE921D_69AE_11D9_BED3_505054503030));
+    assert_eq!(guid.to_string(), "{0CCE921D-69AE-11D9-BED3-505054503030}");
+    assert_eq!(guid.to_string().parse::<Guid>().unwrap(), guid);
+}
+
+#[test]
+fn test_guid_invalid() {
+    assert!(Guid::parse("{0CCE921D-69AE-11D9-BED3}").is_err());
+    assert!(Guid::parse("0CCE921D+69AE-11D9-BED3-505054503030").is_err());
+    assert!(Guid::parse("{0CCE921D-69AE-11D9-BED3-50505450303G}").is_err());
+}
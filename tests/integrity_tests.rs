@@ -0,0 +1,105 @@
+#![cfg(windows)]
+
+use std::str::FromStr;
+
+use tempfile::NamedTempFile;
+use win_acl_rs::{
+    acl::AceFlags,
+    integrity::{IntegrityLevel, MandatoryLabel, MandatoryPolicy, can_write_up},
+    sd::{SecurityDescriptor, SecurityDescriptorBuilder, SecurityInformation},
+    sid::Sid,
+};
+
+#[test]
+fn test_integrity_level_ordering() {
+    assert!(IntegrityLevel::LOW < IntegrityLevel::MEDIUM);
+    assert!(IntegrityLevel::MEDIUM < IntegrityLevel::MEDIUM_PLUS);
+    assert!(IntegrityLevel::HIGH < IntegrityLevel::SYSTEM);
+    assert_eq!(IntegrityLevel::HIGH.to_string(), "High");
+}
+
+#[test]
+fn test_integrity_level_sid() {
+    let sid = IntegrityLevel::HIGH.to_sid().unwrap();
+    assert_eq!(sid.to_string().unwrap(), "S-1-16-12288");
+    assert_eq!(IntegrityLevel::from_sid(&sid).unwrap(), IntegrityLevel::HIGH);
+
+    let admins = Sid::from_string("S-1-5-32-544").unwrap();
+    assert!(IntegrityLevel::from_sid(&admins).is_err());
+}
+
+#[test]
+fn test_label_policy() {
+    let label = MandatoryLabel::new(IntegrityLevel::HIGH, MandatoryPolicy::NO_WRITE_UP);
+    assert!(!can_write_up(IntegrityLevel::MEDIUM, &label));
+    assert!(can_write_up(IntegrityLevel::HIGH, &label));
+    assert!(label.can_read(IntegrityLevel::LOW));
+
+    let label = MandatoryLabel::new(
+        IntegrityLevel::MEDIUM,
+        MandatoryPolicy::NO_WRITE_UP | MandatoryPolicy::NO_READ_UP,
+    );
+    assert!(!label.can_read(IntegrityLevel::LOW));
+    assert!(label.can_execute(IntegrityLevel::LOW));
+}
+
+#[test]
+fn test_current_process_level() {
+    let level = IntegrityLevel::current_process().unwrap();
+    assert!(level >= IntegrityLevel::LOW);
+    assert_eq!(IntegrityLevel::of_process(std::process::id()).unwrap(), level);
+}
+
+#[test]
+fn test_integrity_label() {
+    let sd = SecurityDescriptor::from_str("O:BAG:BAD:(A;;FA;;;WD)S:(ML;;NWNR;;;LW)").unwrap();
+    let label = sd.integrity_label().unwrap().unwrap();
+    assert_eq!(label.level(), IntegrityLevel::LOW);
+    assert_eq!(
+        label.policy(),
+        MandatoryPolicy::NO_WRITE_UP | MandatoryPolicy::NO_READ_UP
+    );
+
+    let sd = SecurityDescriptor::from_str("O:BAG:BAD:(A;;FA;;;WD)").unwrap();
+    assert_eq!(sd.integrity_label().unwrap(), None);
+
+    // a new file has no explicit label
+    let path = NamedTempFile::new().unwrap().into_temp_path();
+    let sd = SecurityDescriptor::from_path(&path).unwrap();
+    assert_eq!(sd.integrity_label().unwrap(), None);
+}
+
+#[test]
+fn test_mandatory_label_ace() {
+    let label = MandatoryLabel::new(IntegrityLevel::LOW, MandatoryPolicy::NO_WRITE_UP);
+    let sacl = label
+        .to_sacl(AceFlags::OBJECT_INHERIT | AceFlags::CONTAINER_INHERIT)
+        .unwrap();
+    assert!(sacl.is_valid());
+    assert_eq!(MandatoryLabel::from_sacl(&sacl).unwrap(), Some(label));
+
+    let ace = sacl.iter().unwrap().next().unwrap();
+    assert_eq!(ace.mandatory_label().unwrap(), Some(label));
+    assert_eq!(ace.flags(), AceFlags::OBJECT_INHERIT | AceFlags::CONTAINER_INHERIT);
+
+    let sd = SecurityDescriptorBuilder::new().sacl(sacl).build().unwrap();
+    assert_eq!(
+        sd.as_sd_string_with(SecurityInformation::LABEL).unwrap(),
+        "S:(ML;OICI;NW;;;LW)"
+    );
+
+    // lowering the label of an owned file needs no privilege
+    let path = NamedTempFile::new().unwrap().into_temp_path();
+    let sd = SecurityDescriptorBuilder::new()
+        .sacl(label.to_sacl(AceFlags::empty()).unwrap())
+        .build()
+        .unwrap();
+    sd.apply_to_path(&path, SecurityInformation::LABEL).unwrap();
+    let sd = SecurityDescriptor::from_path(&path).unwrap();
+    assert_eq!(sd.integrity_label().unwrap(), Some(label));
+
+    let sd = SecurityDescriptor::from_str("D:(A;;FA;;;WD)").unwrap();
+    let dacl = sd.dacl().unwrap();
+    let ace = dacl.into_iter().next().unwrap();
+    assert_eq!(ace.mandatory_label().unwrap(), None);
+}
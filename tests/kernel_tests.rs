@@ -0,0 +1,45 @@
+#![cfg(windows)]
+
+use std::{
+    ffi::OsStr,
+    os::windows::{ffi::OsStrExt, io::AsRawHandle},
+    ptr::null,
+    str::FromStr,
+};
+
+use win_acl_rs::{
+    kernel::{KernelObject, KernelObjectKind},
+    mask::AccessMask,
+    sd::{SecurityDescriptor, SecurityInformation},
+};
+use windows_sys::Win32::{
+    Foundation::{CloseHandle, FALSE},
+    System::Threading::CreateEventW,
+};
+
+#[test]
+fn test_kernel_process_security() {
+    let process = KernelObject::open_process(std::process::id(), AccessMask::READ_CONTROL).unwrap();
+    assert_eq!(process.kind(), KernelObjectKind::Process);
+    assert!(!process.as_raw_handle().is_null());
+
+    let sd = process.security().unwrap();
+    assert!(sd.is_valid());
+    assert!(sd.owner_sid().is_some());
+}
+
+#[test]
+fn test_kernel_event_security() {
+    let name = r"Local\win-acl-rs-kernel-test-event";
+    let wide: Vec<u16> = OsStr::new(name).encode_wide().chain(Some(0)).collect();
+    let created = unsafe { CreateEventW(null(), FALSE, FALSE, wide.as_ptr()) };
+    assert!(!created.is_null());
+
+    let event = KernelObject::open_event(name, AccessMask::READ_CONTROL | AccessMask::WRITE_DAC).unwrap();
+    let sd = SecurityDescriptor::from_str("D:(A;;GA;;;SY)(A;;GA;;;OW)").unwrap();
+    event.apply(&sd, SecurityInformation::DACL).unwrap();
+    assert_eq!(event.security().unwrap().dacl().unwrap().ace_count(), 2);
+
+    assert!(KernelObject::open_mutex(r"Local\win-acl-rs-kernel-test-missing", AccessMask::READ_CONTROL).is_err());
+    unsafe { CloseHandle(created) };
+}
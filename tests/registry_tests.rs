@@ -0,0 +1,88 @@
+#![cfg(windows)]
+
+use win_acl_rs::{
+    SE_REGISTRY_KEY, SE_REGISTRY_WOW64_32KEY,
+    registry::{Hive, RegView, RegistryPath, read_sd_value},
+    sd::SecurityDescriptor,
+};
+
+#[test]
+fn test_parse_short_hive() {
+    let path: RegistryPath = r"HKLM\SOFTWARE\Foo".parse().unwrap();
+    assert_eq!(path.hive(), Hive::LocalMachine);
+    assert_eq!(path.subkey(), r"SOFTWARE\Foo");
+    assert_eq!(path.object_name(), r"MACHINE\SOFTWARE\Foo");
+    assert_eq!(path.object_type(), SE_REGISTRY_KEY);
+}
+
+#[test]
+fn test_parse_long_hive() {
+    let path = RegistryPath::parse(r"hkey_current_user\Software\Foo\").unwrap();
+    assert_eq!(path.hive(), Hive::CurrentUser);
+    assert_eq!(path.subkey(), r"Software\Foo");
+    assert_eq!(path.object_name(), r"CURRENT_USER\Software\Foo");
+    assert_eq!(path.to_string(), r"HKEY_CURRENT_USER\Software\Foo");
+}
+
+#[test]
+fn test_parse_hive_only() {
+    let path = RegistryPath::parse("HKU").unwrap();
+    assert_eq!(path.hive(), Hive::Users);
+    assert_eq!(path.subkey(), "");
+    assert_eq!(path.object_name(), "USERS");
+}
+
+#[test]
+fn test_parse_wow64_view() {
+    let path = RegistryPath::parse(r"MACHINE\SOFTWARE\Foo")
+        .unwrap()
+        .with_view(RegView::Wow6432);
+    assert_eq!(path.object_name(), r"MACHINE\SOFTWARE\Foo");
+    assert_eq!(path.object_type(), SE_REGISTRY_WOW64_32KEY);
+}
+
+#[test]
+fn test_parse_unknown_hive() {
+    assert!(RegistryPath::parse(r"HKXX\SOFTWARE").is_err());
+}
+
+#[test]
+fn test_read_sd_value_missing() {
+    let key = RegistryPath::parse(r"HKCU\Software\win-acl-rs-does-not-exist").unwrap();
+    assert!(read_sd_value(&key, "SD").is_err());
+}
+
+#[test]
+fn test_parse_remote() {
+    let path: RegistryPath = r"\\SERVER01\HKLM\SOFTWARE\Foo".parse().unwrap();
+    assert_eq!(path.machine(), Some("SERVER01"));
+    assert_eq!(path.hive(), Hive::LocalMachine);
+    assert_eq!(path.subkey(), r"SOFTWARE\Foo");
+    assert_eq!(path.object_name(), r"\\SERVER01\MACHINE\SOFTWARE\Foo");
+    assert_eq!(path.to_string(), r"\\SERVER01\HKEY_LOCAL_MACHINE\SOFTWARE\Foo");
+}
+
+#[test]
+fn test_on_machine() {
+    let local = RegistryPath::new(Hive::Users, ".DEFAULT");
+    assert_eq!(local.machine(), None);
+
+    let remote = local.on_machine(r"\\SERVER01");
+    assert_eq!(remote.machine(), Some("SERVER01"));
+    assert_eq!(remote.object_name(), r"\\SERVER01\USERS\.DEFAULT");
+}
+
+#[test]
+fn test_sd_from_registry_key() {
+    for view in [RegView::Default, RegView::Wow6432, RegView::Wow6464] {
+        let sd = SecurityDescriptor::from_registry_key(Hive::LocalMachine, "SOFTWARE", view).unwrap();
+        assert!(sd.is_valid());
+        assert_eq!(sd.origin().unwrap().object_type(), view.object_type());
+        assert_eq!(sd.origin().unwrap().name(), r"MACHINE\SOFTWARE");
+    }
+
+    assert!(
+        SecurityDescriptor::from_registry_key(Hive::CurrentUser, r"Software\win-acl-rs\missing", RegView::Default)
+            .is_err()
+    );
+}
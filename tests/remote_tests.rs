@@ -0,0 +1,26 @@
+#![cfg(windows)]
+
+use std::path::Path;
+
+use win_acl_rs::{
+    registry::{Hive, RegistryPath},
+    remote::RemoteHost,
+};
+
+#[test]
+fn test_remote_host_names() {
+    let host = RemoteHost::new(r"\\SERVER01").unwrap();
+    assert_eq!(host.name(), "SERVER01");
+    assert_eq!(host.unc_path(r"data\reports"), Path::new(r"\\SERVER01\data\reports"));
+    assert_eq!(host.object_name("Spooler"), r"\\SERVER01\Spooler");
+
+    let key = host.registry_path(RegistryPath::new(Hive::LocalMachine, "SOFTWARE"));
+    assert_eq!(key.object_name(), r"\\SERVER01\MACHINE\SOFTWARE");
+}
+
+#[test]
+fn test_remote_host_invalid() {
+    assert!(RemoteHost::new("").is_err());
+    assert!(RemoteHost::new(r"\\").is_err());
+    assert!(RemoteHost::new(r"SERVER01\share").is_err());
+}
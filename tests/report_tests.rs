@@ -0,0 +1,82 @@
+#![cfg(windows)]
+
+use std::str::FromStr;
+
+use win_acl_rs::{
+    report::{Report, ReportRow},
+    sd::SecurityDescriptor,
+};
+
+#[test]
+fn test_report_from_descriptor() {
+    let sd = SecurityDescriptor::from_str("O:BAD:(A;OICI;FA;;;SY)(D;;FW;;;WD)").unwrap();
+    let mut report = Report::new("Test");
+    report.add_descriptor(r"C:\data", &sd).unwrap();
+
+    let rows = report.rows();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].path, r"C:\data");
+    assert_eq!(rows[0].owner, r"BUILTIN\Administrators");
+    assert_eq!(rows[0].trustee, r"NT AUTHORITY\SYSTEM");
+    assert_eq!(rows[0].ace_type, "Allow");
+    assert_eq!(rows[0].rights, "0x001F01FF");
+    assert_eq!(rows[0].inheritance, "(OI)(CI)");
+    assert_eq!(rows[1].trustee, "Everyone");
+    assert_eq!(rows[1].ace_type, "Deny");
+    assert_eq!(rows[1].inheritance, "");
+}
+
+#[test]
+fn test_report_null_dacl() {
+    let sd = SecurityDescriptor::from_str("O:BA").unwrap();
+    let mut report = Report::new("Test");
+    report.add_descriptor("C:\\data", &sd).unwrap();
+    assert_eq!(report.rows().len(), 1);
+    assert!(report.rows()[0].finding.is_some());
+}
+
+#[test]
+fn test_report_csv() {
+    let mut report = Report::new("Test");
+    report.push(ReportRow {
+        path: r"C:\a,b".to_owned(),
+        trustee: "say \"hi\"".to_owned(),
+        finding: Some("bad".to_owned()),
+        ..Default::default()
+    });
+    assert_eq!(
+        report.to_csv(),
+        "Path,Owner,Trustee,Type,Rights,Inheritance,Finding\r\n\"C:\\a,b\",,\"say \"\"hi\"\"\",,,,bad\r\n"
+    );
+}
+
+#[test]
+fn test_report_csv_formulas() {
+    let mut report = Report::new("Test");
+    report.push(ReportRow {
+        path: "=HYPERLINK(\"x\")".to_owned(),
+        owner: "@owner".to_owned(),
+        trustee: "-1,2".to_owned(),
+        ace_type: "+".to_owned(),
+        ..Default::default()
+    });
+    assert_eq!(
+        report.to_csv().lines().nth(1).unwrap(),
+        "\"'=HYPERLINK(\"\"x\"\")\",'@owner,\"'-1,2\",'+,,,"
+    );
+}
+
+#[test]
+fn test_report_html() {
+    let mut report = Report::new("<Test>");
+    report.push(ReportRow {
+        path: "a&b".to_owned(),
+        finding: Some("bad".to_owned()),
+        ..Default::default()
+    });
+    let html = report.to_html();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<title>&lt;Test&gt;</title>"));
+    assert!(html.contains("<tr class=\"finding\"><td>a&amp;b</td>"));
+    assert!(!html.contains("<Test>"));
+}
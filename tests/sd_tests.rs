@@ -1,9 +1,22 @@
 #![cfg(windows)]
 
-use std::str::FromStr;
+use std::{fs::OpenOptions, os::windows::fs::OpenOptionsExt, str::FromStr};
 
 use tempfile::NamedTempFile;
-use win_acl_rs::{SE_PRINTER, elevated::is_admin, error::Result, sd::SecurityDescriptor};
+use win_acl_rs::{
+    SE_FILE_OBJECT, SE_PRINTER, SE_REGISTRY_KEY,
+    acl::{AceType, Acl},
+    elevated::{PrivilegeToken, SecurityDescriptorElevated, current_user_is_admin_member, is_admin, is_member_of},
+    error::Result,
+    mask::{AccessMask, FileAccess},
+    sd::{
+        InheritedAces, Modification, SdControlFlags, SecurityDescriptor, SecurityDescriptorBuilder, SecurityGuard,
+        SecurityInformation, apply_all, apply_set, diff, disable_inheritance, enable_inheritance, get_many,
+    },
+    sid::Sid,
+    wellknown::{WinBuiltinAdministratorsSid, WinWorldSid},
+};
+use windows_sys::Win32::Foundation::{ERROR_INVALID_PARAMETER, ERROR_SERVICE_DOES_NOT_EXIST};
 
 fn create_test_descriptor() -> Result<SecurityDescriptor> {
     let path = NamedTempFile::new().unwrap().into_temp_path();
@@ -16,6 +29,14 @@ fn test_is_admin() {
     assert!(is_admin().is_ok());
 }
 
+#[test]
+fn test_is_member_of() {
+    assert!(is_member_of(&Sid::from_well_known_sid(WinWorldSid).unwrap()).unwrap());
+    assert!(is_member_of(&Sid::current_user().unwrap()).unwrap());
+    assert!(!is_member_of(&Sid::from_string("S-1-5-21-1-2-3-1001").unwrap()).unwrap());
+    assert!(current_user_is_admin_member().is_ok());
+}
+
 #[test]
 fn test_sd_strings() {
     const TEST_SD_STRING: &str = "O:S-1-5-21-1402048822-409899687-2319524958-1001G:S-1-5-21-1402048822-409899687-2319524958-1001D:(A;ID;FA;;;SY)(A;ID;FA;;;BA)(A;ID;FA;;;S-1-5-21-1402048822-409899687-2319524958-1001)";
@@ -45,6 +66,75 @@ fn test_sd_from_handle() {
     assert!(sd.is_valid());
 }
 
+#[test]
+fn test_sd_from_raw_handle() {
+    let file = NamedTempFile::new().unwrap();
+    let sd = SecurityDescriptor::from_raw_handle(file.as_file(), SE_FILE_OBJECT).unwrap();
+
+    assert!(sd.is_valid());
+    assert!(sd.origin().is_none());
+    assert!(
+        sd.equivalent(&SecurityDescriptor::from_path(file.path()).unwrap())
+            .unwrap()
+    );
+}
+
+#[test]
+#[ignore] // would fail on CI
+fn test_sd_from_window_station_and_desktop() {
+    let station = SecurityDescriptor::from_window_station("WinSta0").unwrap();
+    assert!(station.is_valid());
+    assert!(station.owner_sid().is_some());
+
+    let desktop = SecurityDescriptor::from_desktop("Default").unwrap();
+    assert!(desktop.is_valid());
+    assert!(desktop.owner_sid().is_some());
+}
+
+#[test]
+fn test_sd_from_window_station_missing() {
+    assert!(SecurityDescriptor::from_window_station("NoSuchWinSta").is_err());
+    assert!(SecurityDescriptor::from_desktop("NoSuchDesktop").is_err());
+}
+
+#[test]
+fn test_sd_apply_to_raw_handle() {
+    let path = NamedTempFile::new().unwrap().into_temp_path();
+    let file = OpenOptions::new()
+        .access_mode((AccessMask::READ_CONTROL | AccessMask::WRITE_DAC).0)
+        .open(&path)
+        .unwrap();
+
+    let template = SecurityDescriptor::from_str("D:P(A;;FA;;;SY)(A;;FA;;;OW)").unwrap();
+    template
+        .apply_to_raw_handle(
+            &file,
+            SE_FILE_OBJECT,
+            SecurityInformation::DACL | SecurityInformation::PROTECTED_DACL,
+        )
+        .unwrap();
+
+    let sd = SecurityDescriptor::from_raw_handle(&file, SE_FILE_OBJECT).unwrap();
+    assert_eq!(sd.dacl().unwrap().ace_count(), 2);
+    assert!(sd.control().unwrap().contains(SdControlFlags::DACL_PROTECTED));
+}
+
+#[test]
+fn test_sd_from_path_with() {
+    let path = NamedTempFile::new().unwrap().into_temp_path();
+
+    let sd = SecurityDescriptor::from_path_with(&path, SecurityInformation::OWNER).unwrap();
+    assert!(sd.owner_sid().is_some());
+    assert!(sd.group_sid().is_none());
+    assert!(sd.dacl().is_none());
+
+    let sd = SecurityDescriptor::from_path_with(&path, SecurityInformation::DACL).unwrap();
+    assert!(sd.owner_sid().is_none());
+    assert!(sd.dacl().is_some());
+
+    assert!(SecurityDescriptor::from_path_with(&path, SecurityInformation::SACL).is_err());
+}
+
 #[test]
 fn test_sd_group_defaulted() {
     let sd = create_test_descriptor().unwrap();
@@ -104,3 +194,526 @@ fn test_sd_sacl_present() {
     let sacl_present = sd.sacl_present().unwrap();
     assert!(!sacl_present);
 }
+
+#[test]
+fn test_sd_origin_refresh_reapply() {
+    let path = NamedTempFile::new().unwrap().into_temp_path();
+    let mut sd = SecurityDescriptor::from_path(&path).unwrap();
+
+    let origin = sd.origin().unwrap();
+    assert_eq!(origin.name(), path.as_os_str());
+    assert_eq!(origin.object_type(), SE_FILE_OBJECT);
+
+    sd.reapply(SecurityInformation::DACL).unwrap();
+    sd.refresh().unwrap();
+    assert!(sd.is_valid());
+}
+
+#[test]
+fn test_sd_string_has_no_origin() {
+    let mut sd = SecurityDescriptor::from_str("D:(A;;FA;;;SY)").unwrap();
+    assert!(sd.origin().is_none());
+    assert!(sd.refresh().is_err());
+    assert!(sd.reapply(SecurityInformation::DACL).is_err());
+}
+
+#[test]
+fn test_sd_get_many() {
+    let files: Vec<_> = (0..5).map(|_| NamedTempFile::new().unwrap().into_temp_path()).collect();
+    let mut paths: Vec<_> = files.iter().map(|p| p.to_path_buf()).collect();
+    paths.insert(2, "C:\\this\\path\\does\\not\\exist".into());
+
+    let results = get_many(paths.clone(), SecurityInformation::DACL, 3);
+
+    assert_eq!(results.len(), paths.len());
+    for (i, (path, sd)) in results.iter().enumerate() {
+        assert_eq!(path, &paths[i]);
+        assert_eq!(sd.is_ok(), i != 2);
+    }
+}
+
+#[test]
+#[ignore] // would fail on CI
+fn test_sd_from_path_best_effort() {
+    let token = PrivilegeToken::new().try_elevate().unwrap();
+    let path = NamedTempFile::new().unwrap().into_temp_path();
+    let (sd, missing) = SecurityDescriptorElevated::from_path_best_effort(&token, &path).unwrap();
+    assert_eq!(missing, SecurityInformation::empty());
+    assert_eq!(sd.origin().unwrap().components(), SecurityInformation::all());
+
+    assert!(SecurityDescriptorElevated::from_path_best_effort(&token, "C:\\this\\path\\does\\not\\exist").is_err());
+}
+
+fn dacl_string(path: &std::path::Path) -> String {
+    SecurityDescriptor::from_path(path).unwrap().as_sd_string().unwrap()
+}
+
+#[test]
+fn test_sd_apply_set_rolls_back_on_failure() {
+    let files: Vec<_> = (0..2).map(|_| NamedTempFile::new().unwrap().into_temp_path()).collect();
+    let before: Vec<_> = files.iter().map(|p| dacl_string(p)).collect();
+
+    let sd = SecurityDescriptor::from_str("D:P(A;;FA;;;SY)(A;;FA;;;BA)(A;;FA;;;OW)").unwrap();
+    let missing = std::path::PathBuf::from("C:\\this\\path\\does\\not\\exist");
+    let changes = [
+        (files[0].to_path_buf(), &sd),
+        (files[1].to_path_buf(), &sd),
+        (missing, &sd),
+    ];
+
+    assert!(apply_set(changes, SecurityInformation::DACL).is_err());
+
+    let after: Vec<_> = files.iter().map(|p| dacl_string(p)).collect();
+    assert_eq!(before, after);
+}
+
+#[test]
+fn test_sd_apply_all_rolls_back_on_failure() {
+    let file = NamedTempFile::new().unwrap().into_temp_path();
+    let before = dacl_string(&file);
+
+    let sd = || SecurityDescriptor::from_str("D:P(A;;FA;;;SY)(A;;FA;;;OW)").unwrap();
+    let changes = [
+        (file.to_str().unwrap(), SE_FILE_OBJECT, sd()),
+        ("MACHINE\\SOFTWARE\\win-acl-rs\\does-not-exist", SE_REGISTRY_KEY, sd()),
+    ];
+
+    assert!(apply_all(&changes, SecurityInformation::DACL).is_err());
+    assert_eq!(dacl_string(&file), before);
+
+    let applied = apply_all(&changes[..1], SecurityInformation::DACL).unwrap();
+    assert_ne!(dacl_string(&file), before);
+    applied.rollback().unwrap();
+    assert_eq!(dacl_string(&file), before);
+}
+
+#[test]
+fn test_sd_apply_set_explicit_rollback() {
+    let file = NamedTempFile::new().unwrap().into_temp_path();
+    let before = dacl_string(&file);
+
+    let sd = SecurityDescriptor::from_str("D:P(A;;FA;;;SY)(A;;FA;;;BA)(A;;FA;;;OW)").unwrap();
+    let applied = apply_set([(&file, &sd)], SecurityInformation::DACL).unwrap();
+    assert_eq!(applied.len(), 1);
+    assert_eq!(applied.paths().next(), Some(file.as_ref()));
+    assert_ne!(dacl_string(&file), before);
+
+    applied.rollback().unwrap();
+    assert_eq!(dacl_string(&file), before);
+}
+
+#[test]
+fn test_sd_apply_refuses_missing_components() {
+    let file = NamedTempFile::new().unwrap().into_temp_path();
+    let before = SecurityDescriptor::from_path(&file).unwrap().as_sd_string().unwrap();
+
+    let owner_only = SecurityDescriptor::from_str("O:BA").unwrap();
+    let err = owner_only.apply_to_path(&file, SecurityInformation::DACL).unwrap_err();
+    assert_eq!(err.code, ERROR_INVALID_PARAMETER);
+    let err = owner_only.apply_to_path(&file, SecurityInformation::GROUP).unwrap_err();
+    assert_eq!(err.code, ERROR_INVALID_PARAMETER);
+    assert_eq!(
+        SecurityDescriptor::from_path(&file).unwrap().as_sd_string().unwrap(),
+        before
+    );
+
+    // an explicit NULL DACL is present and is written as requested
+    let null_dacl = SecurityDescriptor::from_str("D:NO_ACCESS_CONTROL").unwrap();
+    null_dacl.apply_to_path(&file, SecurityInformation::DACL).unwrap();
+}
+
+#[test]
+fn test_sd_apply_to_path() {
+    let file = NamedTempFile::new().unwrap().into_temp_path();
+    let sd = SecurityDescriptor::from_str("D:P(A;;FA;;;SY)(A;;FA;;;BA)(A;;FA;;;OW)").unwrap();
+    sd.apply_to_path(&file, SecurityInformation::DACL | SecurityInformation::PROTECTED_DACL)
+        .unwrap();
+
+    let applied = SecurityDescriptor::from_path(&file).unwrap();
+    assert_eq!(applied.dacl().unwrap().ace_count(), 3);
+
+    let by_name = SecurityDescriptor::from_str("D:P(A;;FA;;;SY)(A;;FA;;;OW)").unwrap();
+    by_name
+        .apply_to_handle_name(
+            file.to_str().unwrap(),
+            SE_FILE_OBJECT,
+            SecurityInformation::DACL | SecurityInformation::PROTECTED_DACL,
+        )
+        .unwrap();
+    assert_eq!(
+        SecurityDescriptor::from_path(&file)
+            .unwrap()
+            .dacl()
+            .unwrap()
+            .ace_count(),
+        2
+    );
+}
+
+#[test]
+fn test_sd_builder() {
+    let admins = Sid::from_well_known_sid(WinBuiltinAdministratorsSid).unwrap();
+    let mut dacl = Acl::new().unwrap();
+    dacl.allow(AccessMask::full(), &admins).unwrap();
+
+    let builder = SecurityDescriptorBuilder::new()
+        .owner(admins)
+        .dacl(dacl)
+        .protected_dacl(true);
+    assert_eq!(
+        builder.components(),
+        SecurityInformation::OWNER | SecurityInformation::DACL | SecurityInformation::PROTECTED_DACL
+    );
+
+    let sd = builder.build().unwrap();
+    assert!(sd.is_valid());
+    assert!(sd.group_sid().is_none());
+    assert_eq!(builder.to_sd_string().unwrap(), "O:BAD:P(A;;FA;;;BA)");
+
+    let owner_rights = Sid::from_string("S-1-3-4").unwrap();
+    let mut dacl = Acl::new().unwrap();
+    dacl.allow(AccessMask::full(), &owner_rights).unwrap();
+
+    let file = NamedTempFile::new().unwrap().into_temp_path();
+    SecurityDescriptorBuilder::new()
+        .dacl(dacl)
+        .protected_dacl(true)
+        .apply_to_path(&file)
+        .unwrap();
+
+    let applied = SecurityDescriptor::from_path(&file).unwrap().as_sd_string().unwrap();
+    // the DACL may also be flagged auto-inherited (`D:PAI`)
+    assert!(applied.contains("D:P"));
+    assert!(applied.ends_with("(A;;FA;;;OW)"));
+}
+
+#[test]
+fn test_sd_set_dacl() {
+    let mut sd = SecurityDescriptor::from_str("O:BAG:SYD:P(A;;FA;;;SY)").unwrap();
+    let mut dacl = Acl::new().unwrap();
+    dacl.allow(
+        AccessMask::full(),
+        &Sid::from_well_known_sid(WinBuiltinAdministratorsSid).unwrap(),
+    )
+    .unwrap();
+    sd.set_dacl(dacl).unwrap();
+
+    assert!(sd.is_valid());
+    assert_eq!(sd.as_sd_string().unwrap(), "O:BAG:SYD:P(A;;FA;;;BA)");
+}
+
+#[test]
+fn test_sd_set_sacl() {
+    let mut sd = SecurityDescriptorElevated::from_str("O:BAD:P(A;;FA;;;SY)S:(AU;FA;FA;;;WD)").unwrap();
+    sd.set_sacl(Acl::new().unwrap()).unwrap();
+
+    let sddl = sd.as_sd_string().unwrap();
+    assert!(sddl.starts_with("O:BAD:P(A;;FA;;;SY)"));
+    assert!(!sddl.contains("AU;"));
+}
+
+#[test]
+fn test_sd_control() {
+    let sd = SecurityDescriptor::from_str("O:BAD:PAI(A;;FA;;;SY)").unwrap();
+    let control = sd.control().unwrap();
+    assert!(control.contains(SdControlFlags::SELF_RELATIVE));
+    assert!(control.contains(SdControlFlags::DACL_PRESENT | SdControlFlags::DACL_PROTECTED));
+    assert!(control.contains(SdControlFlags::DACL_AUTO_INHERITED));
+    assert!(!control.contains(SdControlFlags::SACL_PRESENT));
+
+    let unprotected = SecurityDescriptor::from_str("O:BAD:(A;;FA;;;SY)").unwrap();
+    assert!(!unprotected.control().unwrap().contains(SdControlFlags::DACL_PROTECTED));
+}
+
+#[test]
+fn test_sd_disable_enable_inheritance() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let file = dir.path().join("file.txt");
+    std::fs::write(&file, b"test").unwrap();
+
+    let inherited_count = SecurityDescriptor::from_path(&file)
+        .unwrap()
+        .dacl()
+        .unwrap()
+        .ace_count();
+
+    disable_inheritance(&file, InheritedAces::Copy).unwrap();
+    let sd = SecurityDescriptor::from_path(&file).unwrap();
+    assert!(sd.control().unwrap().contains(SdControlFlags::DACL_PROTECTED));
+    assert_eq!(sd.dacl().unwrap().ace_count(), inherited_count);
+    assert!(!sd.as_sd_string().unwrap().contains("ID;"));
+
+    enable_inheritance(&file).unwrap();
+    let sd = SecurityDescriptor::from_path(&file).unwrap();
+    assert!(!sd.control().unwrap().contains(SdControlFlags::DACL_PROTECTED));
+
+    disable_inheritance(&file, InheritedAces::Remove).unwrap();
+    let sd = SecurityDescriptor::from_path(&file).unwrap();
+    assert!(sd.control().unwrap().contains(SdControlFlags::DACL_PROTECTED));
+    assert_eq!(sd.dacl().unwrap().ace_count(), 0);
+
+    enable_inheritance(&file).unwrap();
+}
+
+#[test]
+fn test_sd_bytes_roundtrip() {
+    let sd = SecurityDescriptor::from_str("O:BAG:SYD:P(A;OICI;FA;;;SY)(A;;FR;;;WD)").unwrap();
+    let bytes = sd.to_bytes();
+    let copy = SecurityDescriptor::from_bytes(&bytes).unwrap();
+    assert_eq!(copy.as_sd_string().unwrap(), sd.as_sd_string().unwrap());
+    assert_eq!(copy.to_bytes(), bytes);
+
+    // unaligned input
+    let mut shifted = vec![0u8];
+    shifted.extend_from_slice(&bytes);
+    assert!(SecurityDescriptor::from_bytes(&shifted[1..]).is_ok());
+}
+
+#[test]
+fn test_sd_from_invalid_bytes() {
+    let bytes = SecurityDescriptor::from_str("O:BAD:(A;;FA;;;SY)").unwrap().to_bytes();
+
+    assert!(SecurityDescriptor::from_bytes(&[]).is_err());
+    assert!(SecurityDescriptor::from_bytes(&bytes[..bytes.len() - 4]).is_err());
+
+    // owner offset pointing past the end of the buffer
+    let mut corrupted = bytes.clone();
+    corrupted[4..8].copy_from_slice(&(bytes.len() as u32 + 16).to_le_bytes());
+    assert!(SecurityDescriptor::from_bytes(&corrupted).is_err());
+}
+
+#[test]
+fn test_sd_try_clone() {
+    let mut sd = create_test_descriptor().unwrap();
+    let snapshot = sd.try_clone().unwrap();
+    assert_ne!(snapshot.as_ptr(), sd.as_ptr());
+    assert_eq!(snapshot.origin(), sd.origin());
+
+    sd.set_dacl(Acl::new().unwrap()).unwrap();
+    assert_eq!(sd.dacl().unwrap().ace_count(), 0);
+    assert!(snapshot.dacl().unwrap().ace_count() > 0);
+
+    drop(sd);
+    assert!(snapshot.is_valid());
+}
+
+#[test]
+fn test_sd_equivalent() {
+    let a = SecurityDescriptor::from_str("O:BAG:SYD:(A;;FA;;;SY)(A;;FR;;;WD)").unwrap();
+    let b = SecurityDescriptor::from_bytes(&a.to_bytes()).unwrap();
+    assert!(a.equivalent(&b).unwrap());
+    assert!(a.equivalent(&a.try_clone().unwrap()).unwrap());
+
+    let reordered = SecurityDescriptor::from_str("O:BAG:SYD:(A;;FR;;;WD)(A;;FA;;;SY)").unwrap();
+    assert!(!a.equivalent(&reordered).unwrap());
+
+    let other_owner = SecurityDescriptor::from_str("O:SYG:SYD:(A;;FA;;;SY)(A;;FR;;;WD)").unwrap();
+    assert!(!a.equivalent(&other_owner).unwrap());
+
+    let no_dacl = SecurityDescriptor::from_str("O:BAG:SY").unwrap();
+    assert!(!a.equivalent(&no_dacl).unwrap());
+}
+
+#[test]
+fn test_security_guard() {
+    let path = NamedTempFile::new().unwrap().into_temp_path();
+    let original = SecurityDescriptor::from_path(&path).unwrap();
+    let locked = SecurityDescriptor::from_str("D:P(A;;FA;;;OW)").unwrap();
+
+    // dropped: restored
+    {
+        let guard = SecurityGuard::new(&path, SecurityInformation::DACL).unwrap();
+        guard.apply(&locked).unwrap();
+        assert_eq!(
+            SecurityDescriptor::from_path(&path)
+                .unwrap()
+                .dacl()
+                .unwrap()
+                .ace_count(),
+            1
+        );
+    }
+    assert!(
+        SecurityDescriptor::from_path(&path)
+            .unwrap()
+            .equivalent(&original)
+            .unwrap()
+    );
+
+    // committed: kept
+    let guard = SecurityGuard::new(&path, SecurityInformation::DACL).unwrap();
+    guard.apply(&locked).unwrap();
+    guard.commit();
+    assert_eq!(
+        SecurityDescriptor::from_path(&path)
+            .unwrap()
+            .dacl()
+            .unwrap()
+            .ace_count(),
+        1
+    );
+
+    // restored explicitly
+    let guard = SecurityGuard::new(&path, SecurityInformation::DACL).unwrap();
+    guard
+        .apply(&SecurityDescriptor::from_str("D:P(A;;FR;;;OW)").unwrap())
+        .unwrap();
+    guard.restore().unwrap();
+    let sd = SecurityDescriptor::from_path(&path).unwrap();
+    assert_eq!(
+        sd.dacl().unwrap().try_iter().next().unwrap().unwrap().mask(),
+        FileAccess::FULL.0
+    );
+}
+
+#[test]
+fn test_sd_plan() {
+    let path = NamedTempFile::new().unwrap().into_temp_path();
+    let sd = SecurityDescriptor::from_path(&path).unwrap();
+    let before = sd.as_sd_string().unwrap();
+    let count = sd.dacl().unwrap().ace_count();
+
+    let users = Sid::from_string("S-1-5-32-545").unwrap();
+    let system = Sid::from_string("S-1-5-18").unwrap();
+    let planned = sd
+        .plan(&[
+            Modification::grant(users.clone(), FileAccess::READ),
+            Modification::deny(users, FileAccess::WRITE),
+            Modification::Group(system.clone()),
+        ])
+        .unwrap();
+
+    assert_eq!(planned.dacl().unwrap().ace_count(), count + 2);
+    // the deny goes before every allow, so it takes effect
+    let planned_dacl = planned.dacl().unwrap();
+    assert!(planned_dacl.is_canonical().unwrap());
+    assert_eq!(planned_dacl.get(0).unwrap().ace_type(), AceType::AccessDenied);
+    assert_eq!(planned.group_sid().unwrap().to_vec(), system.to_vec());
+    assert_eq!(planned.origin(), sd.origin());
+    // the object is untouched
+    assert_eq!(sd.as_sd_string().unwrap(), before);
+    assert_eq!(dacl_string(&path), before);
+}
+
+#[test]
+fn test_sd_diff() {
+    let a = SecurityDescriptor::from_str("O:BAG:SYD:(A;;FA;;;SY)(A;;FR;;;BU)(A;;FR;;;WD)").unwrap();
+    let b = SecurityDescriptor::from_str("O:SYG:SYD:(A;;FR;;;WD)(A;;FA;;;SY)(A;OICI;FA;;;BA)(A;;FW;;;BU)").unwrap();
+
+    assert!(diff(&a, &a).unwrap().is_empty());
+    let reordered = SecurityDescriptor::from_str("O:BAG:SYD:(A;;FR;;;WD)(A;;FR;;;BU)(A;;FA;;;SY)").unwrap();
+    assert!(diff(&a, &reordered).unwrap().is_empty());
+
+    let changes = diff(&a, &b).unwrap();
+    let (before, after) = changes.owner.clone().unwrap();
+    assert_eq!(before.unwrap().to_string().unwrap(), "S-1-5-32-544");
+    assert_eq!(after.unwrap().to_string().unwrap(), "S-1-5-18");
+    assert!(changes.group.is_none());
+
+    assert_eq!(changes.added.len(), 1);
+    assert_eq!(changes.added[0].trustee.to_string().unwrap(), "S-1-5-32-544");
+    assert!(changes.removed.is_empty());
+
+    assert_eq!(changes.modified.len(), 1);
+    let users = &changes.modified[0];
+    assert_eq!(users.trustee.to_string().unwrap(), "S-1-5-32-545");
+    assert_eq!(users.added_rights(), FileAccess::WRITE.0 & !FileAccess::READ.0);
+    assert_eq!(users.removed_rights(), FileAccess::READ.0 & !FileAccess::WRITE.0);
+}
+
+#[test]
+fn test_sd_diff_conditions() {
+    let a = SecurityDescriptor::from_str(r#"D:(XA;;FR;;;WD;(@User.Title == "PM"))"#).unwrap();
+    let b = SecurityDescriptor::from_str(r#"D:(XA;;FR;;;WD;(@User.Title == "Dev"))"#).unwrap();
+
+    let changes = diff(&a, &b).unwrap();
+    assert_eq!(changes.added.len(), 1);
+    assert_eq!(changes.removed.len(), 1);
+    assert!(changes.modified.is_empty());
+    assert_ne!(changes.added[0].application_data, changes.removed[0].application_data);
+    assert!(diff(&a, &a).unwrap().is_empty());
+}
+
+#[test]
+fn test_sd_diff_dacl_presence_and_protection() {
+    let null_dacl = SecurityDescriptor::from_str("D:NO_ACCESS_CONTROL").unwrap();
+    let empty_dacl = SecurityDescriptor::from_str("D:").unwrap();
+
+    let changes = diff(&null_dacl, &empty_dacl).unwrap();
+    assert!(!changes.is_empty());
+    assert_eq!(changes.dacl_present, Some((false, true)));
+    assert!(changes.added.is_empty() && changes.removed.is_empty());
+    assert_eq!(diff(&empty_dacl, &null_dacl).unwrap().dacl_present, Some((true, false)));
+
+    let inheriting = SecurityDescriptor::from_str("D:(A;;FA;;;SY)").unwrap();
+    let protected = SecurityDescriptor::from_str("D:P(A;;FA;;;SY)").unwrap();
+    let changes = diff(&inheriting, &protected).unwrap();
+    assert_eq!(changes.dacl_protected, Some((false, true)));
+    assert!(changes.dacl_present.is_none());
+    assert!(diff(&protected, &protected).unwrap().is_empty());
+}
+
+#[test]
+fn test_sd_as_sd_string_with() {
+    let sd = SecurityDescriptor::from_str("O:BAG:SYD:(A;;FA;;;SY)S:(ML;;NW;;;LW)").unwrap();
+
+    assert_eq!(
+        sd.as_sd_string_with(SecurityInformation::DACL).unwrap(),
+        "D:(A;;FA;;;SY)"
+    );
+    assert_eq!(
+        sd.as_sd_string_with(SecurityInformation::OWNER | SecurityInformation::GROUP)
+            .unwrap(),
+        "O:BAG:SY"
+    );
+    assert_eq!(
+        sd.as_sd_string_with(SecurityInformation::LABEL).unwrap(),
+        "S:(ML;;NW;;;LW)"
+    );
+    assert_eq!(sd.as_sd_string_with(SecurityInformation::empty()).unwrap(), "");
+}
+
+#[test]
+fn test_sd_content_hash() {
+    let hash = |sddl: &str| SecurityDescriptor::from_str(sddl).unwrap().content_hash().unwrap();
+
+    let a = hash("O:BAG:SYD:(D;;FW;;;WD)(A;;FA;;;SY)(A;;FR;;;BU)");
+    assert_eq!(a, hash("O:BAG:SYD:(D;;FW;;;WD)(A;;FA;;;SY)(A;;FR;;;BU)"));
+    // allow entries in another order grant the same access
+    assert_eq!(a, hash("O:BAG:SYD:(D;;FW;;;WD)(A;;FR;;;BU)(A;;FA;;;SY)"));
+    // a deny after the allows does not
+    assert_ne!(a, hash("O:BAG:SYD:(A;;FA;;;SY)(A;;FR;;;BU)(D;;FW;;;WD)"));
+    assert_ne!(a, hash("O:SYG:SYD:(D;;FW;;;WD)(A;;FA;;;SY)(A;;FR;;;BU)"));
+    assert_ne!(a, hash("O:BAG:SYD:(D;;FW;;;WD)(A;;FA;;;SY)(A;;FX;;;BU)"));
+    assert_ne!(hash("O:BAG:SY"), hash("O:BAG:SYD:"));
+
+    let sd = create_test_descriptor().unwrap();
+    let copy = SecurityDescriptor::from_bytes(&sd.to_bytes()).unwrap();
+    assert_eq!(sd.content_hash().unwrap(), copy.content_hash().unwrap());
+}
+
+#[test]
+fn test_sd_from_service() {
+    // the Windows Event Log service exists on every installation
+    let sd = SecurityDescriptor::from_service("EventLog").unwrap();
+    assert!(sd.is_valid());
+    assert!(sd.dacl().unwrap().ace_count() > 0);
+
+    let err = SecurityDescriptor::from_service("win-acl-rs-missing-service").unwrap_err();
+    assert_eq!(err.code, ERROR_SERVICE_DOES_NOT_EXIST);
+}
+
+#[test]
+fn test_dacl_borrows_descriptor() {
+    let sd = SecurityDescriptor::from_str("O:BAG:BAD:(A;;FA;;;SY)(A;;FR;;;BU)").unwrap();
+    let owned = {
+        let dacl = sd.dacl().unwrap();
+        assert_eq!(dacl.ace_count(), 2);
+        assert_eq!((&dacl).into_iter().count(), 2);
+        dacl.try_clone().unwrap()
+    };
+    drop(sd);
+
+    assert_eq!(owned.ace_count(), 2);
+    assert!(owned.is_valid());
+}
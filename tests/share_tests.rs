@@ -0,0 +1,28 @@
+#![cfg(windows)]
+
+use win_acl_rs::{remote::RemoteHost, share::Share};
+
+#[test]
+fn test_share_parse() {
+    let share = Share::parse(r"\\SERVER01\data").unwrap();
+    assert_eq!(share.server(), Some("SERVER01"));
+    assert_eq!(share.name(), "data");
+    assert_eq!(share.object_name(), r"\\SERVER01\data");
+    assert_eq!(share, Share::on(&RemoteHost::new("SERVER01").unwrap(), "data"));
+
+    let share = Share::parse("data").unwrap();
+    assert_eq!(share.server(), None);
+    assert_eq!(share.object_name(), "data");
+    assert_eq!(share.to_string(), "data");
+
+    assert!(Share::parse("").is_err());
+    assert!(Share::parse(r"\\SERVER01").is_err());
+    assert!(Share::parse(r"\\SERVER01\data\reports").is_err());
+}
+
+#[test]
+fn test_share_missing() {
+    let share = Share::local("win-acl-rs-missing-share");
+    assert!(share.info().is_err());
+    assert!(share.security().is_err());
+}
@@ -1,9 +1,25 @@
 #![cfg(windows)]
 
-use std::str::FromStr;
+use std::{
+    os::windows::io::{FromRawHandle, OwnedHandle},
+    ptr::null_mut,
+    str::FromStr,
+    time::Duration,
+};
 
-use win_acl_rs::{sd::SecurityDescriptor, sid::Sid};
-use windows_sys::Win32::Security::WinAnonymousSid;
+use win_acl_rs::{
+    remote::RemoteHost,
+    sd::SecurityDescriptor,
+    sid::{
+        IdentifierAuthority, Sid,
+        account::{LookupPolicy, SidNameUse, clear_name_cache, lookup_policy, set_lookup_policy},
+    },
+};
+use windows_sys::Win32::{
+    Foundation::ERROR_TIMEOUT,
+    Security::{TOKEN_QUERY, WinAnonymousSid},
+    System::Threading::{GetCurrentProcess, OpenProcessToken},
+};
 
 #[test]
 fn test_owner_sid_obtained_from_sd() {
@@ -70,3 +86,282 @@ fn test_lookup() {
     let lookup = sid.lookup_name().unwrap();
     assert_eq!(lookup.name, "SYSTEM");
 }
+
+#[test]
+fn test_sid_components() {
+    let sid = Sid::from_string("S-1-5-21-1402048822-409899687-2319524958-1001").unwrap();
+    assert_eq!(sid.revision(), 1);
+    assert_eq!(sid.identifier_authority(), IdentifierAuthority::NT);
+    assert_eq!(sid.sub_authorities(), &[21, 1402048822, 409899687, 2319524958, 1001]);
+    assert_eq!(sid.rid(), Some(1001));
+
+    let everyone = Sid::from_string("S-1-1-0").unwrap();
+    assert_eq!(everyone.identifier_authority(), IdentifierAuthority::WORLD);
+    assert_eq!(everyone.sub_authorities(), &[0]);
+
+    let authority = IdentifierAuthority(0x1234_5678_9abc);
+    assert_eq!(authority.to_bytes(), [0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc]);
+    assert_eq!(IdentifierAuthority::from_bytes(authority.to_bytes()), authority);
+}
+
+#[test]
+fn test_sid_new() {
+    let admins = Sid::new(IdentifierAuthority::NT, &[32, 544]).unwrap();
+    assert!(admins.is_valid());
+    assert_eq!(admins, Sid::from_string("S-1-5-32-544").unwrap());
+
+    let capability = Sid::new(IdentifierAuthority::APP_PACKAGE, &[3, 1]).unwrap();
+    assert_eq!(capability.to_string().unwrap(), "S-1-15-3-1");
+
+    assert!(Sid::new(IdentifierAuthority::NT, &[0; 16]).is_err());
+    assert!(Sid::new(IdentifierAuthority(1 << 48), &[0]).is_err());
+}
+
+#[test]
+fn test_sid_with_rid() {
+    let domain = Sid::from_string("S-1-5-21-1402048822-409899687-2319524958").unwrap();
+    let domain_users = domain.with_rid(513).unwrap();
+    assert!(domain_users.is_valid());
+    assert_eq!(domain_users.rid(), Some(513));
+    assert_eq!(
+        domain_users,
+        Sid::from_string("S-1-5-21-1402048822-409899687-2319524958-513").unwrap()
+    );
+
+    let full = Sid::new(IdentifierAuthority::NT, &[1; 15]).unwrap();
+    assert!(full.with_rid(1).is_err());
+}
+
+#[test]
+fn test_machine_and_account_domain() {
+    let machine = Sid::machine().unwrap();
+    assert!(machine.is_valid());
+    assert_eq!(machine.identifier_authority(), IdentifierAuthority::NT);
+    assert_eq!(
+        Sid::account_domain_of(&machine.with_rid(500).unwrap()).unwrap(),
+        machine
+    );
+
+    let user = Sid::from_string("S-1-5-21-1402048822-409899687-2319524958-1001").unwrap();
+    assert_eq!(
+        Sid::account_domain_of(&user).unwrap(),
+        Sid::from_string("S-1-5-21-1402048822-409899687-2319524958").unwrap()
+    );
+    assert!(Sid::account_domain_of(&Sid::from_string("S-1-1-0").unwrap()).is_err());
+}
+
+#[test]
+fn test_sid_ordering() {
+    let mut sids: Vec<Sid> = [
+        "S-1-5-32-544",
+        "S-1-5-21-1-2-3-500",
+        "S-1-1-0",
+        "S-1-5-21-1-2-3",
+        "S-1-5-18",
+        "S-1-16-12288",
+    ]
+    .iter()
+    .map(|s| Sid::from_string(s).unwrap())
+    .collect();
+    sids.sort();
+
+    let sorted: Vec<_> = sids.iter().map(|sid| sid.to_string().unwrap()).collect();
+    assert_eq!(
+        sorted,
+        [
+            "S-1-1-0",
+            "S-1-5-18",
+            "S-1-5-21-1-2-3",
+            "S-1-5-21-1-2-3-500",
+            "S-1-5-32-544",
+            "S-1-16-12288"
+        ]
+    );
+    assert!(Sid::from_string("S-1-5-32-544").unwrap() == Sid::from_string("S-1-5-32-544").unwrap());
+    assert!(Sid::from_string("S-1-5-9").unwrap() < Sid::from_string("S-1-5-10").unwrap());
+}
+
+#[test]
+fn test_sid_as_bytes() {
+    let sid = Sid::from_string("S-1-5-32-544").unwrap();
+    assert_eq!(sid.as_bytes(), sid.to_vec().as_slice());
+    assert_eq!(sid.as_bytes().len(), sid.len());
+    assert_eq!(Sid::from_bytes(sid.as_bytes()).unwrap(), sid);
+
+    let sd = SecurityDescriptor::from_str("O:BAG:SYD:").unwrap();
+    let owner = sd.owner_sid().unwrap();
+    assert_eq!(owner.as_bytes(), sid.as_bytes());
+}
+
+#[test]
+fn test_sid_inline_storage() {
+    let longest = Sid::new(IdentifierAuthority::NT, &[u32::MAX; 15]).unwrap();
+    assert_eq!(longest.as_bytes().len(), 68);
+    assert!(longest.is_valid());
+    assert_eq!(longest.clone(), longest);
+
+    assert!(Sid::from_bytes(&[0; 69]).is_err());
+    let everyone = Sid::from_bytes(Sid::from_string("S-1-1-0").unwrap().as_bytes()).unwrap();
+    assert_eq!(everyone.to_string().unwrap(), "S-1-1-0");
+    assert_ne!(everyone, longest);
+}
+
+#[test]
+fn test_current_user() {
+    let user = Sid::current_user().unwrap();
+    assert!(user.is_valid());
+    assert_eq!(user.identifier_authority(), IdentifierAuthority::NT);
+    assert_eq!(Sid::current_user().unwrap(), user);
+}
+
+#[test]
+fn test_from_token() {
+    let mut raw = null_mut();
+    assert_ne!(
+        unsafe { OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut raw) },
+        0
+    );
+    let token = unsafe { OwnedHandle::from_raw_handle(raw) };
+
+    let sids = Sid::from_token(&token).unwrap();
+    assert_eq!(sids.user, Sid::current_user().unwrap());
+    assert!(sids.primary_group.is_valid());
+    assert!(sids.owner.is_valid());
+}
+
+#[test]
+fn test_same_domain_and_equal_prefix() {
+    let domain = Sid::from_string("S-1-5-21-1402048822-409899687-2319524958").unwrap();
+    let user = domain.with_rid(1001).unwrap();
+    let other = Sid::from_string("S-1-5-21-1-2-3-1001").unwrap();
+
+    assert!(user.same_domain(&domain.with_rid(513).unwrap()).unwrap());
+    assert!(!user.same_domain(&other).unwrap());
+    assert!(user.same_domain(&Sid::from_string("S-1-1-0").unwrap()).is_err());
+
+    assert!(user.equal_prefix(&domain.with_rid(500).unwrap()));
+    assert!(!user.equal_prefix(&other));
+}
+
+#[test]
+fn test_app_container() {
+    let app = Sid::app_container("ContosoApp").unwrap();
+    assert!(app.is_valid());
+    assert_eq!(app.identifier_authority(), IdentifierAuthority::APP_PACKAGE);
+    assert_eq!(app.sub_authorities()[0], 2);
+    assert_eq!(app, Sid::app_container("ContosoApp").unwrap());
+    assert_ne!(app, Sid::app_container("OtherApp").unwrap());
+
+    assert!(Sid::app_container("").is_err());
+}
+
+#[test]
+fn test_service_sid() {
+    let sid = Sid::service("TrustedInstaller").unwrap();
+    assert_eq!(
+        sid.to_string().unwrap(),
+        "S-1-5-80-956008885-3418522649-1831038044-1853292631-2271478464"
+    );
+    assert_eq!(Sid::service("trustedinstaller").unwrap(), sid);
+    assert_eq!(Sid::from_account_name(r"NT SERVICE\TrustedInstaller").unwrap(), sid);
+
+    assert!(Sid::service("").is_err());
+}
+
+#[test]
+fn test_logon_sid() {
+    let sid = Sid::logon_sid().unwrap().unwrap();
+    assert!(sid.is_valid());
+    assert_eq!(sid.identifier_authority(), IdentifierAuthority::NT);
+    assert_eq!(sid.sub_authorities()[0], 5);
+    assert_eq!(sid.sub_authorities().len(), 3);
+}
+
+#[test]
+fn test_lookup_on_remote_host() {
+    let host = RemoteHost::new("localhost").unwrap();
+    let admins = Sid::from_string("S-1-5-32-544").unwrap();
+
+    let lookup = admins.lookup_name_on(&host).unwrap();
+    assert_eq!(lookup.name, admins.lookup_name().unwrap().name);
+    assert_eq!(
+        Sid::from_account_name_on(&host, format!(r"{}\{}", lookup.domain, lookup.name)).unwrap(),
+        admins
+    );
+}
+
+#[test]
+fn test_lookup_policy() {
+    let admins = Sid::from_string("S-1-5-32-544").unwrap();
+    let online = admins.lookup_name_with(LookupPolicy::Online).unwrap();
+
+    let offline = admins.lookup_name_with(LookupPolicy::Offline).unwrap();
+    assert_eq!(offline.name, online.name);
+    let user = Sid::machine().unwrap().with_rid(500).unwrap();
+    assert!(user.lookup_name_with(LookupPolicy::Offline).is_ok());
+    let foreign = Sid::from_string("S-1-5-21-1-2-3-1001").unwrap();
+    assert!(foreign.lookup_name_with(LookupPolicy::Offline).is_err());
+
+    let timeout = LookupPolicy::Timeout(Duration::from_secs(60));
+    assert_eq!(admins.lookup_name_with(timeout).unwrap().name, online.name);
+
+    set_lookup_policy(timeout);
+    assert_eq!(lookup_policy(), timeout);
+    assert_eq!(admins.lookup_name().unwrap().name, online.name);
+    set_lookup_policy(LookupPolicy::Online);
+}
+
+#[test]
+fn test_lookup_timeout() {
+    // resolved on the calling thread, never timing out
+    let users = Sid::from_string("S-1-5-32-545").unwrap();
+    assert!(users.lookup_name_with(LookupPolicy::Timeout(Duration::ZERO)).is_ok());
+
+    // gives up before the lookup starts, so the SID is not remembered as timed out
+    let foreign = Sid::from_string("S-1-5-21-1-2-3-1001").unwrap();
+    let err = foreign
+        .lookup_name_with(LookupPolicy::Timeout(Duration::ZERO))
+        .unwrap_err();
+    assert_eq!(err.code, ERROR_TIMEOUT);
+    let result = foreign.lookup_name_with(LookupPolicy::Timeout(Duration::from_secs(60)));
+    assert!(result.is_ok() || result.unwrap_err().code != ERROR_TIMEOUT);
+}
+
+#[test]
+fn test_lookup_sid_name_use() {
+    let admins = Sid::from_string("S-1-5-32-544").unwrap();
+    let lookup = admins.lookup_name().unwrap();
+    assert_eq!(lookup.sid_type, SidNameUse::Alias);
+    assert_eq!(lookup.sid, admins);
+
+    let lookup = Sid::from_string("S-1-1-0").unwrap().lookup_name().unwrap();
+    assert_eq!(lookup.sid_type, SidNameUse::WellKnownGroup);
+
+    let lookup = Sid::current_user().unwrap().lookup_name().unwrap();
+    assert_eq!(lookup.sid_type, SidNameUse::User);
+}
+
+#[test]
+fn test_debug_is_lookup_free() {
+    let sid = Sid::from_string("S-1-5-32-544").unwrap();
+    let plain = format!("{:?}", sid);
+    assert!(plain.contains("S-1-5-32-544"));
+    assert!(!plain.contains("account"));
+
+    let resolved = format!("{:?}", sid.debug_resolved());
+    assert!(resolved.contains("account"));
+    assert!(resolved.contains("Administrators"));
+}
+
+#[test]
+fn test_display_with_names() {
+    let sid = Sid::from_string("S-1-5-32-544").unwrap();
+    let lookup = sid.lookup_name().unwrap();
+    let expected = format!(r"{}\{} (S-1-5-32-544)", lookup.domain, lookup.name);
+    assert_eq!(sid.display_with_names().to_string(), expected);
+    assert_eq!(sid.display_with_names().to_string(), expected);
+
+    clear_name_cache();
+    let unknown = Sid::from_string("S-1-5-21-1-2-3-1001").unwrap();
+    assert_eq!(unknown.display_with_names().to_string(), "S-1-5-21-1-2-3-1001");
+}
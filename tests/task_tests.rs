@@ -0,0 +1,29 @@
+#![cfg(windows)]
+
+use win_acl_rs::{registry::Hive, task::ScheduledTask};
+
+#[test]
+fn test_task_name_normalized() {
+    let task = ScheduledTask::new(r"Microsoft\Windows\Defrag\ScheduledDefrag\");
+    assert_eq!(task.name(), r"\Microsoft\Windows\Defrag\ScheduledDefrag");
+    assert_eq!(task, ScheduledTask::new(r"\Microsoft\Windows\Defrag\ScheduledDefrag"));
+}
+
+#[test]
+fn test_task_paths() {
+    let task = ScheduledTask::new(r"\MyFolder\MyTask");
+    assert!(task.file_path().ends_with(r"System32\Tasks\MyFolder\MyTask"));
+
+    let key = task.tree_key();
+    assert_eq!(key.hive(), Hive::LocalMachine);
+    assert_eq!(
+        key.subkey(),
+        r"SOFTWARE\Microsoft\Windows NT\CurrentVersion\Schedule\TaskCache\Tree\MyFolder\MyTask"
+    );
+}
+
+#[test]
+fn test_missing_task_security() {
+    let task = ScheduledTask::new(r"\win-acl-rs\does-not-exist");
+    assert!(task.security().is_err());
+}
@@ -0,0 +1,67 @@
+#![cfg(windows)]
+
+use win_acl_rs::{
+    sid::Sid,
+    wellknown::{
+        DomainGroup, WinBuiltinAdministratorsSid, WinLocalSystemSid, WinWorldSid, domain_group_of, friendly_name,
+        from_sddl_alias, local_group_members, sddl_alias_for,
+    },
+};
+
+#[test]
+fn test_from_sddl_alias() {
+    let sid = from_sddl_alias("BA").unwrap();
+    assert_eq!(sid, Sid::from_well_known_sid(WinBuiltinAdministratorsSid).unwrap());
+
+    let sid = from_sddl_alias("wd").unwrap();
+    assert_eq!(sid, Sid::from_well_known_sid(WinWorldSid).unwrap());
+
+    assert!(from_sddl_alias("XX").is_err());
+}
+
+#[test]
+fn test_sddl_alias_for() {
+    let sid = Sid::from_well_known_sid(WinLocalSystemSid).unwrap();
+    assert_eq!(sddl_alias_for(&sid), Some("SY"));
+
+    let sid = Sid::from_string("S-1-5-21-1402048822-409899687-2319524958-1001").unwrap();
+    assert_eq!(sddl_alias_for(&sid), None);
+}
+
+#[test]
+fn test_domain_group_of() {
+    const DOMAIN_SID: &str = "S-1-5-21-1402048822-409899687-2319524958";
+    let domain = Sid::from_string(DOMAIN_SID).unwrap();
+
+    let admins = domain_group_of(DomainGroup::Admins, &domain).unwrap();
+    assert_eq!(admins.to_string().unwrap(), format!("{}-512", DOMAIN_SID));
+
+    let users = domain_group_of(DomainGroup::Users, &domain).unwrap();
+    assert_eq!(
+        users.to_string().unwrap(),
+        format!("{}-{}", DOMAIN_SID, DomainGroup::Users.rid())
+    );
+}
+
+#[test]
+fn test_friendly_name() {
+    let sid = Sid::from_well_known_sid(WinLocalSystemSid).unwrap();
+    assert_eq!(friendly_name(&sid), Some(r"NT AUTHORITY\SYSTEM"));
+
+    let sid = Sid::from_well_known_sid(WinWorldSid).unwrap();
+    assert_eq!(friendly_name(&sid), Some("Everyone"));
+
+    let sid = Sid::from_string("S-1-5-21-1402048822-409899687-2319524958-1001").unwrap();
+    assert_eq!(friendly_name(&sid), None);
+}
+
+#[test]
+fn test_local_group_members() {
+    let users = Sid::from_string("S-1-5-32-545").unwrap().lookup_name().unwrap();
+    let members = local_group_members(&users.name).unwrap();
+    let authenticated_users = Sid::from_string("S-1-5-11").unwrap();
+    assert!(members.iter().any(|member| member.sid == authenticated_users));
+    assert!(members.iter().all(|member| member.sid.is_valid()));
+
+    assert!(local_group_members("No Such Group 3586").is_err());
+}